@@ -5,7 +5,7 @@ use std::thread;
 use std::sync::mpsc::channel;
 
 use cdrs::connection_manager::ConnectionManager;
-use cdrs::transport::Transport;
+use cdrs::transport::TransportTcp;
 use cdrs::authenticators::PasswordAuthenticator;
 use cdrs::compression::Compression;
 use cdrs::client::QueryBuilder;
@@ -19,7 +19,7 @@ fn main() {
     let config = r2d2::Config::builder()
         .pool_size(15)
         .build();
-    let transport = Transport::new(ADDR).unwrap();
+    let transport = TransportTcp::new(ADDR).unwrap();
     let authenticator = PasswordAuthenticator::new(USER, PASS);
     let manager = ConnectionManager::new(transport, authenticator, Compression::None);
 
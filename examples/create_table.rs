@@ -4,7 +4,7 @@ use cdrs::client::{CDRS, QueryBuilder};
 use cdrs::authenticators::PasswordAuthenticator;
 use cdrs::compression::Compression;
 use cdrs::consistency::Consistency;
-use cdrs::transport::Transport;
+use cdrs::transport::TransportTcp;
 
 // default credentials
 const USER: &'static str = "cassandra";
@@ -13,7 +13,7 @@ const ADDR: &'static str = "127.0.0.1:9042";
 
 fn main() {
     let authenticator = PasswordAuthenticator::new(USER, PASS);
-    let tcp_transport = Transport::new(ADDR).unwrap();
+    let tcp_transport = TransportTcp::new(ADDR).unwrap();
     let client = CDRS::new(tcp_transport, authenticator);
     let mut session = client.start(Compression::None).unwrap();
 
@@ -0,0 +1,403 @@
+//! The module contains asynchronous client built on top of futures and tokio.
+//! It's available with `async` feature.
+//!
+//! `AsyncSession` is a cheap to clone handle of a single connection. Requests made
+//! via any of handles are multiplexed over the connection using stream ids, so
+//! many requests could be in flight at the same time. The connection itself is
+//! driven by a task spawned on a reactor which the session was connected with.
+//!
+//! ```no_run
+//! extern crate cdrs;
+//! extern crate futures;
+//! extern crate tokio_core;
+//!
+//! use futures::Future;
+//! use tokio_core::reactor::Core;
+//! use cdrs::async_session::AsyncSession;
+//! use cdrs::authenticators::PasswordAuthenticator;
+//! use cdrs::client::QueryBuilder;
+//! use cdrs::compression::Compression;
+//!
+//! # fn main() {
+//! let mut core = Core::new().unwrap();
+//! let addr = "127.0.0.1:9042".parse().unwrap();
+//! let authenticator = PasswordAuthenticator::new("user", "pass");
+//! let query = QueryBuilder::new("SELECT * FROM system.local").finalize();
+//!
+//! let rows = AsyncSession::connect(&addr, &authenticator, Compression::None, &core.handle())
+//!     .and_then(|session| session.query(query, false, false))
+//!     .map(|frame| frame.get_body().into_rows().unwrap_or(vec![]));
+//! let rows = core.run(rows).unwrap();
+//! # }
+//! ```
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use futures::{future, Async, AsyncSink, Future, Poll, Sink, Stream};
+use futures::sync::{mpsc, oneshot};
+use tokio_codec::{Decoder, Framed};
+use tokio_core::net::TcpStream;
+use tokio_core::reactor::{Handle, Timeout};
+
+use IntoBytes;
+use authenticators::Authenticator;
+use client::{Query, QueryBatch, request_flags};
+use compression::Compression;
+use error;
+use frame::{Frame, Opcode};
+use frame::codec::FrameCodec;
+use frame::frame_event::EVENT_STREAM_ID;
+use frame::frame_query::ParamsReqQuery;
+use frame::parser::convert_frame_into_result;
+use types::{CBytes, CBytesShort};
+use types::rows::Row;
+
+/// Future which is returned by asynchronous client.
+pub type CDRSFuture<T> = Box<Future<Item = T, Error = error::Error>>;
+
+/// Maximum stream id which could be used by a client. Negative ids are reserved
+/// for server events, and `0` is used by connection establishing requests.
+const MAX_STREAM_ID: u64 = 0x7FFF;
+
+type FramedConnection = Framed<TcpStream, FrameCodec>;
+
+type PendingRequest = (Frame, oneshot::Sender<error::Result<Frame>>);
+
+/// Asynchronous session. Its methods return futures which are resolved
+/// when a response is received.
+#[derive(Clone)]
+pub struct AsyncSession {
+    requests: mpsc::UnboundedSender<PendingRequest>,
+    handle: Handle,
+    request_timeout: Option<Duration>
+}
+
+impl AsyncSession {
+    /// Connects to a server, sends `STARTUP` request and authenticates if the server
+    /// requires that. The connection is driven by a task spawned on `handle`.
+    pub fn connect<T: Authenticator>(addr: &SocketAddr,
+        authenticator: &T,
+        compression: Compression,
+        handle: &Handle) -> CDRSFuture<AsyncSession> {
+        let authenticator_name = authenticator.get_cassandra_name().to_string();
+        let auth_token = authenticator.get_auth_token();
+        let handle = handle.clone();
+
+        let session = TcpStream::connect(addr, &handle)
+            .map_err(error::Error::from)
+            .and_then(move |socket| {
+                let connection = FrameCodec::new(compression).framed(socket);
+                return handshake_request(connection, Frame::new_req_startup(compression.into_string()));
+            })
+            .and_then(move |(response, connection)| -> CDRSFuture<FramedConnection> {
+                if response.opcode == Opcode::Ready {
+                    return Box::new(future::ok(connection));
+                }
+                if response.opcode != Opcode::Authenticate {
+                    return Box::new(future::err(error::Error::General(
+                        format!("Unexpected response to STARTUP request {:?}", response.opcode))));
+                }
+
+                let authenticator = response.get_body().get_authenticator().unwrap_or(String::new());
+                if authenticator != authenticator_name {
+                    let io_err = io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Unsupported type of authenticator. {:?} got, but {} is supported.",
+                            authenticator,
+                            authenticator_name));
+                    return Box::new(future::err(error::Error::Io(io_err)));
+                }
+
+                let auth_response = Frame::new_req_auth_response(auth_token.into_cbytes());
+                return Box::new(handshake_request(connection, auth_response)
+                    .map(|(_, connection)| connection));
+            })
+            .map(move |connection| {
+                let (sender, receiver) = mpsc::unbounded();
+                handle.spawn(Connection::new(connection, receiver));
+
+                return AsyncSession {
+                    requests: sender,
+                    handle: handle,
+                    request_timeout: None
+                };
+            });
+
+        return Box::new(session);
+    }
+
+    /// The method sets default timeout of requests made within the session.
+    /// If a response is not received in time `error::Error::Timeout` is returned.
+    /// `None` means that the session will wait for responses indefinitely.
+    pub fn request_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.request_timeout = timeout;
+        return self;
+    }
+
+    /// The method makes a request to DB Server to prepare provided query.
+    pub fn prepare(&self, query: String, with_tracing: bool, with_warnings: bool) -> CDRSFuture<Frame> {
+        let prepare_frame = Frame::new_req_prepare(query, request_flags(with_tracing, with_warnings));
+        return self.send_frame(prepare_frame, self.request_timeout);
+    }
+
+    /// The method makes a request to DB Server to execute a query with provided id
+    /// using provided query parameters. `id` is an ID of a query which Server
+    /// returns back to a driver as a response to `prepare` request.
+    pub fn execute(&self,
+        id: CBytesShort,
+        query_parameters: ParamsReqQuery,
+        with_tracing: bool,
+        with_warnings: bool) -> CDRSFuture<Frame> {
+        let flags = request_flags(with_tracing, with_warnings);
+        let execute_frame = Frame::new_req_execute(id, query_parameters, flags);
+        return self.send_frame(execute_frame, self.request_timeout);
+    }
+
+    /// The method makes a request to DB Server to execute a batch of queries.
+    /// The batch could be built with `BatchQueryBuilder`.
+    pub fn batch(&self, batch: QueryBatch, with_tracing: bool, with_warnings: bool) -> CDRSFuture<Frame> {
+        let batch_frame = batch.into_frame(with_tracing, with_warnings);
+        return self.send_frame(batch_frame, self.request_timeout);
+    }
+
+    /// The method makes a request to DB Server to execute a query provided in `query` argument.
+    pub fn query(&self, query: Query, with_tracing: bool, with_warnings: bool) -> CDRSFuture<Frame> {
+        let timeout = query.get_timeout().or(self.request_timeout);
+        let query_frame = query.into_frame(with_tracing, with_warnings);
+        return self.send_frame(query_frame, timeout);
+    }
+
+    /// The method fetches a single page of query results. Apart of rows it returns
+    /// paging state of the next page, or `None` if the page is the last one.
+    /// Page size and paging state of the page which should be fetched are set
+    /// via `QueryBuilder`.
+    pub fn query_page(&self, query: Query) -> CDRSFuture<(Vec<Row>, Option<CBytes>)> {
+        return Box::new(self.query(query, false, false).map(into_page));
+    }
+
+    /// Returns a stream of query result pages. Next page is requested only when
+    /// the previous one has been consumed. Page size is set via `QueryBuilder`.
+    pub fn query_pages(&self, query: Query) -> QueryPages {
+        return QueryPages {
+            session: self.clone(),
+            query: query,
+            current: None,
+            done: false
+        };
+    }
+
+    fn send_frame(&self, frame: Frame, timeout: Option<Duration>) -> CDRSFuture<Frame> {
+        let (sender, receiver) = oneshot::channel();
+        if self.requests.unbounded_send((frame, sender)).is_err() {
+            return Box::new(future::err(connection_closed()));
+        }
+
+        let response = receiver.then(|result| match result {
+            Ok(response) => response.and_then(convert_frame_into_result),
+            Err(_) => Err(connection_closed())
+        });
+
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => return Box::new(response)
+        };
+        let timer = match Timeout::new(timeout, &self.handle) {
+            Ok(timer) => timer,
+            Err(err) => return Box::new(future::err(error::Error::Io(err)))
+        };
+        let timer = timer
+            .map_err(error::Error::from)
+            .and_then(move |_| -> error::Result<Frame> { Err(error::Error::Timeout(timeout)) });
+
+        return Box::new(response.select(timer)
+            .map(|(response, _)| response)
+            .map_err(|(err, _)| err));
+    }
+}
+
+/// Stream of query result pages returned by `AsyncSession::query_pages`.
+pub struct QueryPages {
+    session: AsyncSession,
+    query: Query,
+    current: Option<CDRSFuture<(Vec<Row>, Option<CBytes>)>>,
+    done: bool
+}
+
+impl Stream for QueryPages {
+    type Item = Vec<Row>;
+    type Error = error::Error;
+
+    fn poll(&mut self) -> Poll<Option<Vec<Row>>, error::Error> {
+        if self.done {
+            return Ok(Async::Ready(None));
+        }
+
+        if self.current.is_none() {
+            self.current = Some(self.session.query_page(self.query.clone()));
+        }
+
+        let page = match self.current.as_mut().unwrap().poll() {
+            Ok(Async::Ready(page)) => page,
+            Ok(Async::NotReady) => return Ok(Async::NotReady),
+            Err(err) => {
+                self.done = true;
+                return Err(err);
+            }
+        };
+        self.current = None;
+
+        let (rows, paging_state) = page;
+        match paging_state {
+            Some(paging_state) => self.query.set_paging_state(Some(paging_state)),
+            None => self.done = true
+        }
+
+        return Ok(Async::Ready(Some(rows)));
+    }
+}
+
+fn into_page(frame: Frame) -> (Vec<Row>, Option<CBytes>) {
+    let body = frame.get_body();
+    let paging_state = body.as_cols().and_then(|rows| rows.metadata.paging_state.clone());
+    let rows = body.into_rows().unwrap_or(vec![]);
+
+    return (rows, paging_state);
+}
+
+/// Sends a request of connection establishing phase and waits for a response.
+fn handshake_request(connection: FramedConnection, frame: Frame) -> CDRSFuture<(Frame, FramedConnection)> {
+    let response = connection.send(frame)
+        .and_then(|connection| connection.into_future().map_err(|(err, _)| err))
+        .and_then(|(response, connection)| match response {
+            Some(response) => convert_frame_into_result(response).map(|response| (response, connection)),
+            None => Err(connection_closed())
+        });
+
+    return Box::new(response);
+}
+
+fn connection_closed() -> error::Error {
+    return error::Error::Io(io::Error::new(io::ErrorKind::ConnectionAborted, "Connection is closed"));
+}
+
+/// Task which writes requests into a connection and dispatches responses
+/// to requests they belong to.
+struct Connection {
+    connection: FramedConnection,
+    requests: mpsc::UnboundedReceiver<PendingRequest>,
+    pending: HashMap<u64, oneshot::Sender<error::Result<Frame>>>,
+    // a request which couldn't be written because of a full write buffer
+    blocked: Option<PendingRequest>,
+    next_stream: u64,
+    requests_done: bool
+}
+
+impl Connection {
+    fn new(connection: FramedConnection, requests: mpsc::UnboundedReceiver<PendingRequest>) -> Connection {
+        return Connection {
+            connection: connection,
+            requests: requests,
+            pending: HashMap::new(),
+            blocked: None,
+            next_stream: 1,
+            requests_done: false
+        };
+    }
+
+    fn next_stream_id(&mut self) -> u64 {
+        loop {
+            let stream = self.next_stream;
+            self.next_stream = if stream >= MAX_STREAM_ID { 1 } else { stream + 1 };
+            if !self.pending.contains_key(&stream) {
+                return stream;
+            }
+        }
+    }
+
+    fn read_responses(&mut self) -> Poll<(), error::Error> {
+        loop {
+            let response = match try_ready!(self.connection.poll()) {
+                Some(response) => response,
+                None => return Err(connection_closed())
+            };
+
+            if response.stream == EVENT_STREAM_ID {
+                debug!("Ignoring server event {:?}", response.opcode);
+                continue;
+            }
+
+            match self.pending.remove(&response.stream) {
+                // the receiver may be gone because of a timeout
+                Some(sender) => {
+                    let _ = sender.send(Ok(response));
+                },
+                None => debug!("Discarding a response with unknown stream id {}", response.stream)
+            }
+        }
+    }
+
+    fn write_requests(&mut self) -> Poll<(), error::Error> {
+        while self.pending.len() < MAX_STREAM_ID as usize {
+            let (mut frame, sender) = match self.blocked.take() {
+                Some(request) => request,
+                None => match self.requests.poll() {
+                    Ok(Async::Ready(Some(request))) => request,
+                    Ok(Async::NotReady) => break,
+                    Ok(Async::Ready(None)) | Err(_) => {
+                        self.requests_done = true;
+                        break;
+                    }
+                }
+            };
+
+            if sender.is_canceled() {
+                continue;
+            }
+
+            frame.stream = self.next_stream_id();
+            let stream = frame.stream;
+            match try!(self.connection.start_send(frame)) {
+                AsyncSink::Ready => {
+                    self.pending.insert(stream, sender);
+                },
+                AsyncSink::NotReady(frame) => {
+                    self.blocked = Some((frame, sender));
+                    break;
+                }
+            }
+        }
+
+        return self.connection.poll_complete();
+    }
+
+    fn fail_pending(&mut self, err: &error::Error) {
+        for (_, sender) in self.pending.drain() {
+            let io_err = io::Error::new(io::ErrorKind::ConnectionAborted, err.to_string());
+            let _ = sender.send(Err(error::Error::Io(io_err)));
+        }
+    }
+}
+
+impl Future for Connection {
+    type Item = ();
+    type Error = ();
+
+    fn poll(&mut self) -> Poll<(), ()> {
+        // responses are read first as they free stream ids for new requests
+        let result = self.read_responses().and_then(|_| self.write_requests());
+
+        if let Err(err) = result {
+            error!("Connection is closed because of an error: {}", err);
+            self.fail_pending(&err);
+            return Ok(Async::Ready(()));
+        }
+
+        if self.requests_done && self.pending.is_empty() {
+            return Ok(Async::Ready(()));
+        }
+
+        return Ok(Async::NotReady);
+    }
+}
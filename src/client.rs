@@ -2,25 +2,38 @@
 use std::net;
 use std::io;
 use std::io::Write;
+use std::cmp;
 use std::collections::HashMap;
 use std::default::Default;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, TryLockError, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 use consistency::Consistency;
 use frame::{Frame, Opcode, Flag};
 use frame::frame_response::ResponseBody;
+use frame::frame_result::{ResResultBody, ResultKind};
+use frame::frame_event::ServerEvent;
+use frame::frame_register::SimpleServerEvent;
 use IntoBytes;
-use frame::parser::parse_frame;
+use frame::parser::{parse_frame, parse_raw_frame, convert_frame_into_result};
 use types::*;
 use types::value::*;
+use types::rows::Row;
 
 use frame::frame_query::*;
+use frame::frame_batch::{BodyReqBatch, BatchType, BatchQuery, BatchQuerySubject};
 use compression::Compression;
 use authenticators::Authenticator;
+use query_trace::QueryTrace;
+use execution_info::ExecutionInfo;
+use schema_change::SchemaChangeOutcome;
+use lwt::LwtResult;
+use frame::frame_error::{AdditionalErrorInfo, WriteType};
+use node_state::{NodeState, NodeStates};
 use error;
-#[cfg(not(feature = "ssl"))]
-use transport::Transport;
-#[cfg(feature = "ssl")]
-use transport_ssl::Transport;
+use transport::CDRSTransport;
 
 /// instead of writing functions which resemble
 /// ```
@@ -45,7 +58,7 @@ macro_rules! builder_opt_field {
 
 /// Structure that represents CQL query and parameters which will be applied during
 /// its execution
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Query {
     query: String,
     // query parameters
@@ -55,7 +68,56 @@ pub struct Query {
     page_size: Option<i32>,
     paging_state: Option<CBytes>,
     serial_consistency: Option<Consistency>,
-    timestamp: Option<i64>
+    timestamp: Option<i64>,
+    custom_payload: Option<CBytesMap>,
+    timeout: Option<Duration>,
+    idempotent: Option<bool>,
+    routing_key: Option<Vec<u8>>
+}
+
+impl Query {
+    /// Returns query's timeout if it was set.
+    pub fn get_timeout(&self) -> Option<Duration> {
+        return self.timeout;
+    }
+
+    /// Returns query's consistency, `ONE` if it wasn't set.
+    pub fn get_consistency(&self) -> Consistency {
+        return self.consistency.clone().unwrap_or(Consistency::One);
+    }
+
+    /// Indicates if the query was marked idempotent, i.e. it's safe to execute it
+    /// more than once. Queries aren't idempotent unless marked so.
+    pub fn is_idempotent(&self) -> bool {
+        return self.idempotent.unwrap_or(false);
+    }
+
+    /// Returns query's routing key if it was set.
+    pub fn get_routing_key(&self) -> Option<&[u8]> {
+        return self.routing_key.as_ref().map(|routing_key| routing_key.as_slice());
+    }
+
+    /// Sets paging state of a page which should be fetched by the query.
+    pub fn set_paging_state(&mut self, paging_state: Option<CBytes>) {
+        self.paging_state = paging_state;
+    }
+
+    /// Converts the query into a request frame.
+    pub fn into_frame(self, with_tracing: bool, with_warnings: bool) -> Frame {
+        let consistency = self.consistency.unwrap_or(Consistency::One);
+        let mut query_frame = Frame::new_req_query(self.query,
+            consistency,
+            self.values,
+            self.with_names,
+            self.page_size,
+            self.paging_state,
+            self.serial_consistency,
+            self.timestamp,
+            request_flags(with_tracing, with_warnings));
+        query_frame.custom_payload = self.custom_payload.unwrap_or(HashMap::new());
+
+        return query_frame;
+    }
 }
 
 /// QueryBuilder is a helper sturcture that helps to construct `Query`. `Query` itself
@@ -71,7 +133,11 @@ pub struct QueryBuilder {
     page_size: Option<i32>,
     paging_state: Option<CBytes>,
     serial_consistency: Option<Consistency>,
-    timestamp: Option<i64>
+    timestamp: Option<i64>,
+    custom_payload: Option<CBytesMap>,
+    timeout: Option<Duration>,
+    idempotent: Option<bool>,
+    routing_key: Option<Vec<u8>>
 }
 
 impl QueryBuilder {
@@ -104,6 +170,19 @@ impl QueryBuilder {
     /// Sets new quey timestamp
     builder_opt_field!(timestamp, i64);
 
+    /// Sets new query custom payload
+    builder_opt_field!(custom_payload, CBytesMap);
+
+    /// Sets new query timeout. It overrides session's default request timeout.
+    builder_opt_field!(timeout, Duration);
+
+    /// Marks the query idempotent, so it could be executed speculatively.
+    builder_opt_field!(idempotent, bool);
+
+    /// Sets serialized partition key of the query which token-aware load balancing
+    /// uses to find replicas. It isn't sent to a server.
+    builder_opt_field!(routing_key, Vec<u8>);
+
     /// Finalizes query building process and returns query itself
     pub fn finalize(&self) -> Query {
         return Query {
@@ -114,7 +193,118 @@ impl QueryBuilder {
             page_size: self.page_size.clone(),
             paging_state: self.paging_state.clone(),
             serial_consistency: self.serial_consistency.clone(),
-            timestamp: self.timestamp.clone()
+            timestamp: self.timestamp.clone(),
+            custom_payload: self.custom_payload.clone(),
+            timeout: self.timeout.clone(),
+            idempotent: self.idempotent.clone(),
+            routing_key: self.routing_key.clone()
+        };
+    }
+}
+
+/// Structure that represents a batch of CQL queries which will be executed
+/// within a single request.
+#[derive(Debug, Clone)]
+pub struct QueryBatch {
+    batch_type: BatchType,
+    queries: Vec<BatchQuery>,
+    consistency: Consistency,
+    serial_consistency: Option<Consistency>,
+    timestamp: Option<i64>,
+    custom_payload: Option<CBytesMap>
+}
+
+impl QueryBatch {
+    /// Converts the batch into a request frame.
+    pub fn into_frame(self, with_tracing: bool, with_warnings: bool) -> Frame {
+        let body = BodyReqBatch {
+            batch_type: self.batch_type,
+            queries: self.queries,
+            consistency: self.consistency,
+            serial_consistency: self.serial_consistency,
+            timestamp: self.timestamp
+        };
+        let mut batch_frame = Frame::new_req_batch(body, request_flags(with_tracing, with_warnings));
+        batch_frame.custom_payload = self.custom_payload.unwrap_or(HashMap::new());
+
+        return batch_frame;
+    }
+}
+
+/// BatchQueryBuilder is a helper structure that helps to construct `QueryBatch`.
+#[derive(Debug)]
+pub struct BatchQueryBuilder {
+    batch_type: BatchType,
+    queries: Vec<BatchQuery>,
+    consistency: Consistency,
+    serial_consistency: Option<Consistency>,
+    timestamp: Option<i64>,
+    custom_payload: Option<CBytesMap>
+}
+
+impl BatchQueryBuilder {
+    /// Factory function that returns new `BatchQueryBuilder` of logged batch
+    /// with consistency `Consistency::One`.
+    pub fn new() -> BatchQueryBuilder {
+        return BatchQueryBuilder {
+            batch_type: BatchType::Logged,
+            queries: vec![],
+            consistency: Consistency::One,
+            serial_consistency: None,
+            timestamp: None,
+            custom_payload: None
+        };
+    }
+
+    /// Sets new batch type
+    pub fn batch_type<'a>(&'a mut self, batch_type: BatchType) -> &'a mut Self {
+        self.batch_type = batch_type;
+        self
+    }
+
+    /// Adds a query string with its values to the batch
+    pub fn add_query<'a>(&'a mut self, query: String, values: Vec<Value>) -> &'a mut Self {
+        self.queries.push(BatchQuery {
+            subject: BatchQuerySubject::QueryString(CStringLong::new(query)),
+            values: values
+        });
+        self
+    }
+
+    /// Adds a prepared query with its values to the batch. `id` is an ID of a query which
+    /// Server returns back to a driver as a response to `prepare` request.
+    pub fn add_query_prepared<'a>(&'a mut self, id: CBytesShort, values: Vec<Value>) -> &'a mut Self {
+        self.queries.push(BatchQuery {
+            subject: BatchQuerySubject::PreparedId(id),
+            values: values
+        });
+        self
+    }
+
+    /// Sets new batch consistency
+    pub fn consistency<'a>(&'a mut self, consistency: Consistency) -> &'a mut Self {
+        self.consistency = consistency;
+        self
+    }
+
+    /// Sets new batch serial_consistency
+    builder_opt_field!(serial_consistency, Consistency);
+
+    /// Sets new batch timestamp
+    builder_opt_field!(timestamp, i64);
+
+    /// Sets new batch custom payload
+    builder_opt_field!(custom_payload, CBytesMap);
+
+    /// Finalizes batch building process and returns batch itself
+    pub fn finalize(&self) -> QueryBatch {
+        return QueryBatch {
+            batch_type: self.batch_type.clone(),
+            queries: self.queries.clone(),
+            consistency: self.consistency.clone(),
+            serial_consistency: self.serial_consistency.clone(),
+            timestamp: self.timestamp.clone(),
+            custom_payload: self.custom_payload.clone()
         };
     }
 }
@@ -131,29 +321,50 @@ pub struct Credentials {
 /// CDRS driver structure that provides a basic functionality to work with DB including
 /// establishing new connection, getting supported options, preparing and executing CQL
 /// queries, using compression and other.
-pub struct CDRS<T: Authenticator> {
+pub struct CDRS<T: Authenticator, X: CDRSTransport> {
     compressor: Compression,
     authenticator: T,
-    transport: Transport
+    transport: X,
+    keyspace: Option<String>
 }
 
+/// Number of attempts to fetch a complete query trace.
+const TRACE_FETCH_ATTEMPTS: u32 = 5;
+/// Delay before the first retry of fetching a query trace. It's doubled on each next attempt.
+const TRACE_FETCH_BASE_DELAY_MS: u64 = 3;
+
+/// Delay between checks of schema versions while waiting for schema agreement.
+const SCHEMA_AGREEMENT_POLL_INTERVAL_MS: u64 = 200;
+
+/// Maximum stream id which could be used by a client. Negative ids are reserved
+/// for server events, and `0` is used by connection establishing requests.
+const MAX_STREAM_ID: u64 = 0x7FFF;
+
 /// Map of options supported by Cassandra server.
 pub type CassandraOptions = HashMap<String, Vec<String>>;
 
-impl<'a, T: Authenticator + 'a> CDRS<T> {
+impl<'a, T: Authenticator + 'a, X: CDRSTransport> CDRS<T, X> {
     /// The method creates new instance of CDRS driver. At this step an instance doesn't
     /// connected to DB Server. To create new instance two parameters are needed to be
-    /// provided - `addr` is IP address of DB Server, `authenticator` is a selected authenticator
+    /// provided - `transport` is a connection to DB Server, `authenticator` is a selected authenticator
     /// that is supported by particular DB Server. There are few authenticators already
     /// provided by this trait.
-    pub fn new(transport: Transport, authenticator: T) -> CDRS<T> {
+    pub fn new(transport: X, authenticator: T) -> CDRS<T, X> {
         return CDRS {
             compressor: Compression::None,
             authenticator: authenticator,
-            transport: transport
+            transport: transport,
+            keyspace: None
         };
     }
 
+    /// Sets a keyspace which is used by a session once it's started (see `Session::use_keyspace`).
+    /// If the keyspace can't be used `start` returns an error.
+    pub fn keyspace(&mut self, keyspace: Option<String>) -> &mut Self {
+        self.keyspace = keyspace;
+        return self;
+    }
+
     /// The method makes an Option request to DB Server. As a response the server returns
     /// a map of supported options.
     pub fn get_options(&mut self) -> error::Result<CassandraOptions> {
@@ -177,7 +388,7 @@ impl<'a, T: Authenticator + 'a> CDRS<T> {
     /// method provided by CRDR driver, it's `Compression::None` that tells drivers that it
     /// should work without compression. If compression is provided then incomming frames
     /// will be decompressed automatically.
-    pub fn start(mut self, compressor: Compression) -> error::Result<Session<T>> {
+    pub fn start(mut self, compressor: Compression) -> error::Result<Session<T, X>> {
         self.compressor = compressor;
         let startup_frame = Frame::new_req_startup(compressor.into_string()).into_cbytes();
 
@@ -185,7 +396,7 @@ impl<'a, T: Authenticator + 'a> CDRS<T> {
         let start_response = try!(parse_frame(&mut self.transport, &compressor));
 
         if start_response.opcode == Opcode::Ready {
-            return Ok(Session::start(self));
+            return self.into_session();
         }
 
         if start_response.opcode == Opcode::Authenticate {
@@ -197,7 +408,7 @@ impl<'a, T: Authenticator + 'a> CDRS<T> {
                 try!(self.transport.write(Frame::new_req_auth_response(auth_token_bytes).into_cbytes().as_slice()));
                 try!(parse_frame(&mut self.transport, &compressor));
 
-                return Ok(Session::start(self));
+                return self.into_session();
             } else {
                 let io_err = io::Error::new(
                     io::ErrorKind::NotFound,
@@ -211,42 +422,298 @@ impl<'a, T: Authenticator + 'a> CDRS<T> {
         unimplemented!();
     }
 
+    fn into_session(self) -> error::Result<Session<T, X>> {
+        let keyspace = self.keyspace.clone();
+        let mut session = Session::start(self);
+        if let Some(keyspace) = keyspace {
+            try!(session.use_keyspace(keyspace.as_str()));
+        }
+
+        return Ok(session);
+    }
+
     fn drop_connection(&mut self) -> error::Result<()> {
         return self.transport.close(net::Shutdown::Both)
             .map_err(|err| error::Error::Io(err));
     }
 }
 
-/// The object that provides functionality for communication with Cassandra server.
-pub struct Session<T: Authenticator> {
+/// Connection of a session. It's shared with a heartbeat thread, so heartbeats
+/// never interleave with requests made by the session.
+struct SessionConnection<T: Authenticator, X: CDRSTransport> {
+    cdrs: CDRS<T, X>,
+    compressor: Compression,
     started: bool,
-    cdrs: CDRS<T>,
-    compressor: Compression
+    broken: bool,
+    next_stream: u64,
+    last_activity: Instant,
+    node_state: Option<NodeState>,
+    keyspace: Option<String>
+}
+
+impl<T: Authenticator, X: CDRSTransport> SessionConnection<T, X> {
+    fn is_broken(&self) -> bool {
+        return self.broken || !self.started;
+    }
+
+    /// Marks the connection defunct. The node isn't marked down, as an error of
+    /// a single connection doesn't mean that other connections to the node are broken.
+    fn mark_broken(&mut self, err: &error::Error) {
+        debug!("Session is broken because of an error: {}", err);
+        self.broken = true;
+    }
+
+    /// Marks the node of the connection down, e.g. when a heartbeat fails.
+    fn mark_node_down(&self, err: &error::Error) {
+        if let Some(ref node_state) = self.node_state {
+            if node_state.mark_down() {
+                warn!("Node {:?} is marked down because of an error: {}",
+                    self.cdrs.transport.peer_addr().ok(), err);
+            }
+        }
+    }
+
+    /// Sends a request frame to DB Server and waits for a response. The connection is marked
+    /// as broken if the request fails because of IO or protocol error, or if it times out
+    /// after a part of the response has been read.
+    fn send_frame(&mut self,
+        frame: Frame,
+        consistency: Option<Consistency>,
+        timeout: Option<Duration>) -> error::Result<(Frame, ExecutionInfo)> {
+        let result = self.write_and_read_frame(frame, consistency, timeout);
+        self.last_activity = Instant::now();
+
+        match result {
+            Ok((ref response, _)) => {
+                if let Some(keyspace) = set_keyspace(response) {
+                    self.keyspace = Some(keyspace);
+                }
+            },
+            Err(error::Error::Server(_)) | Err(error::Error::Timeout(_)) => {},
+            Err(ref err) => self.mark_broken(err)
+        }
+
+        return result;
+    }
+
+    /// Sends a request frame to DB Server and waits for a response. Each request gets its own
+    /// stream id, so responses to previous requests which timed out are discarded.
+    fn write_and_read_frame(&mut self,
+        mut frame: Frame,
+        consistency: Option<Consistency>,
+        timeout: Option<Duration>) -> error::Result<(Frame, ExecutionInfo)> {
+        let stream = self.next_stream;
+        self.next_stream = if stream >= MAX_STREAM_ID { 1 } else { stream + 1 };
+        frame.stream = stream;
+
+        let node = self.cdrs.transport.peer_addr().ok();
+        let started_at = Instant::now();
+
+        try!(self.cdrs.transport.write(frame.into_cbytes().as_slice()));
+
+        let response = loop {
+            if let Some(timeout) = timeout {
+                let elapsed = started_at.elapsed();
+                if elapsed >= timeout {
+                    return Err(error::Error::Timeout(timeout));
+                }
+                try!(self.cdrs.transport.set_read_timeout(Some(timeout - elapsed)));
+            } else {
+                try!(self.cdrs.transport.set_read_timeout(None));
+            }
+
+            let mut reader = ReadCounter::new(&mut self.cdrs.transport);
+            let parsed = parse_raw_frame(&mut reader, &self.compressor);
+            let partially_read = reader.count > 0;
+            let response = match parsed {
+                Ok(response) => response,
+                Err(error::Error::Io(ref err)) if timeout.is_some() && is_timeout_error(err) => {
+                    let err = error::Error::Timeout(timeout.unwrap());
+                    // the rest of the frame is left in the socket, so the next read
+                    // would start in the middle of it
+                    if partially_read {
+                        self.mark_broken(&err);
+                    }
+                    return Err(err);
+                },
+                Err(err) => return Err(err)
+            };
+
+            if response.stream == stream {
+                break try!(convert_frame_into_result(response));
+            }
+
+            debug!("Discarding a response with stream id {} while waiting for stream id {}",
+                response.stream, stream);
+        };
+
+        let info = ExecutionInfo::new(&response, node, consistency, started_at.elapsed());
+        return Ok((response, info));
+    }
+}
+
+/// The object that provides functionality for communication with Cassandra server.
+pub struct Session<T: Authenticator, X: CDRSTransport> {
+    connection: Arc<Mutex<SessionConnection<T, X>>>,
+    heartbeat: Option<Heartbeat>,
+    log_warnings: bool,
+    request_timeout: Option<Duration>,
+    schema_agreement_timeout: Option<Duration>,
+    serial_consistency: Option<Consistency>,
+    cas_timeout_retries: u32,
+    node_states: Option<NodeStates>
 }
 
-impl<T: Authenticator> Session<T> {
+impl<T: Authenticator, X: CDRSTransport> Session<T, X> {
     /// Creates new session basing on CDRS instance.
-    pub fn start(cdrs: CDRS<T>) -> Session<T> {
+    pub fn start(cdrs: CDRS<T, X>) -> Session<T, X> {
         let compressor = cdrs.compressor.clone();
-        return Session {
+        let connection = SessionConnection {
             cdrs: cdrs,
+            compressor: compressor,
             started: true,
-            compressor: compressor
+            broken: false,
+            next_stream: 1,
+            last_activity: Instant::now(),
+            node_state: None,
+            keyspace: None
+        };
+
+        return Session {
+            connection: Arc::new(Mutex::new(connection)),
+            heartbeat: None,
+            log_warnings: false,
+            request_timeout: None,
+            schema_agreement_timeout: None,
+            serial_consistency: None,
+            cas_timeout_retries: 0,
+            node_states: None
         };
     }
 
     /// The method overrides a compression method of current session
     pub fn compressor(&mut self, compressor: Compression) -> &mut Self {
-        self.compressor = compressor;
+        self.connection().compressor = compressor;
+        return self;
+    }
+
+    /// The method sets default timeout of requests made within the session.
+    /// If a response is not received in time `error::Error::Timeout` is returned.
+    /// `None` means that the session will wait for responses indefinitely.
+    pub fn request_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.request_timeout = timeout;
+        return self;
+    }
+
+    /// Enables waiting for schema agreement after requests which changed schema, e.g.
+    /// `CREATE TABLE`. Once a schema change result is received the session polls schema
+    /// versions of nodes until all of them agree or `timeout` passes, and then the result
+    /// is returned. The outcome is reported in `ExecutionInfo::schema_in_agreement`.
+    /// `None` (the default) disables waiting.
+    pub fn schema_agreement_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.schema_agreement_timeout = timeout;
+        return self;
+    }
+
+    /// Sets default serial consistency of conditional writes, i.e. it's used by queries,
+    /// executions and batches which don't set their own serial consistency.
+    /// `None` (the default) means that a server uses `SERIAL`.
+    pub fn serial_consistency(&mut self, serial_consistency: Option<Consistency>) -> &mut Self {
+        self.serial_consistency = serial_consistency;
+        return self;
+    }
+
+    /// Sets a number of times `lwt` retries a conditional write which timed out during
+    /// Paxos phase (i.e. `WriteTimeout` error with `WriteType::Cas`). Such a write may or
+    /// may not be applied, so a retry may report that it wasn't applied because of values
+    /// written by the timed out attempt. Retries are disabled by default.
+    pub fn cas_timeout_retries(&mut self, retries: u32) -> &mut Self {
+        self.cas_timeout_retries = retries;
+        return self;
+    }
+
+    /// If set to `true` every warning returned by a server will be logged
+    /// via `log` crate with `warn` level.
+    pub fn log_warnings(&mut self, log_warnings: bool) -> &mut Self {
+        self.log_warnings = log_warnings;
+        return self;
+    }
+
+    /// Sets up/down state of a node the session is connected to. The node
+    /// is marked down when a heartbeat of the session fails.
+    pub fn node_state(&mut self, node_state: NodeState) -> &mut Self {
+        self.connection().node_state = Some(node_state);
         return self;
     }
 
+    /// Sets states of nodes of a cluster the session belongs to. Peers which are
+    /// known to be down don't take part in schema agreement (see `check_schema_agreement`).
+    pub fn node_states(&mut self, node_states: Option<NodeStates>) -> &mut Self {
+        self.node_states = node_states;
+        return self;
+    }
+
+    /// Starts a background thread which sends `OPTIONS` request whenever the session
+    /// has been idle for `interval`, so idle connections are not killed by firewalls and NATs.
+    /// If a heartbeat fails or isn't answered within `interval` the session is marked broken
+    /// and its node is marked down. The thread stops once the session is broken or dropped.
+    /// Sessions of a pool should rather share a single thread, see `Heartbeat`.
+    pub fn heartbeat(&mut self, interval: Duration) -> &mut Self
+        where T: Send + 'static, X: 'static {
+        let heartbeat = Heartbeat::new(interval);
+        heartbeat.register(self);
+        self.heartbeat = Some(heartbeat);
+        return self;
+    }
+
+    /// Returns a keyspace which is used by the session, i.e. a keyspace of the last
+    /// successful `USE` query.
+    pub fn keyspace(&self) -> Option<String> {
+        return self.connection().keyspace.clone();
+    }
+
+    /// Makes the session use provided keyspace for unqualified table names by sending
+    /// `USE <keyspace>` query. The name is used as is, so case sensitive names should
+    /// be double quoted.
+    pub fn use_keyspace(&mut self, keyspace: &str) -> error::Result<()> {
+        let query = QueryBuilder::new(format!("USE {}", keyspace).as_str()).finalize();
+        let response = try!(self.query(query, false, false));
+        if set_keyspace(&response).is_none() {
+            return Err(error::Error::General(format!("Unexpected response to USE query {:?}",
+                response.opcode)));
+        }
+
+        return Ok(());
+    }
+
+    /// Indicates if the session's connection is unusable, i.e. the session was ended
+    /// or a request failed because of IO or protocol error. Errors returned by a server
+    /// don't break a session, neither do timeouts unless a part of the response was read.
+    pub fn is_broken(&self) -> bool {
+        return self.connection().is_broken();
+    }
+
+    /// The method makes an Options request to DB Server. As a response the server returns
+    /// a map of supported options. It's the cheapest request which could be used to check
+    /// if a connection is alive.
+    pub fn get_options(&mut self) -> error::Result<CassandraOptions> {
+        let timeout = self.request_timeout;
+        let (response, _) = try!(self.send_frame(Frame::new_req_options(), None, timeout));
+
+        return match response.get_body() {
+            ResponseBody::Supported(supported_body) => Ok(supported_body.data),
+            _ => Err(error::Error::General(format!("Unexpected response to OPTIONS request {:?}",
+                response.opcode)))
+        };
+    }
+
     /// Manually ends current session.
     /// Apart of that session will be ended automatically when the instance is dropped.
     pub fn end(&mut self) {
-        if self.started {
-            self.started = false;
-            match self.cdrs.drop_connection() {
+        let mut connection = self.connection();
+        if connection.started {
+            connection.started = false;
+            match connection.cdrs.drop_connection() {
                 Ok(_) => (),
                 Err(err) => {
                     println!("Error occured during dropping CDRS {:?}", err);
@@ -255,23 +722,30 @@ impl<T: Authenticator> Session<T> {
         }
     }
 
+    fn connection(&self) -> MutexGuard<SessionConnection<T, X>> {
+        return lock_connection(&self.connection);
+    }
+
     /// The method makes a request to DB Server to prepare provided query.
     pub fn prepare(&mut self,
         query: String,
         with_tracing: bool,
         with_warnings: bool) -> error::Result<Frame> {
-        let mut flags = vec![];
-        if with_tracing {
-            flags.push(Flag::Tracing);
-        }
-        if with_warnings {
-            flags.push(Flag::Warning);
-        }
+        return self.prepare_with_payload(query, HashMap::new(), with_tracing, with_warnings);
+    }
 
-        let options_frame = Frame::new_req_prepare(query, flags).into_cbytes();
+    /// The method makes a request to DB Server to prepare provided query.
+    /// `custom_payload` is sent to the server along with the request.
+    pub fn prepare_with_payload(&mut self,
+        query: String,
+        custom_payload: CBytesMap,
+        with_tracing: bool,
+        with_warnings: bool) -> error::Result<Frame> {
+        let mut prepare_frame = Frame::new_req_prepare(query, request_flags(with_tracing, with_warnings));
+        prepare_frame.custom_payload = custom_payload;
 
-        try!(self.cdrs.transport.write(options_frame.as_slice()));
-        return parse_frame(&mut self.cdrs.transport, &self.compressor);
+        let timeout = self.request_timeout;
+        return self.send_frame(prepare_frame, None, timeout).map(|(frame, _)| frame);
     }
 
     /// The method makes a request to DB Server to execute a query with provided id
@@ -282,19 +756,66 @@ impl<T: Authenticator> Session<T> {
         query_parameters: ParamsReqQuery,
         with_tracing: bool,
         with_warnings: bool) -> error::Result<Frame> {
+        return self.execute_with_payload(id, query_parameters, HashMap::new(), with_tracing, with_warnings);
+    }
 
-        let mut flags = vec![];
-        if with_tracing {
-            flags.push(Flag::Tracing);
-        }
-        if with_warnings {
-            flags.push(Flag::Warning);
+    /// The method makes a request to DB Server to execute a query with provided id
+    /// using provided query parameters. `custom_payload` is sent to the server
+    /// along with the request.
+    pub fn execute_with_payload(&mut self,
+        id: CBytesShort,
+        query_parameters: ParamsReqQuery,
+        custom_payload: CBytesMap,
+        with_tracing: bool,
+        with_warnings: bool) -> error::Result<Frame> {
+        return self.execute_with_info(id, query_parameters, custom_payload, with_tracing, with_warnings)
+            .map(|(frame, _)| frame);
+    }
+
+    /// The method does the same as `execute_with_payload` but apart of a response frame
+    /// it returns an information about the request execution.
+    pub fn execute_with_info(&mut self,
+        id: CBytesShort,
+        query_parameters: ParamsReqQuery,
+        custom_payload: CBytesMap,
+        with_tracing: bool,
+        with_warnings: bool) -> error::Result<(Frame, ExecutionInfo)> {
+        let mut query_parameters = query_parameters;
+        if let Some(ref serial_consistency) = self.serial_consistency {
+            if !query_parameters.flags.contains(&QueryFlags::WithSerialConsistency) {
+                query_parameters.flags.push(QueryFlags::WithSerialConsistency);
+                query_parameters.serial_consistency = serial_consistency.clone();
+            }
         }
+        let consistency = query_parameters.consistency.clone();
+        let flags = request_flags(with_tracing, with_warnings);
+        let mut execute_frame = Frame::new_req_execute(id, query_parameters, flags);
+        execute_frame.custom_payload = custom_payload;
 
-        let options_frame = Frame::new_req_execute(id, query_parameters, flags).into_cbytes();
+        let timeout = self.request_timeout;
+        return self.send_frame(execute_frame, Some(consistency), timeout);
+    }
 
-        try!(self.cdrs.transport.write(options_frame.as_slice()));
-        return parse_frame(&mut self.cdrs.transport, &self.compressor);
+    /// The method makes a request to DB Server to execute a batch of queries.
+    /// The batch could be built with `BatchQueryBuilder`.
+    pub fn batch(&mut self, batch: QueryBatch, with_tracing: bool, with_warnings: bool)
+        -> error::Result<Frame> {
+        return self.batch_with_info(batch, with_tracing, with_warnings).map(|(frame, _)| frame);
+    }
+
+    /// The method does the same as `batch` but apart of a response frame
+    /// it returns an information about the request execution.
+    pub fn batch_with_info(&mut self, batch: QueryBatch, with_tracing: bool, with_warnings: bool)
+        -> error::Result<(Frame, ExecutionInfo)> {
+        let mut batch = batch;
+        if batch.serial_consistency.is_none() {
+            batch.serial_consistency = self.serial_consistency.clone();
+        }
+        let consistency = batch.consistency.clone();
+        let batch_frame = batch.into_frame(with_tracing, with_warnings);
+
+        let timeout = self.request_timeout;
+        return self.send_frame(batch_frame, Some(consistency), timeout);
     }
 
     /// The method makes a request to DB Server to execute a query provided in `query` argument.
@@ -305,30 +826,502 @@ impl<T: Authenticator> Session<T> {
     /// ```
     pub fn query(&mut self, query: Query, with_tracing: bool, with_warnings: bool)
         -> error::Result<Frame> {
-        let consistency = match query.consistency {
-            Some(cs) => cs,
-            None => Consistency::One,
+        return self.query_with_info(query, with_tracing, with_warnings).map(|(frame, _)| frame);
+    }
+
+    /// The method does the same as `query` but apart of a response frame
+    /// it returns an information about the request execution.
+    pub fn query_with_info(&mut self, query: Query, with_tracing: bool, with_warnings: bool)
+        -> error::Result<(Frame, ExecutionInfo)> {
+        let mut query = query;
+        if query.serial_consistency.is_none() {
+            query.serial_consistency = self.serial_consistency.clone();
+        }
+        let consistency = query.consistency.clone().unwrap_or(Consistency::One);
+        let timeout = query.timeout.or(self.request_timeout);
+        let query_frame = query.into_frame(with_tracing, with_warnings);
+
+        return self.send_frame(query_frame, Some(consistency), timeout);
+    }
+
+    /// Executes a conditional write, e.g. `INSERT ... IF NOT EXISTS`, and parses its
+    /// `[applied]` result. Writes which timed out during Paxos phase are retried
+    /// in accordance to `cas_timeout_retries`.
+    pub fn lwt(&mut self, query: Query, with_tracing: bool, with_warnings: bool) -> error::Result<LwtResult> {
+        return self.send_lwt(|session| session.query(query.clone(), with_tracing, with_warnings));
+    }
+
+    /// Does the same as `lwt` for a prepared conditional write.
+    pub fn lwt_execute(&mut self,
+        id: CBytesShort,
+        query_parameters: ParamsReqQuery,
+        with_tracing: bool,
+        with_warnings: bool) -> error::Result<LwtResult> {
+        return self.send_lwt(|session| {
+            session.execute(id.clone(), query_parameters.clone(), with_tracing, with_warnings)
+        });
+    }
+
+    /// Does the same as `lwt` for a conditional batch. Such a batch returns a row
+    /// per conditional statement (see `LwtResult::from_rows`).
+    pub fn lwt_batch(&mut self, batch: QueryBatch, with_tracing: bool, with_warnings: bool)
+        -> error::Result<LwtResult> {
+        return self.send_lwt(|session| session.batch(batch.clone(), with_tracing, with_warnings));
+    }
+
+    /// Sends a conditional write via `send` and retries it after CAS write timeouts.
+    fn send_lwt<F>(&mut self, send: F) -> error::Result<LwtResult>
+        where F: Fn(&mut Self) -> error::Result<Frame> {
+        let mut retries = 0;
+        loop {
+            let err = match send(self) {
+                Ok(response) => return LwtResult::from_frame(&response),
+                Err(err) => err
+            };
+            if !is_cas_write_timeout(&err) || retries >= self.cas_timeout_retries {
+                return Err(err);
+            }
+
+            retries += 1;
+            debug!("Retrying conditional write after CAS write timeout, retry {}", retries);
+        }
+    }
+
+    /// Executes a DDL query, e.g. `CREATE TABLE`, and returns an outcome of the schema change.
+    /// `None` is returned if schema wasn't changed, e.g. for `CREATE TABLE IF NOT EXISTS`
+    /// when the table already exists.
+    pub fn ddl(&mut self, query: Query, with_tracing: bool, with_warnings: bool)
+        -> error::Result<Option<SchemaChangeOutcome>> {
+        let response = try!(self.query(query, with_tracing, with_warnings));
+
+        return match response.get_body() {
+            ResponseBody::Result(ResResultBody::SchemaChange(change)) => Ok(Some(SchemaChangeOutcome::from(change))),
+            ResponseBody::Result(ResResultBody::Void(_)) => Ok(None),
+            _ => Err(error::Error::General(format!("Unexpected response to DDL query {:?}", response.opcode)))
         };
+    }
 
-        let mut flags = vec![];
-        if with_tracing {
-            flags.push(Flag::Tracing);
+    /// Sends a request frame to DB Server and waits for a response.
+    fn send_frame(&mut self,
+        frame: Frame,
+        consistency: Option<Consistency>,
+        timeout: Option<Duration>) -> error::Result<(Frame, ExecutionInfo)> {
+        let (response, mut info) = try!(self.connection().send_frame(frame, consistency, timeout));
+
+        if self.log_warnings {
+            for warning in response.warnings.iter() {
+                warn!("Server warning: {}", warning);
+            }
         }
-        if with_warnings {
-            flags.push(Flag::Warning);
+
+        if let Some(agreement_timeout) = self.schema_agreement_timeout {
+            if is_schema_change(&response) {
+                info.schema_in_agreement = Some(self.wait_for_schema_agreement(agreement_timeout));
+            }
         }
 
-        let query_frame = Frame::new_req_query(query.query,
-            consistency,
-            query.values,
-            query.with_names,
-            query.page_size,
-            query.paging_state,
-            query.serial_consistency,
-            query.timestamp,
-            flags).into_cbytes();
+        return Ok((response, info));
+    }
+
+    /// Checks if all nodes have the same schema version. Versions are read from
+    /// `system.local` and `system.peers` tables of a node the session is connected to.
+    /// Peers which schema version is unknown and peers which are known to be down
+    /// (see `node_states`) are ignored.
+    pub fn check_schema_agreement(&mut self) -> error::Result<bool> {
+        let local_query = QueryBuilder::new("SELECT schema_version FROM system.local WHERE key = 'local'")
+            .finalize();
+        let peers_query = QueryBuilder::new("SELECT peer, rpc_address, schema_version FROM system.peers")
+            .finalize();
+
+        let mut rows = try!(self.query(local_query, false, false)).get_body().into_rows().unwrap_or(vec![]);
+        rows.extend(try!(self.query(peers_query, false, false)).get_body().into_rows().unwrap_or(vec![]));
+
+        let mut versions: Vec<Uuid> = vec![];
+        for row in rows.iter() {
+            if row.is_null("schema_version").unwrap_or(true) || self.is_peer_down(row) {
+                continue;
+            }
+            let version: Uuid = match row.get_by_name("schema_version") {
+                Some(version) => try!(version),
+                None => continue
+            };
+            if !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
 
-        try!(self.cdrs.transport.write(query_frame.as_slice()));
-        return parse_frame(&mut self.cdrs.transport, &self.compressor);
+        return Ok(versions.len() <= 1);
     }
+
+    /// Indicates if a peer of a `system.peers` row is known to be down, the peer
+    /// is looked up by both its `peer` and `rpc_address` addresses.
+    fn is_peer_down(&self, row: &Row) -> bool {
+        let node_states = match self.node_states {
+            Some(ref node_states) => node_states,
+            None => return false
+        };
+
+        return ["peer", "rpc_address"].iter().any(|column| {
+            if row.is_null(column).unwrap_or(true) {
+                return false;
+            }
+            let address: net::IpAddr = match row.get_by_name(column) {
+                Some(Ok(address)) => address,
+                _ => return false
+            };
+            return node_states.get(&address).map_or(false, |state| !state.is_up());
+        });
+    }
+
+    /// Polls schema versions until all nodes agree (see `check_schema_agreement`)
+    /// or `timeout` passes. Returns `false` if the agreement wasn't reached in time
+    /// or versions couldn't be checked.
+    pub fn wait_for_schema_agreement(&mut self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.check_schema_agreement() {
+                Ok(true) => return true,
+                Ok(false) => {},
+                Err(err) => {
+                    warn!("Cannot check schema agreement: {}", err);
+                    return false;
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                warn!("Schema agreement wasn't reached in {:?}", timeout);
+                return false;
+            }
+            let delay = Duration::from_millis(SCHEMA_AGREEMENT_POLL_INTERVAL_MS);
+            thread::sleep(cmp::min(deadline - now, delay));
+        }
+    }
+
+    /// The method retrieves a trace of a query which was executed with tracing flag.
+    /// `tracing_id` is an id which Server returns back in a response frame
+    /// (see `Frame::tracing_id`). As traces are written asynchronously the method polls
+    /// `system_traces.sessions` and `system_traces.events` until the trace is complete
+    /// and returns an error if it's still incomplete after few attempts.
+    pub fn get_query_trace(&mut self, tracing_id: Uuid) -> error::Result<QueryTrace> {
+        let session_query = format!("SELECT * FROM system_traces.sessions WHERE session_id = {};",
+            tracing_id.hyphenated());
+        let events_query = format!("SELECT * FROM system_traces.events WHERE session_id = {};",
+            tracing_id.hyphenated());
+        let mut delay = TRACE_FETCH_BASE_DELAY_MS;
+
+        for attempt in 0..TRACE_FETCH_ATTEMPTS {
+            if attempt > 0 {
+                thread::sleep(Duration::from_millis(delay));
+                delay = delay * 2;
+            }
+
+            let session_rows = try!(self.query(QueryBuilder::new(session_query.as_str()).finalize(), false, false))
+                .get_body()
+                .into_rows()
+                .unwrap_or(vec![]);
+            let session_row = match session_rows.first() {
+                Some(row) => row,
+                None => continue
+            };
+
+            // it's up to `QueryTrace::from_rows` to decide if the trace is complete
+            let event_rows = try!(self.query(QueryBuilder::new(events_query.as_str()).finalize(), false, false))
+                .get_body()
+                .into_rows()
+                .unwrap_or(vec![]);
+
+            if let Some(trace) = try!(QueryTrace::from_rows(tracing_id, session_row, &event_rows)) {
+                return Ok(trace);
+            }
+        }
+
+        return Err(error::Error::General(format!("Trace {} is not complete after {} attempts",
+            tracing_id, TRACE_FETCH_ATTEMPTS)));
+    }
+
+    /// Registers the session for provided types of server events and turns it into
+    /// a listener of the events. As a listener blocks on reading events, a dedicated
+    /// connection should be used for listening.
+    pub fn listen_for(mut self, events: Vec<SimpleServerEvent>) -> error::Result<Listener<T, X>> {
+        let timeout = self.request_timeout;
+        let (response, _) = try!(self.send_frame(Frame::new_req_register(events), None, timeout));
+        if response.opcode != Opcode::Ready {
+            return Err(error::Error::General(format!("Unexpected response to REGISTER request {:?}",
+                response.opcode)));
+        }
+
+        return Ok(Listener { session: self });
+    }
+}
+
+/// Listener of server events, see `Session::listen_for`. Being an iterator
+/// it yields events until the connection fails.
+pub struct Listener<T: Authenticator, X: CDRSTransport> {
+    session: Session<T, X>
+}
+
+impl<T: Authenticator, X: CDRSTransport> Listener<T, X> {
+    /// Blocks until next event is received. Frames which aren't events are skipped.
+    pub fn next_event(&mut self) -> error::Result<ServerEvent> {
+        let mut connection = self.session.connection();
+        try!(connection.cdrs.transport.set_read_timeout(None));
+
+        let compressor = connection.compressor;
+        loop {
+            let frame = match parse_raw_frame(&mut connection.cdrs.transport, &compressor) {
+                Ok(frame) => frame,
+                Err(err) => {
+                    connection.mark_broken(&err);
+                    return Err(err);
+                }
+            };
+
+            if let ResponseBody::Event(event) = frame.get_body() {
+                return Ok(event.event);
+            }
+            debug!("Discarding a frame {:?} while listening for events", frame.opcode);
+        }
+    }
+}
+
+impl<T: Authenticator, X: CDRSTransport> Iterator for Listener<T, X> {
+    type Item = ServerEvent;
+
+    fn next(&mut self) -> Option<ServerEvent> {
+        return self.next_event().ok();
+    }
+}
+
+fn lock_connection<T: Authenticator, X: CDRSTransport>(connection: &Mutex<SessionConnection<T, X>>)
+    -> MutexGuard<SessionConnection<T, X>> {
+    // a panic while the lock was held cannot leave the connection in inconsistent state
+    return match connection.lock() {
+        Ok(connection) => connection,
+        Err(poisoned) => poisoned.into_inner()
+    };
+}
+
+/// Sends heartbeats on behalf of registered sessions from a single background thread,
+/// e.g. on behalf of all connections of a pool. A session gets `OPTIONS` request whenever
+/// it has been idle for `interval`. If a heartbeat fails or isn't answered within `interval`
+/// the session is marked broken and its node is marked down. The thread is started once
+/// the first session is registered and it stops as soon as the heartbeat is dropped.
+pub struct Heartbeat {
+    interval: Duration,
+    shared: Arc<HeartbeatShared>
+}
+
+struct HeartbeatShared {
+    state: Mutex<HeartbeatState>,
+    stopped: Condvar
+}
+
+struct HeartbeatState {
+    connections: Vec<(usize, Arc<HeartbeatConnection>)>,
+    next_id: usize,
+    started: bool,
+    stopped: bool
+}
+
+impl Heartbeat {
+    /// Creates new heartbeat with provided interval.
+    pub fn new(interval: Duration) -> Heartbeat {
+        let state = HeartbeatState {
+            connections: vec![],
+            next_id: 0,
+            started: false,
+            stopped: false
+        };
+
+        return Heartbeat {
+            interval: interval,
+            shared: Arc::new(HeartbeatShared {
+                state: Mutex::new(state),
+                stopped: Condvar::new()
+            })
+        };
+    }
+
+    /// Starts sending heartbeats on behalf of provided session. The session is
+    /// unregistered once it's broken or dropped.
+    pub fn register<T, X>(&self, session: &Session<T, X>)
+        where T: Authenticator + Send + 'static, X: CDRSTransport + 'static {
+        let connection: Arc<HeartbeatConnection> = Arc::new(Arc::downgrade(&session.connection));
+        let mut state = lock_heartbeat_state(&self.shared.state);
+        let id = state.next_id;
+        state.next_id += 1;
+        state.connections.push((id, connection));
+
+        if !state.started {
+            state.started = true;
+            let shared = self.shared.clone();
+            let interval = self.interval;
+            thread::spawn(move || run_heartbeat(&shared, interval));
+        }
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        lock_heartbeat_state(&self.shared.state).stopped = true;
+        self.shared.stopped.notify_all();
+    }
+}
+
+/// Connection which heartbeats are sent on.
+trait HeartbeatConnection: Send + Sync {
+    /// Sends a heartbeat if the connection has been idle for `interval`. Returns a delay
+    /// until the next heartbeat or `None` if the connection doesn't need heartbeats anymore,
+    /// i.e. it's broken or its session is dropped.
+    fn heartbeat(&self, interval: Duration) -> Option<Duration>;
+}
+
+impl<T: Authenticator + Send, X: CDRSTransport> HeartbeatConnection for Weak<Mutex<SessionConnection<T, X>>> {
+    fn heartbeat(&self, interval: Duration) -> Option<Duration> {
+        let connection = match self.upgrade() {
+            Some(connection) => connection,
+            None => return None
+        };
+        let mut connection = match connection.try_lock() {
+            Ok(connection) => connection,
+            // a request is in progress, so the connection isn't idle
+            Err(TryLockError::WouldBlock) => return Some(interval),
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner()
+        };
+        if connection.is_broken() {
+            return None;
+        }
+
+        let idle = connection.last_activity.elapsed();
+        if idle < interval {
+            return Some(interval - idle);
+        }
+
+        return match connection.send_frame(Frame::new_req_options(), None, Some(interval)) {
+            Ok(_) => Some(interval),
+            Err(err) => {
+                // in opposite to regular requests a heartbeat which timed out
+                // or failed on a server side makes the connection defunct
+                connection.mark_broken(&err);
+                connection.mark_node_down(&err);
+                None
+            }
+        };
+    }
+}
+
+fn lock_heartbeat_state(state: &Mutex<HeartbeatState>) -> MutexGuard<HeartbeatState> {
+    return match state.lock() {
+        Ok(state) => state,
+        Err(poisoned) => poisoned.into_inner()
+    };
+}
+
+fn run_heartbeat(shared: &HeartbeatShared, interval: Duration) {
+    let mut state = lock_heartbeat_state(&shared.state);
+
+    while !state.stopped {
+        let connections = state.connections.clone();
+        // the state isn't locked while heartbeats are sent, so sessions could be registered
+        drop(state);
+
+        let mut delay = interval;
+        let mut finished = vec![];
+        for &(id, ref connection) in connections.iter() {
+            match connection.heartbeat(interval) {
+                Some(next_delay) => delay = cmp::min(delay, next_delay),
+                None => finished.push(id)
+            }
+        }
+        drop(connections);
+
+        state = lock_heartbeat_state(&shared.state);
+        state.connections.retain(|&(id, _)| !finished.contains(&id));
+        if state.stopped {
+            return;
+        }
+
+        state = match shared.stopped.wait_timeout(state, delay) {
+            Ok((state, _)) => state,
+            Err(poisoned) => poisoned.into_inner().0
+        };
+    }
+}
+
+/// Reader which counts bytes read from an underlying reader.
+struct ReadCounter<'a, R: io::Read + 'a> {
+    inner: &'a mut R,
+    count: usize
+}
+
+impl<'a, R: io::Read + 'a> ReadCounter<'a, R> {
+    fn new(inner: &'a mut R) -> ReadCounter<'a, R> {
+        return ReadCounter {
+            inner: inner,
+            count: 0
+        };
+    }
+}
+
+impl<'a, R: io::Read + 'a> io::Read for ReadCounter<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = try!(self.inner.read(buf));
+        self.count += read;
+        return Ok(read);
+    }
+}
+
+/// Depending on a platform a socket read timeout is reported either as
+/// `io::ErrorKind::WouldBlock` or as `io::ErrorKind::TimedOut`.
+fn is_timeout_error(err: &io::Error) -> bool {
+    return err.kind() == io::ErrorKind::WouldBlock || err.kind() == io::ErrorKind::TimedOut;
+}
+
+/// Returns frame flags which ask a server to trace a request and/or
+/// to return warnings.
+pub fn request_flags(with_tracing: bool, with_warnings: bool) -> Vec<Flag> {
+    let mut flags = vec![];
+    if with_tracing {
+        flags.push(Flag::Tracing);
+    }
+    if with_warnings {
+        flags.push(Flag::Warning);
+    }
+
+    return flags;
+}
+
+/// Returns a keyspace name if provided frame is a `SetKeyspace` result. The result kind
+/// is checked before the body is parsed, so other results aren't parsed twice.
+fn set_keyspace(response: &Frame) -> Option<String> {
+    if response.opcode != Opcode::Result || !response.body.starts_with(&ResultKind::SetKeyspace.into_cbytes()) {
+        return None;
+    }
+
+    return match response.get_body() {
+        ResponseBody::Result(ResResultBody::SetKeyspace(body)) => Some(body.body.into_plain()),
+        _ => None
+    };
+}
+
+/// Indicates if provided error is a write timeout of a Paxos (CAS) phase.
+fn is_cas_write_timeout(err: &error::Error) -> bool {
+    return match *err {
+        error::Error::Server(ref err) => match err.additional_info {
+            AdditionalErrorInfo::WriteTimeout(ref timeout) => timeout.write_type == WriteType::Cas,
+            _ => false
+        },
+        _ => false
+    };
+}
+
+/// Indicates if provided frame is a `SchemaChange` result. The result kind is checked
+/// before the body is parsed, so other results (e.g. rows) aren't parsed twice.
+fn is_schema_change(response: &Frame) -> bool {
+    if response.opcode != Opcode::Result || !response.body.starts_with(&ResultKind::SchemaChange.into_cbytes()) {
+        return false;
+    }
+
+    return SchemaChangeOutcome::from_response(response).is_some();
 }
@@ -0,0 +1,481 @@
+//! The module contains a cluster of Cassandra nodes. Each node has its own
+//! [r2d2](https://github.com/sfackler/r2d2) pool of connections, and connections
+//! are taken from nodes which are up in accordance to a load balancing policy.
+use std::cmp;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Instant;
+
+use r2d2;
+
+use authenticators::Authenticator;
+use client::{Query, Session};
+use connection_manager::ConnectionManager;
+use consistency::Consistency;
+use error;
+use execution_info::ExecutionInfo;
+use frame::Frame;
+use load_balancing::{LoadBalancingPolicy, NodeDistance, RoundRobinPolicy};
+use node_state::{NodeState, NodeStates};
+use speculative_execution::SpeculativeExecutionPolicy;
+use transport::CDRSTransport;
+
+/// Connection which is taken from a pool of a node.
+pub type PooledSession<T, X> = r2d2::PooledConnection<ConnectionManager<T, X>>;
+
+struct ClusterNode<T, X>
+    where T: Authenticator + Send + Sync + 'static,
+          X: CDRSTransport + 'static
+{
+    pool: r2d2::Pool<ConnectionManager<T, X>>,
+    state: NodeState,
+    connects_on_demand: bool
+}
+
+/// Cluster of nodes. Nodes are queried in order of a load balancing policy,
+/// round-robin by default. Nodes which are down are excluded from load balancing
+/// until they are marked up again by background reconnection
+/// (see `ConnectionManager::reconnection_policy`). Downed nodes without reconnection
+/// policy are connected on demand, i.e. they're tried after nodes which are up
+/// and marked up once they provide a connection.
+///
+/// Idempotent queries could be executed speculatively (see `speculative_execution_policy`).
+pub struct Cluster<T, X>
+    where T: Authenticator + Send + Sync + 'static,
+          X: CDRSTransport + 'static
+{
+    nodes: Vec<ClusterNode<T, X>>,
+    node_states: NodeStates,
+    load_balancing_policy: Arc<LoadBalancingPolicy>,
+    speculative_execution_policy: Option<Arc<SpeculativeExecutionPolicy>>,
+    executor: Executor
+}
+
+/// Default max number of threads which run speculative executions.
+pub const DEFAULT_SPECULATIVE_EXECUTION_THREADS: usize = 8;
+
+impl<T: Authenticator + Send + Sync + 'static, X: CDRSTransport + 'static> Cluster<T, X> {
+    /// Creates new cluster without nodes.
+    pub fn new() -> Cluster<T, X> {
+        return Cluster {
+            nodes: vec![],
+            node_states: NodeStates::new(),
+            load_balancing_policy: Arc::new(RoundRobinPolicy::new()),
+            speculative_execution_policy: None,
+            executor: Executor::new(DEFAULT_SPECULATIVE_EXECUTION_THREADS)
+        };
+    }
+
+    /// Sets a load balancing policy.
+    pub fn load_balancing_policy(&mut self, policy: Arc<LoadBalancingPolicy>) -> &mut Self {
+        self.load_balancing_policy = policy;
+        self
+    }
+
+    /// Sets a policy of speculative execution of idempotent queries.
+    /// `None` disables speculative executions.
+    pub fn speculative_execution_policy(&mut self, policy: Option<Arc<SpeculativeExecutionPolicy>>)
+        -> &mut Self {
+        self.speculative_execution_policy = policy;
+        self
+    }
+
+    /// Sets max number of threads which run speculative executions. Threads are started
+    /// on demand and shared by all requests, so executions which don't get a thread wait
+    /// for one. The default is `DEFAULT_SPECULATIVE_EXECUTION_THREADS`.
+    pub fn speculative_execution_threads(&mut self, threads: usize) -> &mut Self {
+        self.executor = Executor::new(threads);
+        self
+    }
+
+    /// Adds a node with a pool which is created with provided config and manager.
+    /// A node which may be down at the moment should be added with
+    /// `initialization_fail_fast(false)` config, otherwise an error is returned.
+    /// Location discovery is enabled on the manager (see `ConnectionManager::discover_location`),
+    /// so datacenter and rack of the node are known once it's connected. The manager shares
+    /// states of all nodes of the cluster (see `ConnectionManager::node_states`).
+    pub fn add_node(&mut self,
+                    config: r2d2::Config<Session<T, X>, error::Error>,
+                    mut manager: ConnectionManager<T, X>)
+                    -> Result<(), r2d2::InitializationError> {
+        manager.discover_location(true);
+        manager.node_states(self.node_states.clone());
+        let state = manager.node_state();
+        let connects_on_demand = manager.connects_on_demand();
+        let pool = try!(r2d2::Pool::new(config, manager));
+        self.nodes.push(ClusterNode {
+            pool: pool,
+            state: state,
+            connects_on_demand: connects_on_demand
+        });
+
+        return Ok(());
+    }
+
+    /// Returns number of nodes in the cluster.
+    pub fn len(&self) -> usize {
+        return self.nodes.len();
+    }
+
+    /// Indicates if the cluster has no nodes.
+    pub fn is_empty(&self) -> bool {
+        return self.nodes.is_empty();
+    }
+
+    /// Returns state of a node with provided index,
+    /// nodes are indexed in order they were added.
+    pub fn node_state(&self, index: usize) -> Option<NodeState> {
+        return self.nodes.get(index).map(|node| node.state.clone());
+    }
+
+    /// Returns number of nodes which are up.
+    pub fn nodes_up(&self) -> usize {
+        return self.nodes.iter().filter(|node| node.state.is_up()).count();
+    }
+
+    /// Returns a connection to the first node of a query plan. If a connection to the node
+    /// can't be taken, next nodes are tried. An error is returned if all nodes are down
+    /// or none of them provided a connection.
+    pub fn get_connection(&self) -> error::Result<PooledSession<T, X>> {
+        return self.get_connection_for(None, None);
+    }
+
+    /// Does the same as `get_connection` but for a request of provided consistency
+    /// and routing key, e.g. datacenter-aware policies don't return remote nodes
+    /// for `LOCAL_*` levels and token-aware ones prefer replicas of the routing key.
+    pub fn get_connection_for(&self, consistency: Option<&Consistency>, routing_key: Option<&[u8]>)
+        -> error::Result<PooledSession<T, X>> {
+        let mut last_error = None;
+        for index in try!(self.query_plan(consistency, routing_key)) {
+            match self.nodes[index].pool.get() {
+                Ok(connection) => return Ok(connection),
+                Err(err) => last_error = Some(err)
+            }
+        }
+
+        return Err(no_connection_error(last_error));
+    }
+
+    /// Executes a query on the next node which is up. Idempotent queries are executed
+    /// speculatively if speculative execution policy is set.
+    pub fn query(&self, query: Query, with_tracing: bool, with_warnings: bool)
+        -> error::Result<Frame> {
+        return self.query_with_info(query, with_tracing, with_warnings).map(|(frame, _)| frame);
+    }
+
+    /// The method does the same as `query` but apart of a response frame
+    /// it returns an information about the request execution. `attempts` of the
+    /// information is a number of executions which were started, including executions
+    /// which replaced failed ones.
+    pub fn query_with_info(&self, query: Query, with_tracing: bool, with_warnings: bool)
+        -> error::Result<(Frame, ExecutionInfo)> {
+        match self.speculative_execution_policy {
+            Some(ref policy) if query.is_idempotent() => {
+                return self.query_speculatively(policy.as_ref(), query, with_tracing, with_warnings);
+            },
+            _ => {}
+        }
+
+        let mut connection = try!(self.get_connection_for(Some(&query.get_consistency()), query.get_routing_key()));
+        return connection.query_with_info(query, with_tracing, with_warnings);
+    }
+
+    /// Starts the query on the first node of a query plan, and then on next nodes
+    /// in accordance to the policy until a response is received. Executions which
+    /// failed because of a node (i.e. not because of a server error) don't count,
+    /// if there are no running executions next one is started immediately.
+    /// Executions are run by a bounded pool of threads, the first one takes precedence
+    /// over queued executions and never waits for a busy pool. Once a response is received
+    /// the rest of executions are cancelled: the ones which haven't sent the query yet
+    /// don't send it and don't hold a connection, the ones which have already sent it
+    /// are abandoned and their connections return to pools once they're answered.
+    fn query_speculatively(&self,
+                           policy: &SpeculativeExecutionPolicy,
+                           query: Query,
+                           with_tracing: bool,
+                           with_warnings: bool)
+                           -> error::Result<(Frame, ExecutionInfo)> {
+        let mut plan = policy.new_plan();
+        let mut nodes = try!(self.query_plan(Some(&query.get_consistency()), query.get_routing_key())).into_iter();
+        let (sender, receiver) = mpsc::channel();
+        // once the plan is exhausted only running executions hold senders, so receiving
+        // fails instead of blocking if all of them are gone without an answer
+        let mut sender = Some(sender);
+        // executions which are still running are cancelled once the method returns
+        let cancellation = Cancellation::new();
+        let mut running = 0;
+        let mut started = 0;
+        let mut last_error = None;
+        let mut next_execution = None;
+
+        loop {
+            let speculative = next_execution.map_or(false, |at| at <= Instant::now());
+            if running == 0 || speculative {
+                let (index, execution_sender) = match (nodes.next(), sender.as_ref()) {
+                    (Some(index), Some(sender)) => (index, sender.clone()),
+                    _ if running == 0 => return Err(last_error.unwrap_or_else(|| no_connection_error(None))),
+                    _ => {
+                        sender = None;
+                        next_execution = None;
+                        continue;
+                    }
+                };
+
+                let pool = self.nodes[index].pool.clone();
+                let query = query.clone();
+                let cancelled = cancellation.flag();
+                let execution: Job = Box::new(move || {
+                    if cancelled.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        pool.get()
+                            .map_err(|err| no_connection_error(Some(err)))
+                            .and_then(|mut connection| {
+                                // the connection returns to the pool right away
+                                if cancelled.load(Ordering::SeqCst) {
+                                    return Err(error::Error::General("Execution is cancelled".to_string()));
+                                }
+                                connection.query_with_info(query, with_tracing, with_warnings)
+                            })
+                    })).unwrap_or_else(|_| Err(error::Error::General("Execution panicked".to_string())));
+                    // the receiver is gone if another execution has already answered
+                    let _ = execution_sender.send(result);
+                });
+                if started == 0 {
+                    self.executor.execute_first(execution);
+                } else {
+                    self.executor.execute(execution);
+                }
+                running += 1;
+                started += 1;
+                // a replacement of a failed execution keeps current schedule
+                if started == 1 || speculative {
+                    next_execution = plan.next_execution().map(|delay| Instant::now() + delay);
+                }
+            }
+
+            let received = match next_execution {
+                Some(at) => {
+                    let now = Instant::now();
+                    if at <= now {
+                        continue;
+                    }
+                    match receiver.recv_timeout(at - now) {
+                        Ok(result) => result,
+                        Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => {
+                            return Err(last_error.unwrap_or_else(|| no_connection_error(None)));
+                        }
+                    }
+                },
+                None => match receiver.recv() {
+                    Ok(result) => result,
+                    Err(_) => return Err(last_error.unwrap_or_else(|| no_connection_error(None)))
+                }
+            };
+            running -= 1;
+
+            match received {
+                Ok((frame, mut info)) => {
+                    info.attempts = started;
+                    return Ok((frame, info));
+                },
+                Err(error::Error::Server(err)) => return Err(error::Error::Server(err)),
+                Err(err) => last_error = Some(err)
+            }
+        }
+    }
+
+    /// Returns indexes of nodes in order they should be tried.
+    fn query_plan(&self, consistency: Option<&Consistency>, routing_key: Option<&[u8]>)
+        -> error::Result<Vec<usize>> {
+        if self.nodes.is_empty() {
+            return Err(error::Error::General("Cluster has no nodes".to_string()));
+        }
+
+        let states: Vec<NodeState> = self.nodes.iter().map(|node| node.state.clone()).collect();
+        let mut plan = self.load_balancing_policy.new_query_plan(&states, consistency, routing_key);
+
+        // downed nodes which are connected on demand are tried as a last resort, local ones first
+        let dc_local = consistency.map_or(false, |consistency| consistency.is_dc_local());
+        let mut downed: Vec<(usize, usize)> = self.nodes.iter()
+            .enumerate()
+            .filter(|&(_, node)| node.connects_on_demand && !node.state.is_up())
+            .filter_map(|(index, node)| match self.load_balancing_policy.distance(&node.state) {
+                NodeDistance::Local => Some((0, index)),
+                NodeDistance::Remote if !dc_local => Some((1, index)),
+                _ => None
+            })
+            .collect();
+        downed.sort();
+        plan.extend(downed.into_iter().map(|(_, index)| index));
+
+        return Ok(plan);
+    }
+}
+
+/// Flag which cancels executions of a request once it's dropped.
+struct Cancellation {
+    cancelled: Arc<AtomicBool>
+}
+
+impl Cancellation {
+    fn new() -> Cancellation {
+        return Cancellation { cancelled: Arc::new(AtomicBool::new(false)) };
+    }
+
+    fn flag(&self) -> Arc<AtomicBool> {
+        return self.cancelled.clone();
+    }
+}
+
+impl Drop for Cancellation {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+type Job = Box<FnOnce() + Send>;
+
+/// Bounded pool of threads. Threads are started on demand up to `max_threads`
+/// and stop once the pool is dropped and queued jobs are done.
+struct Executor {
+    shared: Arc<ExecutorShared>,
+    max_threads: usize
+}
+
+struct ExecutorShared {
+    state: Mutex<ExecutorState>,
+    job_added: Condvar
+}
+
+struct ExecutorState {
+    jobs: VecDeque<Job>,
+    threads: usize,
+    idle_threads: usize,
+    stopped: bool
+}
+
+impl Executor {
+    fn new(max_threads: usize) -> Executor {
+        let state = ExecutorState {
+            jobs: VecDeque::new(),
+            threads: 0,
+            idle_threads: 0,
+            stopped: false
+        };
+
+        return Executor {
+            shared: Arc::new(ExecutorShared {
+                state: Mutex::new(state),
+                job_added: Condvar::new()
+            }),
+            max_threads: cmp::max(max_threads, 1)
+        };
+    }
+
+    fn execute(&self, job: Job) {
+        let mut state = lock_executor_state(&self.shared.state);
+        state.jobs.push_back(job);
+
+        if state.idle_threads < state.jobs.len() && state.threads < self.max_threads {
+            state.threads += 1;
+            let shared = self.shared.clone();
+            thread::spawn(move || run_executor_thread(&shared));
+        }
+        self.shared.job_added.notify_one();
+    }
+
+    /// Runs the job ahead of queued ones. If all threads are busy and the pool is full,
+    /// the job runs on a thread of its own, so it never waits for stuck jobs.
+    fn execute_first(&self, job: Job) {
+        let mut state = lock_executor_state(&self.shared.state);
+        if state.idle_threads == 0 && state.threads >= self.max_threads {
+            drop(state);
+            thread::spawn(move || run_job(job));
+            return;
+        }
+
+        state.jobs.push_front(job);
+        if state.idle_threads == 0 {
+            state.threads += 1;
+            let shared = self.shared.clone();
+            thread::spawn(move || run_executor_thread(&shared));
+        }
+        self.shared.job_added.notify_one();
+    }
+}
+
+impl Drop for Executor {
+    fn drop(&mut self) {
+        lock_executor_state(&self.shared.state).stopped = true;
+        self.shared.job_added.notify_all();
+    }
+}
+
+fn lock_executor_state(state: &Mutex<ExecutorState>) -> MutexGuard<ExecutorState> {
+    return match state.lock() {
+        Ok(state) => state,
+        Err(poisoned) => poisoned.into_inner()
+    };
+}
+
+/// Runs a job, a panic of the job doesn't stop a thread which runs it.
+fn run_job(job: Job) {
+    if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+        error!("Job of speculative execution pool panicked");
+    }
+}
+
+/// Decrements number of threads of a pool once a thread stops, even if it panics.
+struct ExecutorThread<'a> {
+    shared: &'a ExecutorShared
+}
+
+impl<'a> Drop for ExecutorThread<'a> {
+    fn drop(&mut self) {
+        lock_executor_state(&self.shared.state).threads -= 1;
+    }
+}
+
+fn run_executor_thread(shared: &ExecutorShared) {
+    let _thread = ExecutorThread { shared: shared };
+    let mut state = lock_executor_state(&shared.state);
+
+    loop {
+        match state.jobs.pop_front() {
+            Some(job) => {
+                drop(state);
+                run_job(job);
+                state = lock_executor_state(&shared.state);
+            },
+            None if state.stopped => return,
+            None => {
+                state.idle_threads += 1;
+                state = match shared.job_added.wait(state) {
+                    Ok(state) => state,
+                    Err(poisoned) => poisoned.into_inner()
+                };
+                state.idle_threads -= 1;
+            }
+        }
+    }
+}
+
+fn no_connection_error(err: Option<r2d2::GetTimeout>) -> error::Error {
+    return match err {
+        Some(err) => error::Error::General(format!("No connection available: {}", err)),
+        None => error::Error::General("No nodes available, all of them are down or ignored".to_string())
+    };
+}
+
+impl<T: Authenticator + Send + Sync + 'static, X: CDRSTransport + 'static> Default for Cluster<T, X> {
+    fn default() -> Cluster<T, X> {
+        return Cluster::new();
+    }
+}
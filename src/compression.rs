@@ -13,7 +13,7 @@ type Result<T> = result::Result<T, CompressionError>;
 #[derive(Debug)]
 pub enum CompressionError {
     /// Snappy error.
-    Snappy(Box<Error>),
+    Snappy(Box<Error + Send + Sync>),
     /// Lz4 error.
     Lz4(String)
 }
@@ -1,56 +1,350 @@
 //! This modules contains an implementation of [r2d2](https://github.com/sfackler/r2d2)
 //! functionality of connection pools. To get more details about creating r2d2 pools
 //! please refer to original documentation.
-use client::{CDRS, Session, QueryBuilder};
+use std::io;
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use client::{CDRS, Heartbeat, Session, QueryBuilder};
+use types::IntoRustByName;
 use error::{Error as CError};
 use authenticators::Authenticator;
 use compression::Compression;
+use consistency::Consistency;
+use node_state::{NodeLocation, NodeState, NodeStates};
+use reconnection_policy::{ReconnectionPolicy, ReconnectionSchedule};
+use transport::CDRSTransport;
 use r2d2;
 
-#[cfg(not(feature = "ssl"))]
-use transport::Transport;
-#[cfg(feature = "ssl")]
-use transport_ssl::Transport;
-
 /// [r2d2](https://github.com/sfackler/r2d2) `ManageConnection`.
-pub struct ConnectionManager<T> {
-    transport: Transport,
+///
+/// Connections which failed because of IO or protocol errors are reported as broken,
+/// so the pool drops them. Connections are validated by `OPTIONS` request unless
+/// validation query is set.
+///
+/// All connections made by a manager share up/down state of a node they are connected to.
+/// The node is marked down when a connection can't be established or a heartbeat fails,
+/// and it's marked up again once a new connection is established.
+///
+/// If reconnection policy is set, a downed node isn't connected on demand. Instead
+/// new connections fail immediately while reconnection attempts are made in background
+/// in accordance to the policy, and the node is marked up once an attempt succeeds.
+pub struct ConnectionManager<T, X> {
+    transport: Arc<X>,
     authenticator: T,
-    compression: Compression
+    compression: Compression,
+    validation_query: Option<String>,
+    heartbeat: Option<Heartbeat>,
+    request_timeout: Option<Duration>,
+    schema_agreement_timeout: Option<Duration>,
+    log_warnings: bool,
+    serial_consistency: Option<Consistency>,
+    cas_timeout_retries: u32,
+    keyspace: Option<String>,
+    node_state: NodeState,
+    node_states: Option<NodeStates>,
+    reconnection_policy: Option<Arc<ReconnectionPolicy>>,
+    reconnecting: Arc<AtomicBool>,
+    discover_location: bool
 }
 
-impl<T: Authenticator + Send + Sync + 'static> ConnectionManager<T> {
+/// Query which returns datacenter and rack of a node it's executed on.
+pub const LOCATION_QUERY: &'static str = "SELECT data_center, rack FROM system.local";
+
+impl<T: Authenticator + Send + Sync + 'static, X: CDRSTransport + 'static> ConnectionManager<T, X> {
     /// Creates a new instance of `ConnectionManager`.
     /// It requires transport, authenticator and compression as inputs.
-    pub fn new(transport: Transport, authenticator: T, compression: Compression)
-        -> ConnectionManager<T> {
+    pub fn new(transport: X, authenticator: T, compression: Compression)
+        -> ConnectionManager<T, X> {
         ConnectionManager {
-            transport: transport,
+            transport: Arc::new(transport),
             authenticator: authenticator,
-            compression: compression
+            compression: compression,
+            validation_query: None,
+            heartbeat: None,
+            request_timeout: None,
+            schema_agreement_timeout: None,
+            log_warnings: false,
+            serial_consistency: None,
+            cas_timeout_retries: 0,
+            keyspace: None,
+            node_state: NodeState::new(),
+            node_states: None,
+            reconnection_policy: None,
+            reconnecting: Arc::new(AtomicBool::new(false)),
+            discover_location: false
+        }
+    }
+
+    /// Sets a query which is used to validate connections instead of `OPTIONS` request,
+    /// e.g. `SELECT now() FROM system.local`. `None` restores validation via `OPTIONS`.
+    pub fn validation_query(&mut self, query: Option<String>) -> &mut Self {
+        self.validation_query = query;
+        self
+    }
+
+    /// Enables heartbeats on new connections, i.e. a connection sends `OPTIONS` request
+    /// whenever it has been idle for provided interval. Heartbeats of all connections are
+    /// sent from a single thread (see `Heartbeat`). `None` disables heartbeats.
+    pub fn heartbeat_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.heartbeat = interval.map(Heartbeat::new);
+        self
+    }
+
+    /// Sets default timeout of requests made via new connections (see `Session::request_timeout`),
+    /// so a hung node doesn't block callers forever. `None` means that connections wait
+    /// for responses indefinitely.
+    pub fn request_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Enables waiting for schema agreement after schema changes made via new connections
+    /// (see `Session::schema_agreement_timeout`). `None` disables waiting.
+    pub fn schema_agreement_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.schema_agreement_timeout = timeout;
+        self
+    }
+
+    /// Enables logging of server warnings by new connections (see `Session::log_warnings`).
+    pub fn log_warnings(&mut self, log_warnings: bool) -> &mut Self {
+        self.log_warnings = log_warnings;
+        self
+    }
+
+    /// Sets default serial consistency of conditional writes made via new connections
+    /// (see `Session::serial_consistency`).
+    pub fn serial_consistency(&mut self, serial_consistency: Option<Consistency>) -> &mut Self {
+        self.serial_consistency = serial_consistency;
+        self
+    }
+
+    /// Sets a number of retries of conditional writes which timed out during Paxos phase
+    /// for new connections (see `Session::cas_timeout_retries`).
+    pub fn cas_timeout_retries(&mut self, retries: u32) -> &mut Self {
+        self.cas_timeout_retries = retries;
+        self
+    }
+
+    /// Sets a keyspace which every new connection uses (see `CDRS::keyspace`), including
+    /// connections which replace broken ones after the node is reconnected. `None` means
+    /// that new connections don't use any keyspace.
+    pub fn keyspace(&mut self, keyspace: Option<String>) -> &mut Self {
+        self.keyspace = keyspace;
+        self
+    }
+
+    /// Sets a policy of background reconnection to a downed node.
+    /// `None` disables background reconnection, so the node is connected on demand.
+    pub fn reconnection_policy(&mut self, policy: Option<Arc<ReconnectionPolicy>>) -> &mut Self {
+        self.reconnection_policy = policy;
+        self
+    }
+
+    /// Indicates if a downed node is connected on demand, i.e. reconnection policy isn't set.
+    pub fn connects_on_demand(&self) -> bool {
+        self.reconnection_policy.is_none()
+    }
+
+    /// Enables discovery of datacenter and rack of a node. If the location isn't known yet,
+    /// it's queried from `system.local` once a new connection is established
+    /// (see `node_state().location()`). Failed discovery doesn't fail the connection.
+    pub fn discover_location(&mut self, discover: bool) -> &mut Self {
+        self.discover_location = discover;
+        self
+    }
+
+    /// Returns up/down state of a node the manager connects to.
+    pub fn node_state(&self) -> NodeState {
+        self.node_state.clone()
+    }
+
+    /// Sets states of all nodes of a cluster, so new connections ignore peers which are down
+    /// while they check schema agreement (see `Session::node_states`). State of the node
+    /// the manager connects to is added to them.
+    pub fn node_states(&mut self, node_states: NodeStates) -> &mut Self {
+        if let Ok(addr) = self.transport.peer_addr() {
+            node_states.insert(addr.ip(), self.node_state.clone());
+        }
+        self.node_states = Some(node_states);
+        self
+    }
+
+    /// Indicates if background reconnection to a downed node is in progress.
+    pub fn is_reconnecting(&self) -> bool {
+        self.reconnecting.load(Ordering::SeqCst)
+    }
+
+    fn start_session(&self) -> Result<Session<T, X>, CError> {
+        start_session(&*self.transport, &self.authenticator, self.compression, self.keyspace.clone())
+    }
+
+    /// Starts background reconnection unless the node is up, reconnection policy
+    /// isn't set or reconnection has already been started.
+    fn schedule_reconnection(&self) {
+        if self.node_state.is_up() {
+            return;
+        }
+        let policy = match self.reconnection_policy {
+            Some(ref policy) => policy,
+            None => return
+        };
+        if self.reconnecting.swap(true, Ordering::SeqCst) {
+            return;
         }
+
+        let schedule = policy.new_schedule();
+        let transport = Arc::downgrade(&self.transport);
+        let authenticator = self.authenticator.clone();
+        let compression = self.compression;
+        let node_state = self.node_state.clone();
+        let reconnecting = self.reconnecting.clone();
+        thread::spawn(move || {
+            run_reconnection(schedule, transport, authenticator, compression, &node_state);
+            reconnecting.store(false, Ordering::SeqCst);
+        });
     }
 }
 
-impl<T: Authenticator + Send + Sync + 'static> r2d2::ManageConnection for ConnectionManager<T> {
-    type Connection = Session<T>;
+impl<T: Authenticator + Send + Sync + 'static, X: CDRSTransport + 'static> r2d2::ManageConnection
+    for ConnectionManager<T, X> {
+    type Connection = Session<T, X>;
     type Error = CError;
 
     fn connect(&self) -> Result<Self::Connection, Self::Error> {
-        let transport = try!(self.transport.try_clone());
-        let compression = self.compression.clone();
-        let cdrs = CDRS::new(transport, self.authenticator.clone());
+        if self.reconnection_policy.is_some() && !self.node_state.is_up() {
+            self.schedule_reconnection();
+            return Err(CError::Io(io::Error::new(io::ErrorKind::NotConnected,
+                                                 "Node is down, reconnection is scheduled")));
+        }
 
-        cdrs.start(compression)
+        let mut session = match self.start_session() {
+            Ok(session) => session,
+            // e.g. the keyspace doesn't exist, but the node is up
+            Err(CError::Server(err)) => return Err(CError::Server(err)),
+            Err(err) => {
+                self.node_state.mark_down();
+                self.schedule_reconnection();
+                return Err(err);
+            }
+        };
+
+        // location discovery is bounded by the timeout as well
+        session.request_timeout(self.request_timeout);
+        if self.discover_location && self.node_state.datacenter().is_none() {
+            match query_location(&mut session) {
+                Ok(location) => self.node_state.set_location(location),
+                Err(err) => debug!("Node location discovery failed: {:?}", err)
+            }
+        }
+
+        session.node_state(self.node_state.clone());
+        session.node_states(self.node_states.clone());
+        if let Some(ref heartbeat) = self.heartbeat {
+            heartbeat.register(&session);
+        }
+        session.schema_agreement_timeout(self.schema_agreement_timeout);
+        session.log_warnings(self.log_warnings);
+        session.serial_consistency(self.serial_consistency.clone());
+        session.cas_timeout_retries(self.cas_timeout_retries);
+        self.node_state.mark_up();
+
+        Ok(session)
     }
 
     fn is_valid(&self, connection: &mut Self::Connection) -> Result<(), Self::Error> {
-        let query = QueryBuilder::new("SELECT * FROM system.peers;").finalize();
+        if connection.is_broken() {
+            self.schedule_reconnection();
+            return Err(CError::Io(io::Error::new(io::ErrorKind::NotConnected, "Connection is broken")));
+        }
+
+        let result = match self.validation_query {
+            Some(ref query) => {
+                let query = QueryBuilder::new(query.as_str()).finalize();
+                connection.query(query, false, false).map(|_| (()))
+            },
+            None => connection.get_options().map(|_| (()))
+        };
+
+        // the node answers, e.g. it was marked down because of a heartbeat which failed once
+        if result.is_ok() && self.node_state.mark_up() {
+            info!("Node is up again, a connection was validated");
+        }
 
-        connection.query(query, false, false).map(|_| (()))
+        result
     }
 
-    fn has_broken(&self, _connection: &mut Self::Connection) -> bool {
+    fn has_broken(&self, connection: &mut Self::Connection) -> bool {
+        if connection.is_broken() {
+            self.schedule_reconnection();
+            return true;
+        }
+
         false
     }
 }
+
+fn start_session<T: Authenticator + Send + Sync + 'static, X: CDRSTransport + 'static>(
+    transport: &X, authenticator: &T, compression: Compression, keyspace: Option<String>)
+    -> Result<Session<T, X>, CError> {
+    let transport = try!(transport.try_clone());
+    let mut cdrs = CDRS::new(transport, authenticator.clone());
+    cdrs.keyspace(keyspace);
+
+    cdrs.start(compression)
+}
+
+fn query_location<T: Authenticator, X: CDRSTransport>(session: &mut Session<T, X>)
+    -> Result<NodeLocation, CError> {
+    let query = QueryBuilder::new(LOCATION_QUERY).finalize();
+    let rows = try!(session.query(query, false, false)).get_body().into_rows().unwrap_or(vec![]);
+    let row = match rows.first() {
+        Some(row) => row,
+        None => return Err(CError::General("No rows in system.local".to_string()))
+    };
+
+    let datacenter: Option<String> = match row.get_by_name("data_center") {
+        Some(datacenter) => Some(try!(datacenter)),
+        None => None
+    };
+    let rack: Option<String> = match row.get_by_name("rack") {
+        Some(rack) => Some(try!(rack)),
+        None => None
+    };
+
+    return Ok(NodeLocation {
+        datacenter: datacenter,
+        rack: rack
+    });
+}
+
+/// Makes reconnection attempts until one of them succeeds, the node is marked up
+/// by somebody else or the manager is dropped.
+fn run_reconnection<T: Authenticator + Send + Sync + 'static, X: CDRSTransport + 'static>(
+    mut schedule: Box<ReconnectionSchedule>, transport: Weak<X>, authenticator: T,
+    compression: Compression, node_state: &NodeState) {
+    loop {
+        thread::sleep(schedule.next_delay());
+        if node_state.is_up() {
+            return;
+        }
+        let transport = match transport.upgrade() {
+            Some(transport) => transport,
+            None => return
+        };
+
+        // the attempt only checks that the node is reachable, so no keyspace is used
+        match start_session(&*transport, &authenticator, compression, None) {
+            Ok(mut session) => {
+                session.end();
+                if node_state.mark_up() {
+                    info!("Node is up again, reconnection succeeded");
+                }
+                return;
+            }
+            Err(err) => debug!("Reconnection attempt failed: {:?}", err)
+        }
+    }
+}
@@ -34,6 +34,17 @@ pub enum Consistency {
     LocalOne
 }
 
+impl Consistency {
+    /// Indicates if the consistency level is satisfied by replicas of a local datacenter
+    /// only, i.e. it's one of `LOCAL_ONE`, `LOCAL_QUORUM` and `LOCAL_SERIAL`.
+    pub fn is_dc_local(&self) -> bool {
+        return match *self {
+            Consistency::LocalOne | Consistency::LocalQuorum | Consistency::LocalSerial => true,
+            _ => false
+        };
+    }
+}
+
 impl IntoBytes for Consistency {
     fn into_cbytes(&self) -> Vec<u8> {
         return match self {
@@ -3,6 +3,7 @@ use std::fmt;
 use std::io;
 use std::result;
 use std::string::FromUtf8Error;
+use std::time::Duration;
 use frame::frame_error::CDRSError;
 use compression::CompressionError;
 use uuid::ParseError;
@@ -27,7 +28,10 @@ pub enum Error {
     /// Internal Compression/Decompression error
     Compression(CompressionError),
     /// Server error.
-    Server(CDRSError)
+    Server(CDRSError),
+    /// A response was not received within provided timeout. A request may still
+    /// be executed by a server, so it's safe to retry only idempotent requests.
+    Timeout(Duration)
 }
 
 impl fmt::Display for Error {
@@ -39,6 +43,7 @@ impl fmt::Display for Error {
             Error::FromUtf8(ref err) => write!(f, "FromUtf8Error error: {:?}", err),
             Error::UUIDParse(ref err) => write!(f, "UUIDParse error: {:?}", err),
             Error::General(ref err) => write!(f, "GeneralParsing error: {:?}", err),
+            Error::Timeout(ref timeout) => write!(f, "Timeout error: no response within {:?}", timeout),
         }
     }
 }
@@ -52,7 +57,8 @@ impl error::Error for Error {
             Error::FromUtf8(ref err) => err.description(),
             // FIXME: err.description not found in current scope, std::error::Error not satisfied
             Error::UUIDParse(_) => "UUID Parse Error",
-            Error::General(ref err) => err.as_str()
+            Error::General(ref err) => err.as_str(),
+            Error::Timeout(_) => "Request timed out"
         }
     }
 }
@@ -0,0 +1,83 @@
+//! The module contains an information about request execution which is returned
+//! by `Session` along with a response frame.
+use std::net;
+use std::time::Duration;
+use uuid::Uuid;
+
+use consistency::Consistency;
+use frame::{Frame, Opcode};
+use frame::frame_result::{ResultKind, RowsMetadataFlag};
+use types::{CBytes, try_i32_from_bytes};
+use IntoBytes;
+
+/// Information about execution of a single request.
+#[derive(Debug, Clone)]
+pub struct ExecutionInfo {
+    /// Warnings returned by a server, e.g. about reading too many tombstones
+    /// or about too big batches.
+    pub warnings: Vec<String>,
+    /// Tracing id if the request was made with tracing flag.
+    pub tracing_id: Option<Uuid>,
+    /// Address of a node which served the request.
+    pub node: Option<net::SocketAddr>,
+    /// Number of executions which were started to get the response, including the ones which
+    /// replaced failed executions. It's always `1` for requests made via `Session`,
+    /// see `Cluster::query_with_info` for speculative executions.
+    pub attempts: u32,
+    /// Consistency level the request was made with. `None` for requests
+    /// which don't have consistency level, e.g. `prepare`. Achieved consistency
+    /// isn't reported: servers don't return it in successful responses and the driver
+    /// never lowers consistency on retries, so a successful request achieved this level.
+    pub consistency: Option<Consistency>,
+    /// Paging state which should be used to fetch next page of rows.
+    pub paging_state: Option<CBytes>,
+    /// Time spent between sending the request and receiving the response.
+    pub latency: Duration,
+    /// Indicates if all nodes agreed on a schema version after a schema change.
+    /// `None` if the response isn't a schema change or the agreement wasn't awaited
+    /// (see `Session::schema_agreement_timeout`).
+    pub schema_in_agreement: Option<bool>
+}
+
+impl ExecutionInfo {
+    /// Creates new execution info basing on a response frame.
+    pub fn new(response: &Frame,
+        node: Option<net::SocketAddr>,
+        consistency: Option<Consistency>,
+        latency: Duration) -> ExecutionInfo {
+        return ExecutionInfo {
+            warnings: response.warnings(),
+            tracing_id: response.tracing_id(),
+            node: node,
+            attempts: 1,
+            consistency: consistency,
+            paging_state: paging_state(response),
+            latency: latency,
+            schema_in_agreement: None
+        };
+    }
+}
+
+/// Reads paging state of a `Rows` result. The paging state precedes column specs
+/// in rows metadata, so neither column specs nor rows are parsed.
+fn paging_state(response: &Frame) -> Option<CBytes> {
+    if response.opcode != Opcode::Result || !response.body.starts_with(&ResultKind::Rows.into_cbytes()) {
+        return None;
+    }
+
+    // result kind, flags and columns count
+    let body = &response.body;
+    let flags = match body.get(4..8).map(|bytes| try_i32_from_bytes(bytes.to_vec())) {
+        Some(Ok(flags)) => flags,
+        _ => return None
+    };
+    if !RowsMetadataFlag::has_has_more_pages(flags) {
+        return None;
+    }
+    let len = match body.get(12..16).map(|bytes| try_i32_from_bytes(bytes.to_vec())) {
+        Some(Ok(len)) if len >= 0 => len as usize,
+        _ => return None
+    };
+
+    return body.get(16..16 + len).map(|bytes| CBytes::new(bytes.to_vec()));
+}
@@ -0,0 +1,75 @@
+//! The module contains a codec which splits a byte stream into frames. It's used
+//! by asynchronous client on top of `tokio_codec::Framed`.
+use std::io::Cursor;
+
+use bytes::BytesMut;
+use tokio_codec::{Decoder, Encoder};
+
+use IntoBytes;
+use compression::Compression;
+use error;
+use frame::*;
+use frame::parser::parse_raw_frame;
+use types::from_bytes;
+
+/// Number of frame header bytes, i.e. bytes which precede a body.
+const HEADER_LEN: usize = VERSION_LEN + FLAG_LEN + STREAM_LEN + OPCODE_LEN + LENGTH_LEN;
+
+/// Codec which decodes frames sent by a server and encodes frames sent by a client.
+/// Decoded frames are decompressed in accordance to the codec's compression.
+/// Frames of type `error` are decoded as normal frames, i.e. they aren't converted
+/// into `Err`, so the codec could be used by servers as well.
+#[derive(Debug, Clone)]
+pub struct FrameCodec {
+    compression: Compression
+}
+
+impl FrameCodec {
+    /// Creates new codec which uses provided compression.
+    pub fn new(compression: Compression) -> FrameCodec {
+        return FrameCodec { compression: compression };
+    }
+
+    /// Returns compression which is used by the codec.
+    pub fn get_compression(&self) -> Compression {
+        return self.compression;
+    }
+
+    /// Sets compression which should be used for decoding of next frames.
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = compression;
+    }
+}
+
+impl Decoder for FrameCodec {
+    type Item = Frame;
+    type Error = error::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> error::Result<Option<Frame>> {
+        if src.len() < HEADER_LEN {
+            return Ok(None);
+        }
+
+        let length_start = HEADER_LEN - LENGTH_LEN;
+        let body_len = from_bytes(src[length_start..HEADER_LEN].to_vec()) as usize;
+        let frame_len = HEADER_LEN + body_len;
+        if src.len() < frame_len {
+            src.reserve(frame_len - src.len());
+            return Ok(None);
+        }
+
+        let frame_bytes = src.split_to(frame_len);
+        let mut cursor = Cursor::new(frame_bytes.to_vec());
+        return parse_raw_frame(&mut cursor, &self.compression).map(Some);
+    }
+}
+
+impl Encoder for FrameCodec {
+    type Item = Frame;
+    type Error = error::Error;
+
+    fn encode(&mut self, frame: Frame, dst: &mut BytesMut) -> error::Result<()> {
+        dst.extend_from_slice(frame.into_cbytes().as_slice());
+        return Ok(());
+    }
+}
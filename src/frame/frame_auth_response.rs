@@ -1,10 +1,12 @@
+use std::collections::HashMap;
+use std::io::Cursor;
 use types::CBytes;
-use IntoBytes;
+use {IntoBytes, FromCursor};
 use frame::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BodyReqAuthResponse {
-    data: CBytes
+    pub data: CBytes
 }
 
 impl BodyReqAuthResponse {
@@ -19,6 +21,12 @@ impl IntoBytes for BodyReqAuthResponse {
     }
 }
 
+impl FromCursor for BodyReqAuthResponse {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BodyReqAuthResponse {
+        return BodyReqAuthResponse::new(CBytes::from_cursor(&mut cursor));
+    }
+}
+
 // Frame implementation related to BodyReqStartup
 
 impl Frame {
@@ -39,7 +47,8 @@ impl Frame {
             body: body.into_cbytes(),
             // for request frames it's always None
             tracing_id: None,
-            warnings: vec![]
+            warnings: vec![],
+            custom_payload: HashMap::new()
         };
     }
 }
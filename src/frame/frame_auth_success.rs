@@ -1,5 +1,6 @@
-use FromCursor;
+use {FromCursor, IntoBytes};
 use std::io::Cursor;
+use types::to_int;
 
 /// `BodyReqAuthSuccess` is a frame that represents a successfull authentication response.
 #[derive(Debug)]
@@ -10,3 +11,10 @@ impl FromCursor for BodyReqAuthSuccess {
         return BodyReqAuthSuccess {};
     }
 }
+
+impl IntoBytes for BodyReqAuthSuccess {
+    fn into_cbytes(&self) -> Vec<u8> {
+        // [bytes] token, authenticators of CDRS don't use it
+        return to_int(-1);
+    }
+}
@@ -1,5 +1,5 @@
 use std::io::Cursor;
-use FromCursor;
+use {FromCursor, IntoBytes};
 use types::CString;
 
 /// A server authentication challenge.
@@ -15,3 +15,9 @@ impl FromCursor for BodyResAuthenticate {
         };
     }
 }
+
+impl IntoBytes for BodyResAuthenticate {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return self.data.into_cbytes();
+    }
+}
@@ -0,0 +1,209 @@
+#![warn(missing_docs)]
+//! Contains Batch Frame related functionality.
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use super::*;
+use consistency::Consistency;
+use {AsByte, IntoBytes, FromCursor};
+use error;
+use types::*;
+use types::value::*;
+
+const WITH_SERIAL_CONSISTENCY: u8 = 0x10;
+const WITH_DEFAULT_TIMESTAMP: u8 = 0x20;
+
+/// Structure which represents body of Batch request
+#[derive(Debug, Clone)]
+pub struct BodyReqBatch {
+    /// Batch type.
+    pub batch_type: BatchType,
+    /// Array of queries which will be executed within the batch.
+    pub queries: Vec<BatchQuery>,
+    /// Cassandra consistency level.
+    pub consistency: Consistency,
+    /// Serial `Consistency`.
+    pub serial_consistency: Option<Consistency>,
+    /// Timestamp.
+    pub timestamp: Option<i64>
+}
+
+impl BodyReqBatch {
+    fn flags_as_byte(&self) -> u8 {
+        let mut flags = 0;
+        if self.serial_consistency.is_some() {
+            flags = flags | WITH_SERIAL_CONSISTENCY;
+        }
+        if self.timestamp.is_some() {
+            flags = flags | WITH_DEFAULT_TIMESTAMP;
+        }
+        return flags;
+    }
+}
+
+impl IntoBytes for BodyReqBatch {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+
+        v.push(self.batch_type.as_byte());
+        v.extend_from_slice(to_short(self.queries.len() as u64).as_slice());
+        for query in self.queries.iter() {
+            v.extend_from_slice(query.into_cbytes().as_slice());
+        }
+        v.extend_from_slice(self.consistency.into_cbytes().as_slice());
+        v.push(self.flags_as_byte());
+        if let Some(ref serial_consistency) = self.serial_consistency {
+            v.extend_from_slice(serial_consistency.into_cbytes().as_slice());
+        }
+        if let Some(timestamp) = self.timestamp {
+            v.extend_from_slice(i_to_n_bytes(timestamp, 8).as_slice());
+        }
+
+        return v;
+    }
+}
+
+impl BodyReqBatch {
+    /// Decodes a body of batch request. Unlike other request bodies it may be
+    /// malformed in a way which is detected, i.e. it may have unknown batch type.
+    pub fn try_from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> error::Result<BodyReqBatch> {
+        let batch_type = try!(BatchType::try_from_byte(cursor_next_value(&mut cursor, 1)[0]));
+        let n = CIntShort::from_cursor(&mut cursor);
+        let queries = (0..n).map(|_| BatchQuery::from_cursor(&mut cursor)).collect();
+        let consistency = Consistency::from_cursor(&mut cursor);
+        let flags = cursor_next_value(&mut cursor, 1)[0];
+        let serial_consistency = if flags & WITH_SERIAL_CONSISTENCY != 0 {
+            Some(Consistency::from_cursor(&mut cursor))
+        } else {
+            None
+        };
+        let timestamp = if flags & WITH_DEFAULT_TIMESTAMP != 0 {
+            Some(from_i_bytes(cursor_next_value(&mut cursor, 8)))
+        } else {
+            None
+        };
+
+        return Ok(BodyReqBatch {
+            batch_type: batch_type,
+            queries: queries,
+            consistency: consistency,
+            serial_consistency: serial_consistency,
+            timestamp: timestamp
+        });
+    }
+}
+
+/// Batch type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchType {
+    /// The batch will be "logged". This is equivalent to a
+    /// normal CQL3 batch statement.
+    Logged,
+    /// The batch will be "unlogged".
+    Unlogged,
+    /// The batch will be a "counter" batch (and non-counter
+    /// statements will be rejected).
+    Counter
+}
+
+impl AsByte for BatchType {
+    fn as_byte(&self) -> u8 {
+        return match *self {
+            BatchType::Logged => 0,
+            BatchType::Unlogged => 1,
+            BatchType::Counter => 2
+        };
+    }
+}
+
+impl BatchType {
+    /// Converts a byte into batch type, unknown types are reported as errors.
+    pub fn try_from_byte(byte: u8) -> error::Result<BatchType> {
+        return match byte {
+            0 => Ok(BatchType::Logged),
+            1 => Ok(BatchType::Unlogged),
+            2 => Ok(BatchType::Counter),
+            _ => Err(error::Error::General(format!("Unexpected Cassandra batch type: {}", byte)))
+        };
+    }
+}
+
+/// Contains either a query string or an id of prepared query.
+#[derive(Debug, Clone)]
+pub enum BatchQuerySubject {
+    /// Id of prepared query.
+    PreparedId(CBytesShort),
+    /// CQL query string.
+    QueryString(CStringLong)
+}
+
+/// The structure that represents a single query of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchQuery {
+    /// Query string or prepared query id.
+    pub subject: BatchQuerySubject,
+    /// Query values.
+    pub values: Vec<Value>
+}
+
+impl IntoBytes for BatchQuery {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+
+        match self.subject {
+            BatchQuerySubject::QueryString(ref query) => {
+                v.push(0);
+                v.extend_from_slice(query.into_cbytes().as_slice());
+            },
+            BatchQuerySubject::PreparedId(ref id) => {
+                v.push(1);
+                v.extend_from_slice(id.into_cbytes().as_slice());
+            }
+        }
+
+        v.extend_from_slice(to_short(self.values.len() as u64).as_slice());
+        for value in self.values.iter() {
+            v.extend_from_slice(value.into_cbytes().as_slice());
+        }
+
+        return v;
+    }
+}
+
+impl FromCursor for BatchQuery {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BatchQuery {
+        let subject = match cursor_next_value(&mut cursor, 1)[0] {
+            0 => BatchQuerySubject::QueryString(CStringLong::from_cursor(&mut cursor)),
+            _ => BatchQuerySubject::PreparedId(CBytesShort::from_cursor(&mut cursor))
+        };
+        let n = CIntShort::from_cursor(&mut cursor);
+        let values = (0..n).map(|_| Value::from_cursor(&mut cursor)).collect();
+
+        return BatchQuery {
+            subject: subject,
+            values: values
+        };
+    }
+}
+
+impl Frame {
+    /// **Note:** This function should be used internally for building batch request frames.
+    pub fn new_req_batch(body: BodyReqBatch, flags: Vec<Flag>) -> Frame {
+        let version = Version::Request;
+        // sync client
+        let stream: u64 = 0;
+        let opcode = Opcode::Batch;
+
+        return Frame {
+            version: version,
+            flags: flags,
+            stream: stream,
+            opcode: opcode,
+            body: body.into_cbytes(),
+            // for request frames it's always None
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: HashMap::new()
+        };
+    }
+}
@@ -5,7 +5,7 @@ use std::io;
 use std::result;
 use consistency::Consistency;
 use types::*;
-use FromCursor;
+use {FromCursor, IntoBytes};
 use frame::Frame;
 
 /// CDRS specific `Result` which contains a [`Frame`] in case of `Ok` and `CDRSError` if `Err`.
@@ -40,6 +40,15 @@ impl FromCursor for CDRSError {
     }
 }
 
+impl IntoBytes for CDRSError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = to_int(self.error_code as i64);
+        v.extend_from_slice(self.message.into_cbytes().as_slice());
+        v.extend_from_slice(self.additional_info.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// Additional error info in accordance to [Cassandra protocol v4](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1011).
 #[derive(Debug)]
 pub enum AdditionalErrorInfo {
@@ -88,6 +97,55 @@ impl AdditionalErrorInfo {
             _ => unreachable!()
         };
     }
+
+    /// Returns an error code which corresponds to the additional info.
+    pub fn error_code(&self) -> CInt {
+        return match *self {
+            AdditionalErrorInfo::Server(_) => 0x0000,
+            AdditionalErrorInfo::Protocol(_) => 0x000A,
+            AdditionalErrorInfo::Authentication(_) => 0x0100,
+            AdditionalErrorInfo::Unavailable(_) => 0x1000,
+            AdditionalErrorInfo::Overloaded(_) => 0x1001,
+            AdditionalErrorInfo::IsBootstrapping(_) => 0x1002,
+            AdditionalErrorInfo::Truncate(_) => 0x1003,
+            AdditionalErrorInfo::WriteTimeout(_) => 0x1100,
+            AdditionalErrorInfo::ReadTimeout(_) => 0x1200,
+            AdditionalErrorInfo::ReadFailure(_) => 0x1300,
+            AdditionalErrorInfo::FunctionFailure(_) => 0x1400,
+            AdditionalErrorInfo::WriteFailure(_) => 0x1500,
+            AdditionalErrorInfo::Syntax(_) => 0x2000,
+            AdditionalErrorInfo::Unauthorized(_) => 0x2100,
+            AdditionalErrorInfo::Invalid(_) => 0x2200,
+            AdditionalErrorInfo::Config(_) => 0x2300,
+            AdditionalErrorInfo::AlreadyExists(_) => 0x2400,
+            AdditionalErrorInfo::Unprepared(_) => 0x2500
+        };
+    }
+}
+
+impl IntoBytes for AdditionalErrorInfo {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return match *self {
+            AdditionalErrorInfo::Unavailable(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::WriteTimeout(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::ReadTimeout(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::ReadFailure(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::FunctionFailure(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::WriteFailure(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::AlreadyExists(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::Unprepared(ref err) => err.into_cbytes(),
+            AdditionalErrorInfo::Server(_) |
+            AdditionalErrorInfo::Protocol(_) |
+            AdditionalErrorInfo::Authentication(_) |
+            AdditionalErrorInfo::Overloaded(_) |
+            AdditionalErrorInfo::IsBootstrapping(_) |
+            AdditionalErrorInfo::Truncate(_) |
+            AdditionalErrorInfo::Syntax(_) |
+            AdditionalErrorInfo::Unauthorized(_) |
+            AdditionalErrorInfo::Invalid(_) |
+            AdditionalErrorInfo::Config(_) => vec![]
+        };
+    }
 }
 
 /// Is used if error does not contain any additional info.
@@ -125,6 +183,15 @@ impl FromCursor for UnavailableError {
     }
 }
 
+impl IntoBytes for UnavailableError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.cl.into_cbytes();
+        v.extend_from_slice(to_int(self.required as i64).as_slice());
+        v.extend_from_slice(to_int(self.alive as i64).as_slice());
+        return v;
+    }
+}
+
 /// Timeout exception during a write request.
 #[derive(Debug)]
 pub struct WriteTimeoutError {
@@ -154,6 +221,16 @@ impl FromCursor for WriteTimeoutError {
     }
 }
 
+impl IntoBytes for WriteTimeoutError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.cl.into_cbytes();
+        v.extend_from_slice(to_int(self.received as i64).as_slice());
+        v.extend_from_slice(to_int(self.blockfor as i64).as_slice());
+        v.extend_from_slice(self.write_type.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// Timeout exception during a read request.
 #[derive(Debug)]
 pub struct ReadTimeoutError {
@@ -167,6 +244,17 @@ pub struct ReadTimeoutError {
 }
 
 impl ReadTimeoutError {
+    /// Creates new read timeout error.
+    pub fn new(cl: Consistency, received: CInt, blockfor: CInt, replica_has_responded: bool)
+        -> ReadTimeoutError {
+        return ReadTimeoutError {
+            cl: cl,
+            received: received,
+            blockfor: blockfor,
+            data_present: replica_has_responded as u8
+        };
+    }
+
     /// Shows if replica has resonded to a query.
     pub fn replica_has_responded(&self) -> bool {
         return self.data_present != 0;
@@ -188,6 +276,16 @@ impl FromCursor for ReadTimeoutError {
     }
 }
 
+impl IntoBytes for ReadTimeoutError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.cl.into_cbytes();
+        v.extend_from_slice(to_int(self.received as i64).as_slice());
+        v.extend_from_slice(to_int(self.blockfor as i64).as_slice());
+        v.push(self.data_present);
+        return v;
+    }
+}
+
 /// A non-timeout exception during a read request.
 #[derive(Debug)]
 pub struct ReadFailureError {
@@ -203,6 +301,21 @@ pub struct ReadFailureError {
 }
 
 impl ReadFailureError {
+    /// Creates new read failure error.
+    pub fn new(cl: Consistency,
+        received: CInt,
+        blockfor: CInt,
+        num_failures: CInt,
+        replica_has_responded: bool) -> ReadFailureError {
+        return ReadFailureError {
+            cl: cl,
+            received: received,
+            blockfor: blockfor,
+            num_failures: num_failures,
+            data_present: replica_has_responded as u8
+        };
+    }
+
     /// Shows if replica has resonded to a query.
     pub fn replica_has_responded(&self) -> bool {
         return self.data_present != 0;
@@ -226,6 +339,17 @@ impl FromCursor for ReadFailureError {
     }
 }
 
+impl IntoBytes for ReadFailureError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.cl.into_cbytes();
+        v.extend_from_slice(to_int(self.received as i64).as_slice());
+        v.extend_from_slice(to_int(self.blockfor as i64).as_slice());
+        v.extend_from_slice(to_int(self.num_failures as i64).as_slice());
+        v.push(self.data_present);
+        return v;
+    }
+}
+
 /// A (user defined) function failed during execution.
 #[derive(Debug)]
 pub struct FunctionFailureError {
@@ -250,6 +374,15 @@ impl FromCursor for FunctionFailureError {
     }
 }
 
+impl IntoBytes for FunctionFailureError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.keyspace.into_cbytes();
+        v.extend_from_slice(self.function.into_cbytes().as_slice());
+        v.extend_from_slice(self.arg_types.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// A non-timeout exception during a write request. [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1106)
 #[derive(Debug)]
 pub struct WriteFailureError {
@@ -282,8 +415,19 @@ impl FromCursor for WriteFailureError {
     }
 }
 
+impl IntoBytes for WriteFailureError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.cl.into_cbytes();
+        v.extend_from_slice(to_int(self.received as i64).as_slice());
+        v.extend_from_slice(to_int(self.blockfor as i64).as_slice());
+        v.extend_from_slice(to_int(self.num_failures as i64).as_slice());
+        v.extend_from_slice(self.write_type.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// Describes the type of the write that failed. [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1118)
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum WriteType {
     /// The write was a non-batched non-counter write
     Simple,
@@ -297,7 +441,16 @@ pub enum WriteType {
     Counter,
     /// The failure occured during the write to the batch log when a (logged) batch
     /// write was requested.
-    BatchLog
+    BatchLog,
+    /// The timeout occured during the Compare And Set write/update, i.e. during Paxos
+    /// phase of a lightweight transaction.
+    Cas,
+    /// The timeout occured when a write involves view update and failure to acquire
+    /// local view lock for key within timeout.
+    View,
+    /// The timeout occured when cdc_total_space_in_mb is exceeded when doing a write
+    /// to data tracked by cdc.
+    Cdc
 }
 
 impl FromCursor for WriteType {
@@ -308,11 +461,30 @@ impl FromCursor for WriteType {
             "UNLOGGED_BATCH" => WriteType::UnloggedBatch,
             "COUNTER" => WriteType::Counter,
             "BATCH_LOG" => WriteType::BatchLog,
+            "CAS" => WriteType::Cas,
+            "VIEW" => WriteType::View,
+            "CDC" => WriteType::Cdc,
             _ => unreachable!()
         };
     }
 }
 
+impl IntoBytes for WriteType {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let write_type = match *self {
+            WriteType::Simple => "SIMPLE",
+            WriteType::Batch => "BATCH",
+            WriteType::UnloggedBatch => "UNLOGGED_BATCH",
+            WriteType::Counter => "COUNTER",
+            WriteType::BatchLog => "BATCH_LOG",
+            WriteType::Cas => "CAS",
+            WriteType::View => "VIEW",
+            WriteType::Cdc => "CDC"
+        };
+        return CString::new(write_type.to_string()).into_cbytes();
+    }
+}
+
 /// The query attempted to create a keyspace or a table that was already existing.
 /// [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1140)
 #[derive(Debug)]
@@ -336,6 +508,14 @@ impl FromCursor for AlreadyExistsError {
     }
 }
 
+impl IntoBytes for AlreadyExistsError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.ks.into_cbytes();
+        v.extend_from_slice(self.table.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// Can be thrown while a prepared statement tries to be
 /// executed if the provided prepared statement ID is not known by
 /// this host. [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L1150)
@@ -347,10 +527,17 @@ pub struct UnpreparedError {
 
 impl FromCursor for UnpreparedError {
     fn from_cursor(mut cursor: &mut io::Cursor<Vec<u8>>) -> UnpreparedError {
-        let id = CBytes::from_cursor(&mut cursor);
+        // the id is [short bytes]
+        let id = CBytes::new(CBytesShort::from_cursor(&mut cursor).into_plain());
 
         return UnpreparedError {
             id: id
         };
     }
 }
+
+impl IntoBytes for UnpreparedError {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return CBytesShort::new(self.id.as_plain()).into_cbytes();
+    }
+}
@@ -0,0 +1,180 @@
+//! Contains Event Frame related functionality. Events are pushed by a server
+//! to clients which have registered for them via `register` request.
+use std::io::Cursor;
+
+use types::*;
+use frame::frame_register::{SimpleServerEvent, TOPOLOGY_CHANGE, STATUS_CHANGE, SCHEMA_CHANGE};
+use frame::frame_result::BodyResResultSchemaChange;
+use schema_change::SchemaChangeOutcome;
+use {IntoBytes, FromCursor};
+
+/// Stream id which is used by a server for event frames.
+pub const EVENT_STREAM_ID: u64 = 0xFFFF;
+
+/// The structure which represents a body of a frame of type `event`.
+#[derive(Debug, Clone)]
+pub struct BodyResEvent {
+    /// An event pushed by a server.
+    pub event: ServerEvent
+}
+
+impl FromCursor for BodyResEvent {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BodyResEvent {
+        return BodyResEvent { event: ServerEvent::from_cursor(&mut cursor) };
+    }
+}
+
+impl IntoBytes for BodyResEvent {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return self.event.into_cbytes();
+    }
+}
+
+/// Event pushed by a server.
+#[derive(Debug, Clone)]
+pub enum ServerEvent {
+    /// A node has been added to or removed from the cluster.
+    TopologyChange(TopologyChange),
+    /// A node has gone up or down.
+    StatusChange(StatusChange),
+    /// Schema has been changed.
+    SchemaChange(BodyResResultSchemaChange)
+}
+
+impl ServerEvent {
+    /// Returns type of the event.
+    pub fn event_type(&self) -> SimpleServerEvent {
+        return match *self {
+            ServerEvent::TopologyChange(_) => SimpleServerEvent::TopologyChange,
+            ServerEvent::StatusChange(_) => SimpleServerEvent::StatusChange,
+            ServerEvent::SchemaChange(_) => SimpleServerEvent::SchemaChange
+        };
+    }
+
+    /// Returns an outcome of a schema change if the event is a schema change.
+    pub fn schema_change(&self) -> Option<SchemaChangeOutcome> {
+        return match *self {
+            ServerEvent::SchemaChange(ref change) => Some(SchemaChangeOutcome::from(change.clone())),
+            _ => None
+        };
+    }
+}
+
+impl FromCursor for ServerEvent {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> ServerEvent {
+        let event_type = CString::from_cursor(&mut cursor);
+        return match event_type.as_str() {
+            TOPOLOGY_CHANGE => ServerEvent::TopologyChange(TopologyChange::from_cursor(&mut cursor)),
+            STATUS_CHANGE => ServerEvent::StatusChange(StatusChange::from_cursor(&mut cursor)),
+            SCHEMA_CHANGE => ServerEvent::SchemaChange(BodyResResultSchemaChange::from_cursor(&mut cursor)),
+            _ => {
+                error!("Unexpected Cassandra event type: {}", event_type.as_str());
+                panic!("Unexpected Cassandra event type: {}", event_type.as_str());
+            }
+        };
+    }
+}
+
+impl IntoBytes for ServerEvent {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = CString::new(self.event_type().as_string()).into_cbytes();
+        match *self {
+            ServerEvent::TopologyChange(ref change) => v.extend_from_slice(change.into_cbytes().as_slice()),
+            ServerEvent::StatusChange(ref change) => v.extend_from_slice(change.into_cbytes().as_slice()),
+            ServerEvent::SchemaChange(ref change) => v.extend_from_slice(change.into_cbytes().as_slice())
+        }
+        return v;
+    }
+}
+
+/// Type of topology change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TopologyChangeType {
+    /// A node has joined the cluster.
+    NewNode,
+    /// A node has left the cluster.
+    RemovedNode
+}
+
+/// A node has been added to or removed from the cluster.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopologyChange {
+    /// Type of the change.
+    pub change_type: TopologyChangeType,
+    /// Address of the node.
+    pub addr: CInet
+}
+
+impl FromCursor for TopologyChange {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> TopologyChange {
+        let change_type = match CString::from_cursor(&mut cursor).as_str() {
+            "NEW_NODE" => TopologyChangeType::NewNode,
+            "REMOVED_NODE" => TopologyChangeType::RemovedNode,
+            _ => unreachable!()
+        };
+        let addr = CInet::from_cursor(&mut cursor);
+
+        return TopologyChange {
+            change_type: change_type,
+            addr: addr
+        };
+    }
+}
+
+impl IntoBytes for TopologyChange {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let change_type = match self.change_type {
+            TopologyChangeType::NewNode => "NEW_NODE",
+            TopologyChangeType::RemovedNode => "REMOVED_NODE"
+        };
+        let mut v = CString::new(change_type.to_string()).into_cbytes();
+        v.extend_from_slice(self.addr.into_cbytes().as_slice());
+        return v;
+    }
+}
+
+/// Type of status change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatusChangeType {
+    /// A node is up.
+    Up,
+    /// A node is down.
+    Down
+}
+
+/// A node has gone up or down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusChange {
+    /// Type of the change.
+    pub change_type: StatusChangeType,
+    /// Address of the node.
+    pub addr: CInet
+}
+
+impl FromCursor for StatusChange {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> StatusChange {
+        let change_type = match CString::from_cursor(&mut cursor).as_str() {
+            "UP" => StatusChangeType::Up,
+            "DOWN" => StatusChangeType::Down,
+            _ => unreachable!()
+        };
+        let addr = CInet::from_cursor(&mut cursor);
+
+        return StatusChange {
+            change_type: change_type,
+            addr: addr
+        };
+    }
+}
+
+impl IntoBytes for StatusChange {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let change_type = match self.change_type {
+            StatusChangeType::Up => "UP",
+            StatusChangeType::Down => "DOWN"
+        };
+        let mut v = CString::new(change_type.to_string()).into_cbytes();
+        v.extend_from_slice(self.addr.into_cbytes().as_slice());
+        return v;
+    }
+}
@@ -1,14 +1,17 @@
+use std::collections::HashMap;
+use std::io::Cursor;
 use types::*;
 use frame::*;
-use super::super::IntoBytes;
+use super::super::{IntoBytes, FromCursor};
 use super::frame_query::ParamsReqQuery;
 
 /// The structure that represents a body of a frame of type `execute`.
+#[derive(Debug, Clone)]
 pub struct BodyReqExecute {
     /// Id of prepared query
-    id: CBytesShort,
+    pub id: CBytesShort,
     /// Query paramaters which have the same meaning as one for `query`
-    query_parameters: ParamsReqQuery
+    pub query_parameters: ParamsReqQuery
 }
 
 impl BodyReqExecute {
@@ -30,6 +33,14 @@ impl IntoBytes for BodyReqExecute {
     }
 }
 
+impl FromCursor for BodyReqExecute {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BodyReqExecute {
+        let id = CBytesShort::from_cursor(&mut cursor);
+        let query_parameters = ParamsReqQuery::from_cursor(&mut cursor);
+        return BodyReqExecute::new(id, query_parameters);
+    }
+}
+
 impl Frame {
     /// **Note:** This function should be used internally for building query request frames.
     pub fn new_req_execute(id: CBytesShort, query_parameters: ParamsReqQuery, flags: Vec<Flag>) -> Frame {
@@ -47,7 +58,8 @@ impl Frame {
             body: body.into_cbytes(),
             // for request frames it's always None
             tracing_id: None,
-            warnings: vec![]
+            warnings: vec![],
+            custom_payload: HashMap::new()
         };
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use super::super::IntoBytes;
 use frame::*;
 
@@ -37,7 +38,8 @@ impl Frame {
             body: body.into_cbytes(),
             // for request frames it's always None
             tracing_id: None,
-            warnings: vec![]
+            warnings: vec![],
+            custom_payload: HashMap::new()
         };
     }
 }
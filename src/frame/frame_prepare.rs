@@ -1,10 +1,14 @@
+use std::collections::HashMap;
+use std::io::Cursor;
 use types::*;
 use super::*;
-use super::super::IntoBytes;
+use super::super::{IntoBytes, FromCursor};
 
 /// Struct that represents a body of a frame of type `prepare`
+#[derive(Debug, Clone)]
 pub struct BodyReqPrepare {
-    query: CStringLong
+    /// Query string which should be prepared.
+    pub query: CStringLong
 }
 
 impl BodyReqPrepare {
@@ -22,6 +26,12 @@ impl IntoBytes for BodyReqPrepare {
     }
 }
 
+impl FromCursor for BodyReqPrepare {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BodyReqPrepare {
+        return BodyReqPrepare { query: CStringLong::from_cursor(&mut cursor) };
+    }
+}
+
 impl Frame {
     /// **Note:** This function should be used internally for building query request frames.
     pub fn new_req_prepare(query: String, flags: Vec<Flag>) -> Frame {
@@ -39,7 +49,8 @@ impl Frame {
             body: body.into_cbytes(),
             // for request frames it's always None
             tracing_id: None,
-            warnings: vec![]
+            warnings: vec![],
+            custom_payload: HashMap::new()
         };
     }
 }
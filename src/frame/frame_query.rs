@@ -1,13 +1,16 @@
 #![warn(missing_docs)]
 //! Contains Query Frame related functionality.
 // use self::frame::*;
+use std::io::Cursor;
+
 use super::*;
 use consistency::Consistency;
-use {AsByte, IntoBytes};
+use {AsByte, IntoBytes, FromCursor};
 use types::*;
 use types::value::*;
 
 /// Structure which represents body of Query request
+#[derive(Debug, Clone)]
 pub struct BodyReqQuery {
     /// Query string.
     pub query: CStringLong,
@@ -36,6 +39,9 @@ impl BodyReqQuery {
             if page_size.is_some() {
                 flags.push(QueryFlags::PageSize);
             }
+            if paging_state.is_some() {
+                flags.push(QueryFlags::WithPagingState);
+            }
             if serial_consistency.is_some() {
                 flags.push(QueryFlags::WithSerialConsistency);
             }
@@ -45,7 +51,7 @@ impl BodyReqQuery {
 
             let _values = values.unwrap_or(vec![]);
             let _page_size = page_size.unwrap_or(0);
-            let _paging_state = paging_state.map_or(vec![], |ps| ps.into_cbytes());
+            let _paging_state = paging_state.unwrap_or(CBytes::new(vec![]));
             let _serial_consistency = serial_consistency.unwrap_or(Consistency::Serial);
             let _timestamp = timestamp.unwrap_or(0);
 
@@ -56,7 +62,7 @@ impl BodyReqQuery {
                     flags: flags,
                     values: _values,
                     page_size: _page_size,
-                    paging_state: CBytes::new(_paging_state),
+                    paging_state: _paging_state,
                     serial_consistency: _serial_consistency,
                     timestamp: _timestamp
                 }
@@ -73,7 +79,20 @@ impl IntoBytes for BodyReqQuery {
     }
 }
 
+impl FromCursor for BodyReqQuery {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BodyReqQuery {
+        let query = CStringLong::from_cursor(&mut cursor);
+        let query_params = ParamsReqQuery::from_cursor(&mut cursor);
+
+        return BodyReqQuery {
+            query: query,
+            query_params: query_params
+        };
+    }
+}
+
 /// Parameters of Query request.
+#[derive(Debug, Clone)]
 pub struct ParamsReqQuery {
     /// Cassandra consistency level.
     pub consistency: Consistency,
@@ -102,7 +121,6 @@ impl ParamsReqQuery {
         return self.flags.iter().fold(0, |acc, flag| acc | flag.as_byte());
     }
 
-    #[allow(dead_code)]
     fn parse_query_flags(byte: u8) -> Vec<QueryFlags> {
         let mut flags: Vec<QueryFlags> = vec![];
 
@@ -135,28 +153,79 @@ impl ParamsReqQuery {
 impl IntoBytes for ParamsReqQuery {
     fn into_cbytes(&self) -> Vec<u8> {
         let mut v: Vec<u8> = vec![];
+        let flags = self.flags_as_byte();
 
         v.extend_from_slice(self.consistency.into_cbytes().as_slice());
-        v.push(self.flags_as_byte());
-        if QueryFlags::has_value(self.flags_as_byte()) {
+        v.push(flags);
+        if QueryFlags::has_value(flags) {
+            v.extend_from_slice(to_short(self.values.len() as u64).as_slice());
             for val in self.values.iter() {
                 v.extend_from_slice(val.into_cbytes().as_slice());
             }
         }
-        if QueryFlags::has_with_paging_state(self.flags_as_byte()) {
+        if QueryFlags::has_page_size(flags) {
+            v.extend_from_slice(to_int(self.page_size as i64).as_slice());
+        }
+        if QueryFlags::has_with_paging_state(flags) {
             v.extend_from_slice(self.paging_state.into_cbytes().as_slice());
         }
-        if QueryFlags::has_with_serial_consistency(self.flags_as_byte()) {
+        if QueryFlags::has_with_serial_consistency(flags) {
             v.extend_from_slice(self.serial_consistency.into_cbytes().as_slice());
         }
-        if QueryFlags::has_with_default_timestamp(self.flags_as_byte()) {
-            v.extend_from_slice(to_int(self.timestamp).as_slice());
+        if QueryFlags::has_with_default_timestamp(flags) {
+            v.extend_from_slice(i_to_n_bytes(self.timestamp, 8).as_slice());
         }
 
         return v;
     }
 }
 
+impl FromCursor for ParamsReqQuery {
+    /// **Note:** named values are not supported, values are always read
+    /// as positional ones.
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> ParamsReqQuery {
+        let consistency = Consistency::from_cursor(&mut cursor);
+        let flags_byte = cursor_next_value(&mut cursor, 1)[0];
+
+        let values = if QueryFlags::has_value(flags_byte) {
+            let n = CIntShort::from_cursor(&mut cursor);
+            (0..n).map(|_| Value::from_cursor(&mut cursor)).collect()
+        } else {
+            vec![]
+        };
+        let page_size = if QueryFlags::has_page_size(flags_byte) {
+            CInt::from_cursor(&mut cursor)
+        } else {
+            0
+        };
+        let paging_state = if QueryFlags::has_with_paging_state(flags_byte) {
+            CBytes::from_cursor(&mut cursor)
+        } else {
+            CBytes::new(vec![])
+        };
+        let serial_consistency = if QueryFlags::has_with_serial_consistency(flags_byte) {
+            Consistency::from_cursor(&mut cursor)
+        } else {
+            Consistency::Serial
+        };
+        let timestamp = if QueryFlags::has_with_default_timestamp(flags_byte) {
+            from_i_bytes(cursor_next_value(&mut cursor, 8))
+        } else {
+            0
+        };
+
+        return ParamsReqQuery {
+            consistency: consistency,
+            flags: ParamsReqQuery::parse_query_flags(flags_byte),
+            values: values,
+            page_size: page_size,
+            paging_state: paging_state,
+            serial_consistency: serial_consistency,
+            timestamp: timestamp
+        };
+    }
+}
+
 const FLAGS_VALUE: u8 = 0x01;
 const FLAGS_SKIP_METADATA: u8 = 0x02;
 const WITH_PAGE_SIZE: u8 = 0x04;
@@ -166,7 +235,7 @@ const WITH_DEFAULT_TIMESTAMP: u8 = 0x20;
 const WITH_NAME_FOR_VALUES: u8 = 0x40;
 
 /// Cassandra Query Flags.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum QueryFlags {
     /// If set indicates that Query Params contains value.
     Value,
@@ -297,7 +366,8 @@ impl Frame {
             body: body.into_cbytes(),
             // for request frames it's always None
             tracing_id: None,
-            warnings: vec![]
+            warnings: vec![],
+            custom_payload: HashMap::new()
         };
     }
 }
@@ -0,0 +1,100 @@
+//! Contains Register Frame related functionality.
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use super::*;
+use types::*;
+use {IntoBytes, FromCursor};
+
+/// Types of events which a client could be registered for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimpleServerEvent {
+    /// Events related to change in the cluster topology.
+    TopologyChange,
+    /// Events related to change of node status.
+    StatusChange,
+    /// Events related to schema change.
+    SchemaChange
+}
+
+impl SimpleServerEvent {
+    /// Returns a string representation of event type in accordance to the protocol.
+    pub fn as_string(&self) -> String {
+        return match *self {
+            SimpleServerEvent::TopologyChange => String::from(TOPOLOGY_CHANGE),
+            SimpleServerEvent::StatusChange => String::from(STATUS_CHANGE),
+            SimpleServerEvent::SchemaChange => String::from(SCHEMA_CHANGE)
+        };
+    }
+}
+
+impl<'a> From<&'a str> for SimpleServerEvent {
+    fn from(event: &'a str) -> SimpleServerEvent {
+        return match event {
+            TOPOLOGY_CHANGE => SimpleServerEvent::TopologyChange,
+            STATUS_CHANGE => SimpleServerEvent::StatusChange,
+            SCHEMA_CHANGE => SimpleServerEvent::SchemaChange,
+            _ => {
+                error!("Unexpected Cassandra event type: {}", event);
+                panic!("Unexpected Cassandra event type: {}", event);
+            }
+        };
+    }
+}
+
+/// Protocol name of topology change events.
+pub const TOPOLOGY_CHANGE: &'static str = "TOPOLOGY_CHANGE";
+/// Protocol name of status change events.
+pub const STATUS_CHANGE: &'static str = "STATUS_CHANGE";
+/// Protocol name of schema change events.
+pub const SCHEMA_CHANGE: &'static str = "SCHEMA_CHANGE";
+
+/// The structure which represents a body of a frame of type `register`.
+#[derive(Debug, Clone)]
+pub struct BodyReqRegister {
+    /// Types of events which a client wants to be notified about.
+    pub events: Vec<SimpleServerEvent>
+}
+
+impl IntoBytes for BodyReqRegister {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let events = self.events.iter().map(|event| event.as_string()).collect();
+        return CStringList::new(events).into_cbytes();
+    }
+}
+
+impl FromCursor for BodyReqRegister {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BodyReqRegister {
+        let events = CStringList::from_cursor(&mut cursor)
+            .into_plain()
+            .iter()
+            .map(|event| SimpleServerEvent::from(event.as_str()))
+            .collect();
+
+        return BodyReqRegister { events: events };
+    }
+}
+
+impl Frame {
+    /// Creates new frame of type `register`.
+    pub fn new_req_register(events: Vec<SimpleServerEvent>) -> Frame {
+        let version = Version::Request;
+        let flag = Flag::Ignore;
+        // sync client
+        let stream: u64 = 0;
+        let opcode = Opcode::Register;
+        let body = BodyReqRegister { events: events };
+
+        return Frame {
+            version: version,
+            flags: vec![flag],
+            stream: stream,
+            opcode: opcode,
+            body: body.into_cbytes(),
+            // for request frames it's always None
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: HashMap::new()
+        };
+    }
+}
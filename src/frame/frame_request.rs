@@ -0,0 +1,75 @@
+//! Contains a generalized representation of request frame bodies. It's used to
+//! decode frames sent by a client, e.g. by a mock server in tests.
+use std::io::Cursor;
+
+use FromCursor;
+use error;
+use frame::*;
+use frame::frame_auth_response::BodyReqAuthResponse;
+use frame::frame_batch::BodyReqBatch;
+use frame::frame_execute::BodyReqExecute;
+use frame::frame_prepare::BodyReqPrepare;
+use frame::frame_query::BodyReqQuery;
+use frame::frame_register::BodyReqRegister;
+use frame::frame_startup::BodyReqStartup;
+
+/// RequestBody is a generalized enum that represents all types of requests. Each of enum
+/// option wraps related body type.
+#[derive(Debug, Clone)]
+pub enum RequestBody {
+    Startup(BodyReqStartup),
+    Options,
+    Query(BodyReqQuery),
+    Prepare(BodyReqPrepare),
+    Execute(BodyReqExecute),
+    Register(BodyReqRegister),
+    Batch(BodyReqBatch),
+    AuthResponse(BodyReqAuthResponse)
+}
+
+impl RequestBody {
+    /// Decodes a body of request frame. Response opcodes and detectably malformed
+    /// bodies are reported as errors.
+    pub fn from(bytes: Vec<u8>, request_type: &Opcode) -> error::Result<RequestBody> {
+        let mut cursor: Cursor<Vec<u8>> = Cursor::new(bytes);
+        return match request_type {
+            &Opcode::Startup => Ok(RequestBody::Startup(BodyReqStartup::from_cursor(&mut cursor))),
+            &Opcode::Options => Ok(RequestBody::Options),
+            &Opcode::Query => Ok(RequestBody::Query(BodyReqQuery::from_cursor(&mut cursor))),
+            &Opcode::Prepare => Ok(RequestBody::Prepare(BodyReqPrepare::from_cursor(&mut cursor))),
+            &Opcode::Execute => Ok(RequestBody::Execute(BodyReqExecute::from_cursor(&mut cursor))),
+            &Opcode::Register => Ok(RequestBody::Register(BodyReqRegister::from_cursor(&mut cursor))),
+            &Opcode::Batch => BodyReqBatch::try_from_cursor(&mut cursor).map(RequestBody::Batch),
+            &Opcode::AuthResponse => {
+                Ok(RequestBody::AuthResponse(BodyReqAuthResponse::from_cursor(&mut cursor)))
+            },
+            // response frames
+            &Opcode::Error |
+            &Opcode::Ready |
+            &Opcode::Authenticate |
+            &Opcode::Supported |
+            &Opcode::Result |
+            &Opcode::Event |
+            &Opcode::AuthChallenge |
+            &Opcode::AuthSuccess => {
+                Err(error::Error::General(format!("{:?} is not a request opcode", request_type)))
+            }
+        };
+    }
+
+    /// Returns a query string of `query` and `prepare` requests.
+    pub fn query_string(&self) -> Option<&str> {
+        return match *self {
+            RequestBody::Query(ref body) => Some(body.query.as_str()),
+            RequestBody::Prepare(ref body) => Some(body.query.as_str()),
+            _ => None
+        };
+    }
+}
+
+impl Frame {
+    /// Decodes a body of request frame.
+    pub fn get_request_body(&self) -> error::Result<RequestBody> {
+        return RequestBody::from(self.body.clone(), &self.opcode);
+    }
+}
@@ -10,6 +10,7 @@ use frame::frame_supported::*;
 use frame::frame_auth_challenge::*;
 use frame::frame_authenticate::BodyResAuthenticate;
 use frame::frame_auth_success::BodyReqAuthSuccess;
+use frame::frame_event::BodyResEvent;
 use types::rows::Row;
 
 #[derive(Debug)]
@@ -25,7 +26,7 @@ pub enum ResponseBody {
     Prepare,
     Execute,
     Register,
-    Event,
+    Event(BodyResEvent),
     Batch,
     AuthChallenge(BodyResAuthChallenge),
     AuthResponse,
@@ -51,7 +52,7 @@ impl ResponseBody {
             &Opcode::Prepare => unreachable!(),
             &Opcode::Execute => unreachable!(),
             &Opcode::Register => unreachable!(),
-            &Opcode::Event => unreachable!(),
+            &Opcode::Event => ResponseBody::Event(BodyResEvent::from_cursor(&mut cursor)),
             &Opcode::Batch => unreachable!(),
             &Opcode::AuthChallenge => ResponseBody::AuthChallenge(BodyResAuthChallenge::from_cursor(&mut cursor)),
             // request frame
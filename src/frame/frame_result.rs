@@ -103,6 +103,21 @@ impl FromCursor for ResResultBody {
     }
 }
 
+impl IntoBytes for ResResultBody {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let (kind, body) = match *self {
+            ResResultBody::Void(ref body) => (ResultKind::Void, body.into_cbytes()),
+            ResResultBody::Rows(ref body) => (ResultKind::Rows, body.into_cbytes()),
+            ResResultBody::SetKeyspace(ref body) => (ResultKind::SetKeyspace, body.into_cbytes()),
+            ResResultBody::Prepared(ref body) => (ResultKind::Prepared, body.into_cbytes()),
+            ResResultBody::SchemaChange(ref body) => (ResultKind::SchemaChange, body.into_cbytes())
+        };
+        let mut v = kind.into_cbytes();
+        v.extend_from_slice(body.as_slice());
+        return v;
+    }
+}
+
 /// Body of a response of type Void
 #[derive(Debug)]
 pub struct BodyResResultVoid {}
@@ -127,6 +142,12 @@ impl FromCursor for BodyResResultVoid {
     }
 }
 
+impl IntoBytes for BodyResResultVoid {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return vec![];
+    }
+}
+
 /// It represents set keyspace result body. Body contains keyspace name.
 #[derive(Debug)]
 pub struct BodyResResultSetKeyspace {
@@ -149,6 +170,12 @@ impl FromCursor for BodyResResultSetKeyspace {
     }
 }
 
+impl IntoBytes for BodyResResultSetKeyspace {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return self.body.into_cbytes();
+    }
+}
+
 
 /// Structure that represents result of type [rows](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L533).
 #[derive(Debug)]
@@ -197,6 +224,19 @@ impl FromCursor for BodyResResultRows {
     }
 }
 
+impl IntoBytes for BodyResResultRows {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.metadata.into_cbytes();
+        v.extend_from_slice(to_int(self.rows_count as i64).as_slice());
+        for row in self.rows_content.iter() {
+            for value in row.iter() {
+                v.extend_from_slice(value.into_cbytes().as_slice());
+            }
+        }
+        return v;
+    }
+}
+
 /// Rows metadata.
 #[derive(Debug, Clone)]
 pub struct RowsMetadata {
@@ -244,6 +284,26 @@ impl FromCursor for RowsMetadata {
     }
 }
 
+impl IntoBytes for RowsMetadata {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+        v.extend_from_slice(to_int(self.flags as i64).as_slice());
+        v.extend_from_slice(to_int(self.columns_count as i64).as_slice());
+        if let Some(ref paging_state) = self.paging_state {
+            v.extend_from_slice(paging_state.into_cbytes().as_slice());
+        }
+        if let Some(ref global_table_space) = self.global_table_space {
+            for name in global_table_space.iter() {
+                v.extend_from_slice(name.into_cbytes().as_slice());
+            }
+        }
+        for col_spec in self.col_specs.iter() {
+            v.extend_from_slice(col_spec.into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
+
 const GLOBAL_TABLE_SPACE: i32 = 0x0001;
 const HAS_MORE_PAGES: i32 = 0x0002;
 const NO_METADATA: i32 = 0x0004;
@@ -352,8 +412,25 @@ impl ColSpec {
         }
 }
 
+impl IntoBytes for ColSpec {
+    /// Keyspace and table names are written only if they are set, i.e. if
+    /// Global_tables_spec flag is not set.
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+        if let Some(ref ksname) = self.ksname {
+            v.extend_from_slice(ksname.into_cbytes().as_slice());
+        }
+        if let Some(ref tablename) = self.tablename {
+            v.extend_from_slice(tablename.into_cbytes().as_slice());
+        }
+        v.extend_from_slice(self.name.into_cbytes().as_slice());
+        v.extend_from_slice(self.col_type.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// Cassandra data types which clould be returned by a server.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ColType {
     Custom,
     Ascii,
@@ -375,6 +452,8 @@ pub enum ColType {
     Time,
     Smallint,
     Tinyint,
+    /// `duration`, it's sent as a custom type by protocol v4.
+    Duration,
     List,
     Map,
     Set,
@@ -406,6 +485,7 @@ impl FromBytes for ColType {
             0x0012 => ColType::Time,
             0x0013 => ColType::Smallint,
             0x0014 => ColType::Tinyint,
+            0x0015 => ColType::Duration,
             0x0020 => ColType::List,
             0x0021 => ColType::Map,
             0x0022 => ColType::Set,
@@ -416,6 +496,44 @@ impl FromBytes for ColType {
     }
 }
 
+impl IntoBytes for ColType {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let id = match *self {
+            ColType::Custom => 0x0000,
+            ColType::Ascii => 0x0001,
+            ColType::Bigint => 0x0002,
+            ColType::Blob => 0x0003,
+            ColType::Boolean => 0x0004,
+            ColType::Counter => 0x0005,
+            ColType::Decimal => 0x0006,
+            ColType::Double => 0x0007,
+            ColType::Float => 0x0008,
+            ColType::Int => 0x0009,
+            ColType::Timestamp => 0x000B,
+            ColType::Uuid => 0x000C,
+            ColType::Varchar => 0x000D,
+            ColType::Varint => 0x000E,
+            ColType::Timeuuid => 0x000F,
+            ColType::Inet => 0x0010,
+            ColType::Date => 0x0011,
+            ColType::Time => 0x0012,
+            ColType::Smallint => 0x0013,
+            ColType::Tinyint => 0x0014,
+            ColType::Duration => 0x0015,
+            ColType::List => 0x0020,
+            ColType::Map => 0x0021,
+            ColType::Set => 0x0022,
+            ColType::Udt => 0x0030,
+            ColType::Tuple => 0x0031,
+            ColType::Null => {
+                error!("Null column type cannot be sent over the wire");
+                panic!("Null column type cannot be sent over the wire");
+            }
+        };
+        return to_short(id);
+    }
+}
+
 impl FromCursor for ColType {
     fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> ColType {
         let option_id_bytes = cursor_next_value(&mut cursor, SHORT_LEN as u64);
@@ -425,7 +543,7 @@ impl FromCursor for ColType {
 }
 
 /// Cassandra option that represent column type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ColTypeOption {
     /// Id refers to `ColType`.
     pub id: ColType,
@@ -447,6 +565,7 @@ impl FromCursor for ColTypeOption {
                 Some(ColTypeOptionValue::CList(Box::new(col_type)))
             },
             ColType::Udt => Some(ColTypeOptionValue::UdtType(CUdt::from_cursor(&mut cursor))),
+            ColType::Tuple => Some(ColTypeOptionValue::TupleType(CTuple::from_cursor(&mut cursor))),
             ColType::Map => {
                 let name_type = ColTypeOption::from_cursor(&mut cursor);
                 let value_type = ColTypeOption::from_cursor(&mut cursor);
@@ -462,19 +581,48 @@ impl FromCursor for ColTypeOption {
     }
 }
 
+impl IntoBytes for ColTypeOption {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.id.into_cbytes();
+        if let Some(ref value) = self.value {
+            v.extend_from_slice(value.into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
+
 /// Enum that represents all possible types of `value` of `ColTypeOption`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ColTypeOptionValue {
     CString(CString),
     ColType(ColType),
     CSet(Box<ColTypeOption>),
     CList(Box<ColTypeOption>),
     UdtType(CUdt),
+    TupleType(CTuple),
     CMap((Box<ColTypeOption>, Box<ColTypeOption>))
 }
 
+impl IntoBytes for ColTypeOptionValue {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return match *self {
+            ColTypeOptionValue::CString(ref class_name) => class_name.into_cbytes(),
+            ColTypeOptionValue::ColType(ref col_type) => col_type.into_cbytes(),
+            ColTypeOptionValue::CSet(ref item_type) => item_type.into_cbytes(),
+            ColTypeOptionValue::CList(ref item_type) => item_type.into_cbytes(),
+            ColTypeOptionValue::UdtType(ref udt) => udt.into_cbytes(),
+            ColTypeOptionValue::TupleType(ref tuple) => tuple.into_cbytes(),
+            ColTypeOptionValue::CMap((ref key_type, ref value_type)) => {
+                let mut v = key_type.into_cbytes();
+                v.extend_from_slice(value_type.into_cbytes().as_slice());
+                v
+            }
+        };
+    }
+}
+
 /// User defined type. [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L608)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CUdt {
     /// Keyspace name.
     pub ks: CString,
@@ -503,6 +651,45 @@ impl FromCursor for CUdt {
     }
 }
 
+impl IntoBytes for CUdt {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.ks.into_cbytes();
+        v.extend_from_slice(self.udt_name.into_cbytes().as_slice());
+        v.extend_from_slice(to_short(self.descriptions.len() as u64).as_slice());
+        for &(ref name, ref col_type) in self.descriptions.iter() {
+            v.extend_from_slice(name.into_cbytes().as_slice());
+            v.extend_from_slice(col_type.into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
+
+/// Tuple type. [Read more...](https://github.com/apache/cassandra/blob/trunk/doc/native_protocol_v4.spec#L621)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CTuple {
+    /// Types of tuple's elements.
+    pub types: Vec<ColTypeOption>
+}
+
+impl FromCursor for CTuple {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CTuple {
+        let n = from_bytes(cursor_next_value(&mut cursor, SHORT_LEN as u64));
+        let types = (0..n).map(|_| ColTypeOption::from_cursor(&mut cursor)).collect();
+
+        return CTuple { types: types };
+    }
+}
+
+impl IntoBytes for CTuple {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = to_short(self.types.len() as u64);
+        for col_type in self.types.iter() {
+            v.extend_from_slice(col_type.into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
+
 /// The structure represents a body of a response frame of type `prepared`
 #[derive(Debug)]
 pub struct BodyResResultPrepared {
@@ -529,6 +716,15 @@ impl FromCursor for BodyResResultPrepared {
     }
 }
 
+impl IntoBytes for BodyResResultPrepared {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.id.into_cbytes();
+        v.extend_from_slice(self.metadata.into_cbytes().as_slice());
+        v.extend_from_slice(self.result_metadata.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// The structure that represents metadata of prepared response.
 #[derive(Debug)]
 pub struct PreparedMetadata {
@@ -571,7 +767,27 @@ impl FromCursor for PreparedMetadata {
     }
 }
 
-#[derive(Debug)]
+impl IntoBytes for PreparedMetadata {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+        v.extend_from_slice(to_int(self.flags as i64).as_slice());
+        v.extend_from_slice(to_int(self.columns_count as i64).as_slice());
+        v.extend_from_slice(to_int(self.pk_count as i64).as_slice());
+        for idx in self.pk_indexes.iter() {
+            v.extend_from_slice(i_to_n_bytes(*idx as i64, SHORT_LEN).as_slice());
+        }
+        if let Some((ref keyspace, ref tablename)) = self.global_table_spec {
+            v.extend_from_slice(keyspace.into_cbytes().as_slice());
+            v.extend_from_slice(tablename.into_cbytes().as_slice());
+        }
+        for col_spec in self.col_specs.iter() {
+            v.extend_from_slice(col_spec.into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct BodyResResultSchemaChange {
     pub change_type: ChangeType,
     pub target: Target,
@@ -592,8 +808,17 @@ impl FromCursor for BodyResResultSchemaChange {
     }
 }
 
+impl IntoBytes for BodyResResultSchemaChange {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = self.change_type.into_cbytes();
+        v.extend_from_slice(self.target.into_cbytes().as_slice());
+        v.extend_from_slice(self.options.into_cbytes().as_slice());
+        return v;
+    }
+}
+
 /// Represents type of changes.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ChangeType {
     Created,
     Updated,
@@ -611,8 +836,19 @@ impl FromCursor for ChangeType {
     }
 }
 
+impl IntoBytes for ChangeType {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let change_type = match *self {
+            ChangeType::Created => "CREATED",
+            ChangeType::Updated => "UPDATED",
+            ChangeType::Dropped => "DROPPED"
+        };
+        return CString::new(change_type.to_string()).into_cbytes();
+    }
+}
+
 /// Refers to a target of changes were made.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Target {
     Keyspace,
     Table,
@@ -634,8 +870,21 @@ impl FromCursor for Target {
     }
 }
 
+impl IntoBytes for Target {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let target = match *self {
+            Target::Keyspace => "KEYSPACE",
+            Target::Table => "TABLE",
+            Target::Type => "TYPE",
+            Target::Function => "FUNCTION",
+            Target::Aggregate => "AGGREGATE"
+        };
+        return CString::new(target.to_string()).into_cbytes();
+    }
+}
+
 /// Option that contains an information about changes were made.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ChangeSchemeOptions {
     /// Changes related to keyspaces. Contains keyspace name.
     Keyspace(String),
@@ -675,3 +924,22 @@ impl ChangeSchemeOptions {
         return ChangeSchemeOptions::Function((keyspace, name, types));
     }
 }
+
+impl IntoBytes for ChangeSchemeOptions {
+    fn into_cbytes(&self) -> Vec<u8> {
+        return match *self {
+            ChangeSchemeOptions::Keyspace(ref keyspace) => CString::new(keyspace.clone()).into_cbytes(),
+            ChangeSchemeOptions::Table((ref keyspace, ref name)) => {
+                let mut v = CString::new(keyspace.clone()).into_cbytes();
+                v.extend_from_slice(CString::new(name.clone()).into_cbytes().as_slice());
+                v
+            },
+            ChangeSchemeOptions::Function((ref keyspace, ref name, ref types)) => {
+                let mut v = CString::new(keyspace.clone()).into_cbytes();
+                v.extend_from_slice(CString::new(name.clone()).into_cbytes().as_slice());
+                v.extend_from_slice(CStringList::new(types.clone()).into_cbytes().as_slice());
+                v
+            }
+        };
+    }
+}
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
+use std::io::Cursor;
 
 use super::*;
 use super::super::types::*;
-use super::super::{IntoBytes};
+use super::super::{IntoBytes, FromCursor};
 
+#[derive(Debug, Clone)]
 pub struct BodyReqStartup {
     pub map: HashMap<String, String>
 }
@@ -56,6 +58,21 @@ impl IntoBytes for BodyReqStartup {
     }
 }
 
+impl FromCursor for BodyReqStartup {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> BodyReqStartup {
+        let n = CIntShort::from_cursor(&mut cursor);
+        let mut map = HashMap::with_capacity(n as usize);
+        for _ in 0..n {
+            let key = CString::from_cursor(&mut cursor).into_plain();
+            let val = CString::from_cursor(&mut cursor).into_plain();
+            map.insert(key, val);
+        }
+        return BodyReqStartup {
+            map: map
+        };
+    }
+}
+
 // Frame implementation related to BodyReqStartup
 
 impl Frame {
@@ -76,7 +93,8 @@ impl Frame {
             body: body.into_cbytes(),
             // for request frames it's always None
             tracing_id: None,
-            warnings: vec![]
+            warnings: vec![],
+            custom_payload: HashMap::new()
         };
     }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::io::Cursor;
-use super::super::FromCursor;
+use super::super::{FromCursor, IntoBytes};
 use types::*;
 
 #[derive(Debug)]
@@ -24,3 +24,14 @@ impl FromCursor for BodyResSupported {
         };
     }
 }
+
+impl IntoBytes for BodyResSupported {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v = to_short(self.data.len() as u64);
+        for (name, val) in self.data.iter() {
+            v.extend_from_slice(CString::new(name.clone()).into_cbytes().as_slice());
+            v.extend_from_slice(CStringList::new(val.clone()).into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
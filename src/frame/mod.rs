@@ -1,6 +1,7 @@
 //! `frame` module contains general Frame functionality.
 use std::convert::{From};
-use types::to_n_bytes;
+use std::collections::HashMap;
+use types::{to_n_bytes, CBytesMap, CStringList};
 use {AsByte, IntoBytes};
 use self::frame_response::ResponseBody;
 use compression::Compression;
@@ -17,16 +18,22 @@ pub const STREAM_LEN: usize = 2;
 /// Number of body length bytes in accordance to protocol.
 pub const LENGTH_LEN: usize = 4;
 
+#[cfg(feature = "async")]
+pub mod codec;
 pub mod frame_auth_challenge;
 pub mod frame_auth_response;
 pub mod frame_auth_success;
 pub mod frame_authenticate;
+pub mod frame_batch;
 pub mod frame_error;
+pub mod frame_event;
 pub mod frame_execute;
 pub mod frame_options;
 pub mod frame_prepare;
 pub mod frame_query;
 pub mod frame_ready;
+pub mod frame_register;
+pub mod frame_request;
 pub mod frame_response;
 pub mod frame_result;
 pub mod frame_startup;
@@ -43,7 +50,10 @@ pub struct Frame {
     pub stream: u64, // we're going to use 0 here until async client is implemented
     pub body: Vec<u8>,
     pub tracing_id: Option<Uuid>,
-    pub warnings: Vec<String>
+    pub warnings: Vec<String>,
+    /// Custom payload. For request frames it's sent to a server if it's not empty,
+    /// for response frames it contains a payload returned by a server.
+    pub custom_payload: CBytesMap
 }
 
 impl Frame {
@@ -59,13 +69,56 @@ impl Frame {
         return self.warnings.clone();
     }
 
+    /// Returns custom payload of the frame, it's empty if a server didn't send any.
+    pub fn custom_payload(&self) -> HashMap<String, Vec<u8>> {
+        return self.custom_payload.clone();
+    }
+
+    /// Returns frame's flags including `Flag::CustomPayload` if custom payload is not empty
+    /// and, for response frames, `Flag::Tracing` and `Flag::Warning` if tracing id
+    /// and warnings are set.
+    fn flags_byte(&self) -> u8 {
+        let mut flags = Flag::many_to_cbytes(&self.flags);
+        if !self.custom_payload.is_empty() {
+            flags = flags | Flag::CustomPayload.as_byte();
+        }
+        if self.version == Version::Response && self.tracing_id.is_some() {
+            flags = flags | Flag::Tracing.as_byte();
+        }
+        if self.version == Version::Response && !self.warnings.is_empty() {
+            flags = flags | Flag::Warning.as_byte();
+        }
+
+        return flags;
+    }
+
+    /// Returns uncompressed body prepended by custom payload if the last one is not empty.
+    /// Response frames' body is also prepended by tracing id and warnings if they are set.
+    fn body_with_payload(&self) -> Vec<u8> {
+        let mut body = vec![];
+        if self.version == Version::Response {
+            if let Some(ref tracing_id) = self.tracing_id {
+                body.extend_from_slice(tracing_id.as_bytes());
+            }
+            if !self.warnings.is_empty() {
+                body.extend_from_slice(CStringList::new(self.warnings.clone()).into_cbytes().as_slice());
+            }
+        }
+        if !self.custom_payload.is_empty() {
+            body.extend_from_slice(self.custom_payload.into_cbytes().as_slice());
+        }
+
+        body.extend_from_slice(self.body.as_slice());
+        return body;
+    }
+
     pub fn encode_with(self, compressor: Compression) -> error::Result<Vec<u8>> {
         let mut v = vec![];
 
         let version_bytes = self.version.as_byte();
-        let flag_bytes = Flag::many_to_cbytes(&self.flags);
+        let flag_bytes = self.flags_byte();
         let opcode_bytes = self.opcode.as_byte();
-        let encoded_body = try!(compressor.encode(self.body));
+        let encoded_body = try!(compressor.encode(self.body_with_payload()));
         let body_len = encoded_body.len();
 
         v.push(version_bytes);
@@ -84,16 +137,17 @@ impl<'a> IntoBytes for Frame {
         let mut v = vec![];
 
         let version_bytes = self.version.as_byte();
-        let flag_bytes = Flag::many_to_cbytes(&self.flags);
+        let flag_bytes = self.flags_byte();
         let opcode_bytes = self.opcode.as_byte();
-        let body_len = self.body.len();
+        let body = self.body_with_payload();
+        let body_len = body.len();
 
         v.push(version_bytes);
         v.push(flag_bytes);
         v.extend_from_slice(to_n_bytes(self.stream, STREAM_LEN).as_slice());
         v.push(opcode_bytes);
         v.extend_from_slice(to_n_bytes(body_len as u64, LENGTH_LEN).as_slice());
-        v.extend_from_slice(self.body.as_slice());
+        v.extend_from_slice(body.as_slice());
 
         return v;
     }
@@ -218,7 +272,7 @@ impl From<u8> for Flag {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Opcode {
     Error,
     Startup,
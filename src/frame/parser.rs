@@ -1,14 +1,22 @@
 use std::io::{Read, Cursor};
+use std::collections::HashMap;
 
 use FromCursor;
 use compression::Compression;
 use frame::frame_response::ResponseBody;
 use super::*;
-use types::{from_bytes, UUID_LEN, CStringList};
+use types::{from_bytes, UUID_LEN, CStringList, CBytesMap};
 use types::data_serialization_types::decode_timeuuid;
 use error;
 
-pub fn parse_frame(mut cursor: &mut Read, compressor: &Compression) -> error::Result<Frame> {
+pub fn parse_frame(cursor: &mut Read, compressor: &Compression) -> error::Result<Frame> {
+    let frame = try!(parse_raw_frame(cursor, compressor));
+    return convert_frame_into_result(frame);
+}
+
+/// Parses a frame but in opposite to `parse_frame` it doesn't convert
+/// frames of type `error` into `Err`.
+pub fn parse_raw_frame(mut cursor: &mut Read, compressor: &Compression) -> error::Result<Frame> {
     let mut version_bytes = [0; VERSION_LEN];
     let mut flag_bytes = [0; FLAG_LEN];
     let mut opcode_bytes = [0; OPCODE_LEN];
@@ -16,11 +24,11 @@ pub fn parse_frame(mut cursor: &mut Read, compressor: &Compression) -> error::Re
     let mut length_bytes =[0; LENGTH_LEN];
 
     // NOTE: order of reads matters
-    try!(cursor.read(&mut version_bytes));
-    try!(cursor.read(&mut flag_bytes));
-    try!(cursor.read(&mut stream_bytes));
-    try!(cursor.read(&mut opcode_bytes));
-    try!(cursor.read(&mut length_bytes));
+    try!(cursor.read_exact(&mut version_bytes));
+    try!(cursor.read_exact(&mut flag_bytes));
+    try!(cursor.read_exact(&mut stream_bytes));
+    try!(cursor.read_exact(&mut opcode_bytes));
+    try!(cursor.read_exact(&mut length_bytes));
 
     let version = Version::from(version_bytes.to_vec());
     let flags = Flag::get_collection(flag_bytes[0]);
@@ -43,7 +51,11 @@ pub fn parse_frame(mut cursor: &mut Read, compressor: &Compression) -> error::Re
     // TODO: use cursor to get tracing id, warnings and actual body
     let mut body_cursor = Cursor::new(full_body);
 
-    let tracing_id = if flags.iter().any(|flag| flag == &Flag::Tracing) {
+    // tracing id and warnings are sent only by a server, for request frames
+    // tracing flag just asks a server to trace a request
+    let is_response = version == Version::Response;
+
+    let tracing_id = if is_response && flags.iter().any(|flag| flag == &Flag::Tracing) {
         let mut tracing_bytes = Vec::with_capacity(UUID_LEN);
         unsafe {
             tracing_bytes.set_len(UUID_LEN);
@@ -55,12 +67,18 @@ pub fn parse_frame(mut cursor: &mut Read, compressor: &Compression) -> error::Re
         None
     };
 
-    let warnings = if flags.iter().any(|flag| flag == &Flag::Warning) {
+    let warnings = if is_response && flags.iter().any(|flag| flag == &Flag::Warning) {
         CStringList::from_cursor(&mut body_cursor).into_plain()
     } else {
         vec![]
     };
 
+    let custom_payload = if flags.iter().any(|flag| flag == &Flag::CustomPayload) {
+        CBytesMap::from_cursor(&mut body_cursor)
+    } else {
+        HashMap::new()
+    };
+
     let mut body = vec![];
 
     try!(body_cursor.read_to_end(&mut body));
@@ -72,13 +90,15 @@ pub fn parse_frame(mut cursor: &mut Read, compressor: &Compression) -> error::Re
         stream: stream,
         body: body,
         tracing_id: tracing_id,
-        warnings: warnings
+        warnings: warnings,
+        custom_payload: custom_payload
     };
 
-    return conver_frame_into_result(frame);
+    return Ok(frame);
 }
 
-fn conver_frame_into_result(frame: Frame) -> error::Result<Frame> {
+/// Converts frames of type `error` into `Err` and returns `Ok` for the rest.
+pub fn convert_frame_into_result(frame: Frame) -> error::Result<Frame> {
     match frame.opcode {
         Opcode::Error => match frame.get_body() {
             ResponseBody::Error(err) => Err(error::Error::Server(err)),
@@ -8,23 +8,64 @@ extern crate lz4_compress;
 extern crate uuid;
 #[cfg(feature = "ssl")]
 extern crate openssl;
+#[cfg(feature = "tls-rustls")]
+extern crate rustls;
+#[cfg(feature = "tls-rustls")]
+extern crate webpki;
+#[cfg(feature = "tls-rustls")]
+extern crate webpki_roots;
 extern crate r2d2;
+extern crate rand;
+extern crate socket2;
+#[cfg(feature = "async")]
+extern crate bytes;
+#[cfg(feature = "async")]
+#[macro_use]
+extern crate futures;
+#[cfg(feature = "async")]
+extern crate tokio_codec;
+#[cfg(feature = "async")]
+extern crate tokio_core;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
 use std::io::Cursor;
 
 pub mod frame;
 pub mod types;
 
+#[cfg(feature = "async")]
+pub mod async_session;
 pub mod authenticators;
 pub mod client;
+pub mod cluster;
 pub mod compression;
 pub mod connection_manager;
 pub mod consistency;
 pub mod error;
-#[cfg(not(feature = "ssl"))]
+pub mod execution_info;
+pub mod load_balancing;
+pub mod lwt;
+pub mod metadata;
+pub mod node_state;
+pub mod query_trace;
+pub mod reconnection_policy;
+pub mod schema_change;
+#[cfg(feature = "serde")]
+pub mod serde_support;
+pub mod speculative_execution;
+pub mod testing;
+pub mod transport_config;
 pub mod transport;
+pub mod transport_loopback;
+pub mod tls_config;
 #[cfg(feature = "ssl")]
 pub mod transport_ssl;
+#[cfg(feature = "tls-rustls")]
+pub mod transport_rustls;
+#[cfg(unix)]
+pub mod transport_unix;
 
 /// `IntoBytes` should be used to convert a structure into array of bytes.
 pub trait IntoBytes {
@@ -0,0 +1,200 @@
+//! The module contains load balancing policies which decide which nodes of a cluster
+//! should be queried and in which order.
+//!
+//! Policies could be combined, e.g. `TokenAwarePolicy` wraps a datacenter-aware
+//! policy: replicas of a partition which the child policy considers `Local` are tried
+//! first, and then the rest of child's query plan.
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use consistency::Consistency;
+use node_state::NodeState;
+
+/// Distance to a node from a client's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeDistance {
+    /// Node of a local datacenter.
+    Local,
+    /// Node of a remote datacenter, it's used only if local nodes aren't available.
+    Remote,
+    /// Node which should never be queried.
+    Ignored
+}
+
+/// Load balancing policy.
+pub trait LoadBalancingPolicy: Send + Sync {
+    /// Returns distance to provided node.
+    fn distance(&self, node: &NodeState) -> NodeDistance;
+
+    /// Returns indexes of `nodes` in order they should be queried. Nodes which are down
+    /// or ignored aren't included. `consistency` is a consistency level of a request
+    /// and `routing_key` is a serialized partition key of the request if they're known.
+    fn new_query_plan(&self,
+                      nodes: &[NodeState],
+                      consistency: Option<&Consistency>,
+                      routing_key: Option<&[u8]>)
+                      -> Vec<usize>;
+}
+
+/// Returns indexes of nodes which are up and match the predicate, rotated by `offset`.
+fn rotate<F>(nodes: &[NodeState], offset: usize, predicate: F) -> Vec<usize>
+    where F: Fn(&NodeState) -> bool
+{
+    let matching: Vec<usize> = (0..nodes.len())
+        .filter(|index| nodes[*index].is_up() && predicate(&nodes[*index]))
+        .collect();
+    let len = matching.len();
+
+    return (0..len).map(|i| matching[(offset + i) % len]).collect();
+}
+
+/// Policy which queries all nodes in round-robin order regardless of their location.
+#[derive(Debug, Default)]
+pub struct RoundRobinPolicy {
+    next: AtomicUsize
+}
+
+impl RoundRobinPolicy {
+    /// Creates new round-robin policy.
+    pub fn new() -> RoundRobinPolicy {
+        return RoundRobinPolicy { next: AtomicUsize::new(0) };
+    }
+}
+
+impl LoadBalancingPolicy for RoundRobinPolicy {
+    fn distance(&self, _: &NodeState) -> NodeDistance {
+        return NodeDistance::Local;
+    }
+
+    fn new_query_plan(&self, nodes: &[NodeState], _: Option<&Consistency>, _: Option<&[u8]>) -> Vec<usize> {
+        let offset = self.next.fetch_add(1, Ordering::SeqCst);
+        return rotate(nodes, offset, |_| true);
+    }
+}
+
+/// Policy which queries nodes of a local datacenter in round-robin order.
+/// Nodes of remote datacenters follow local ones, so they're queried only if local nodes
+/// are down or failed, and never for `LOCAL_*` consistency levels. Nodes which
+/// datacenter isn't known yet are considered remote.
+#[derive(Debug)]
+pub struct DcAwareRoundRobinPolicy {
+    local_dc: String,
+    next: AtomicUsize
+}
+
+impl DcAwareRoundRobinPolicy {
+    /// Creates new policy with provided local datacenter.
+    pub fn new(local_dc: &str) -> DcAwareRoundRobinPolicy {
+        return DcAwareRoundRobinPolicy {
+            local_dc: local_dc.to_string(),
+            next: AtomicUsize::new(0)
+        };
+    }
+
+    fn is_local(&self, node: &NodeState) -> bool {
+        return node.datacenter().map_or(false, |dc| dc == self.local_dc);
+    }
+}
+
+impl LoadBalancingPolicy for DcAwareRoundRobinPolicy {
+    fn distance(&self, node: &NodeState) -> NodeDistance {
+        return if self.is_local(node) { NodeDistance::Local } else { NodeDistance::Remote };
+    }
+
+    fn new_query_plan(&self, nodes: &[NodeState], consistency: Option<&Consistency>, _: Option<&[u8]>)
+        -> Vec<usize> {
+        let offset = self.next.fetch_add(1, Ordering::SeqCst);
+        let mut plan = rotate(nodes, offset, |node| self.is_local(node));
+        if !consistency.map_or(false, |consistency| consistency.is_dc_local()) {
+            plan.extend(rotate(nodes, offset, |node| !self.is_local(node)));
+        }
+
+        return plan;
+    }
+}
+
+/// Datacenter-aware policy which prefers nodes of a local rack. Nodes of the local rack
+/// are queried first, then other nodes of the local datacenter and then nodes of remote
+/// datacenters in the same way as `DcAwareRoundRobinPolicy` does.
+#[derive(Debug)]
+pub struct RackAwareRoundRobinPolicy {
+    dc_aware: DcAwareRoundRobinPolicy,
+    local_rack: String
+}
+
+impl RackAwareRoundRobinPolicy {
+    /// Creates new policy with provided local datacenter and rack.
+    pub fn new(local_dc: &str, local_rack: &str) -> RackAwareRoundRobinPolicy {
+        return RackAwareRoundRobinPolicy {
+            dc_aware: DcAwareRoundRobinPolicy::new(local_dc),
+            local_rack: local_rack.to_string()
+        };
+    }
+
+    fn is_local_rack(&self, node: &NodeState) -> bool {
+        return self.dc_aware.is_local(node) &&
+            node.rack().map_or(false, |rack| rack == self.local_rack);
+    }
+}
+
+impl LoadBalancingPolicy for RackAwareRoundRobinPolicy {
+    fn distance(&self, node: &NodeState) -> NodeDistance {
+        return self.dc_aware.distance(node);
+    }
+
+    fn new_query_plan(&self, nodes: &[NodeState], consistency: Option<&Consistency>, routing_key: Option<&[u8]>)
+        -> Vec<usize> {
+        let plan = self.dc_aware.new_query_plan(nodes, consistency, routing_key);
+        let (mut local_rack, rest): (Vec<usize>, Vec<usize>) = plan.into_iter()
+            .partition(|index| self.is_local_rack(&nodes[*index]));
+        local_rack.extend(rest);
+
+        return local_rack;
+    }
+}
+
+/// Locator of replicas of a partition, e.g. by a token ring of a cluster.
+pub trait ReplicaLocator: Send + Sync {
+    /// Returns indexes of `nodes` which are replicas of a partition with provided
+    /// routing key.
+    fn replicas(&self, routing_key: &[u8], nodes: &[NodeState]) -> Vec<usize>;
+}
+
+/// Token-aware policy which wraps another policy. Replicas of a partition which
+/// the child policy considers `Local` are queried first, in order of the child's
+/// query plan, and then the rest of child's query plan. Requests without a routing
+/// key are routed by the child policy alone.
+pub struct TokenAwarePolicy<P: LoadBalancingPolicy> {
+    child: P,
+    locator: Arc<ReplicaLocator>
+}
+
+impl<P: LoadBalancingPolicy> TokenAwarePolicy<P> {
+    /// Creates new policy which wraps `child` and finds replicas with `locator`.
+    pub fn new(child: P, locator: Arc<ReplicaLocator>) -> TokenAwarePolicy<P> {
+        return TokenAwarePolicy {
+            child: child,
+            locator: locator
+        };
+    }
+}
+
+impl<P: LoadBalancingPolicy> LoadBalancingPolicy for TokenAwarePolicy<P> {
+    fn distance(&self, node: &NodeState) -> NodeDistance {
+        return self.child.distance(node);
+    }
+
+    fn new_query_plan(&self, nodes: &[NodeState], consistency: Option<&Consistency>, routing_key: Option<&[u8]>)
+        -> Vec<usize> {
+        let plan = self.child.new_query_plan(nodes, consistency, routing_key);
+        let replicas = match routing_key {
+            Some(routing_key) => self.locator.replicas(routing_key, nodes),
+            None => return plan
+        };
+        let (mut local_replicas, rest): (Vec<usize>, Vec<usize>) = plan.into_iter()
+            .partition(|index| replicas.contains(index) && self.distance(&nodes[*index]) == NodeDistance::Local);
+        local_replicas.extend(rest);
+
+        return local_replicas;
+    }
+}
@@ -0,0 +1,59 @@
+//! The module contains helpers for lightweight transactions, i.e. conditional writes
+//! such as `INSERT ... IF NOT EXISTS` or `UPDATE ... IF col = ?`.
+use error;
+use frame::Frame;
+use types::IntoRustByName;
+use types::rows::Row;
+
+/// Name of a column which indicates if a conditional write was applied.
+pub const APPLIED_COLUMN: &'static str = "[applied]";
+
+/// Result of a conditional write. If the write wasn't applied, rows contain
+/// current values of columns which took part in the condition.
+#[derive(Debug)]
+pub struct LwtResult {
+    applied: bool,
+    rows: Vec<Row>
+}
+
+impl LwtResult {
+    /// Parses a response to a conditional write.
+    pub fn from_frame(frame: &Frame) -> error::Result<LwtResult> {
+        return match frame.get_body().into_rows() {
+            Some(rows) => LwtResult::from_rows(rows),
+            None => Err(error::Error::General(format!("Response to a conditional write isn't rows result {:?}",
+                frame.opcode)))
+        };
+    }
+
+    /// Parses rows returned by a conditional write. Conditional batches return a row
+    /// per conditional statement, all of them have the same `[applied]` value.
+    pub fn from_rows(rows: Vec<Row>) -> error::Result<LwtResult> {
+        let applied: bool = match rows.first().and_then(|row| row.get_by_name(APPLIED_COLUMN)) {
+            Some(applied) => try!(applied),
+            None => return Err(error::Error::General(format!("Response doesn't contain {} column",
+                APPLIED_COLUMN)))
+        };
+
+        return Ok(LwtResult {
+            applied: applied,
+            rows: rows
+        });
+    }
+
+    /// Indicates if the write was applied.
+    pub fn applied(&self) -> bool {
+        return self.applied;
+    }
+
+    /// Returns current values which prevented the write from being applied,
+    /// `None` if the write was applied.
+    pub fn existing(&self) -> Option<&Row> {
+        return if self.applied { None } else { self.rows.first() };
+    }
+
+    /// Returns all returned rows including `[applied]` column.
+    pub fn rows(&self) -> &Vec<Row> {
+        return &self.rows;
+    }
+}
@@ -0,0 +1,616 @@
+//! The module contains schema metadata, i.e. keyspaces, tables, columns, user defined
+//! types, functions, aggregates, indexes and materialized views, which are loaded
+//! from `system_schema` tables.
+//!
+//! Metadata could be kept current by processing schema changes, both results of DDL
+//! queries and `SCHEMA_CHANGE` events (see `Session::listen_for`):
+//!
+//! ```no_run
+//! # use cdrs::authenticators::PasswordAuthenticator;
+//! # use cdrs::client::CDRS;
+//! # use cdrs::compression::Compression;
+//! # use cdrs::frame::frame_register::SimpleServerEvent;
+//! # use cdrs::metadata::Metadata;
+//! # use cdrs::transport::TransportTcp;
+//! # let connect = || CDRS::new(TransportTcp::new("127.0.0.1:9042").unwrap(),
+//! #                            PasswordAuthenticator::new("user", "pass"))
+//! #     .start(Compression::None).unwrap();
+//! let mut session = connect();
+//! let metadata = Metadata::new();
+//! metadata.refresh(&mut session).unwrap();
+//!
+//! let listener = connect().listen_for(vec![SimpleServerEvent::SchemaChange]).unwrap();
+//! for event in listener {
+//!     metadata.process_event(&mut session, &event).unwrap();
+//! }
+//! ```
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use authenticators::Authenticator;
+use client::{QueryBuilder, Session};
+use error;
+use frame::frame_event::ServerEvent;
+use frame::frame_result::{ChangeType, ColTypeOption};
+use schema_change::{SchemaChangeOutcome, SchemaElement};
+use transport::CDRSTransport;
+use types::{AsRust, IntoRustByName};
+use types::list::List;
+use types::map::Map;
+use types::rows::Row;
+
+pub mod type_parser;
+
+use self::type_parser::{UserTypeDefinitions, parse_type};
+
+/// Replication strategy of a keyspace.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplicationStrategy {
+    /// `SimpleStrategy` with its replication factor.
+    SimpleStrategy { replication_factor: usize },
+    /// `NetworkTopologyStrategy` with replication factors per datacenter.
+    NetworkTopologyStrategy { datacenters: BTreeMap<String, usize> },
+    /// `LocalStrategy` which is used by system keyspaces.
+    LocalStrategy,
+    /// Any other strategy with its class name and options.
+    Other { class: String, options: BTreeMap<String, String> }
+}
+
+impl ReplicationStrategy {
+    /// Creates replication strategy from `replication` options of a keyspace.
+    pub fn from_options(options: &BTreeMap<String, String>) -> ReplicationStrategy {
+        let class = options.get("class").cloned().unwrap_or(String::new());
+        let other = || ReplicationStrategy::Other {
+            class: class.clone(),
+            options: options.clone()
+        };
+
+        return match class.rsplit('.').next().unwrap_or("") {
+            "SimpleStrategy" => {
+                match options.get("replication_factor").and_then(|rf| parse_replication_factor(rf)) {
+                    Some(rf) => ReplicationStrategy::SimpleStrategy { replication_factor: rf },
+                    None => other()
+                }
+            },
+            "NetworkTopologyStrategy" => {
+                let mut datacenters = BTreeMap::new();
+                for (dc, rf) in options.iter().filter(|&(key, _)| key != "class") {
+                    match parse_replication_factor(rf) {
+                        Some(rf) => datacenters.insert(dc.clone(), rf),
+                        None => return other()
+                    };
+                }
+                ReplicationStrategy::NetworkTopologyStrategy { datacenters: datacenters }
+            },
+            "LocalStrategy" => ReplicationStrategy::LocalStrategy,
+            _ => other()
+        };
+    }
+}
+
+/// Parses replication factor which could be either a number of replicas or
+/// `<all replicas>/<transient replicas>`.
+fn parse_replication_factor(rf: &str) -> Option<usize> {
+    return rf.split('/').next().and_then(|rf| rf.trim().parse().ok());
+}
+
+/// Kind of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    PartitionKey,
+    Clustering,
+    Regular,
+    Static
+}
+
+/// Clustering order of a column. It's `None` for columns which aren't clustering ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusteringOrder {
+    Asc,
+    Desc,
+    None
+}
+
+/// Metadata of a column of a table or a view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMetadata {
+    /// Column name.
+    pub name: String,
+    /// Kind of the column.
+    pub kind: ColumnKind,
+    /// Position of a partition key or clustering column within the key,
+    /// `-1` for other columns.
+    pub position: i32,
+    /// Clustering order of the column.
+    pub clustering_order: ClusteringOrder,
+    /// Column type as it's stored in schema, e.g. `frozen<list<int>>`.
+    pub type_string: String,
+    /// Parsed column type.
+    pub col_type: ColTypeOption
+}
+
+/// Metadata of a secondary index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexMetadata {
+    /// Index name.
+    pub name: String,
+    /// Kind of the index, i.e. `COMPOSITES`, `KEYS` or `CUSTOM`.
+    pub kind: String,
+    /// Index options, e.g. `target` column.
+    pub options: BTreeMap<String, String>
+}
+
+/// Metadata of a table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableMetadata {
+    /// Keyspace name.
+    pub keyspace: String,
+    /// Table name.
+    pub name: String,
+    /// Partition key columns in order of the key.
+    pub partition_key: Vec<ColumnMetadata>,
+    /// Clustering columns in order of the key.
+    pub clustering_columns: Vec<ColumnMetadata>,
+    /// All columns: partition key, clustering columns and then other ones ordered by name.
+    pub columns: Vec<ColumnMetadata>,
+    /// Secondary indexes of the table.
+    pub indexes: Vec<IndexMetadata>
+}
+
+impl TableMetadata {
+    /// Creates table metadata from its columns in any order.
+    pub fn new(keyspace: &str, name: &str, columns: Vec<ColumnMetadata>) -> TableMetadata {
+        let (mut keys, mut others): (Vec<ColumnMetadata>, Vec<ColumnMetadata>) = columns.into_iter()
+            .partition(|column| column.kind == ColumnKind::PartitionKey ||
+                column.kind == ColumnKind::Clustering);
+        keys.sort_by_key(|column| (column.kind != ColumnKind::PartitionKey, column.position));
+        others.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let partition_key = keys.iter().filter(|column| column.kind == ColumnKind::PartitionKey).cloned().collect();
+        let clustering_columns = keys.iter().filter(|column| column.kind == ColumnKind::Clustering).cloned().collect();
+        keys.extend(others);
+
+        return TableMetadata {
+            keyspace: keyspace.to_string(),
+            name: name.to_string(),
+            partition_key: partition_key,
+            clustering_columns: clustering_columns,
+            columns: keys,
+            indexes: vec![]
+        };
+    }
+
+    /// Returns a column with provided name.
+    pub fn column(&self, name: &str) -> Option<&ColumnMetadata> {
+        return self.columns.iter().find(|column| column.name == name);
+    }
+}
+
+/// Metadata of a materialized view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewMetadata {
+    /// Name of a table the view is based on.
+    pub base_table: String,
+    /// Indicates if the view includes all columns of the base table.
+    pub include_all_columns: bool,
+    /// Where clause of the view.
+    pub where_clause: String,
+    /// Name, keys and columns of the view.
+    pub table: TableMetadata
+}
+
+/// Metadata of a user defined type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserTypeMetadata {
+    /// Keyspace name.
+    pub keyspace: String,
+    /// Type name.
+    pub name: String,
+    /// Pairs `(field name, field type)` in order of definition.
+    pub fields: Vec<(String, ColTypeOption)>
+}
+
+/// Metadata of a user defined function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionMetadata {
+    /// Keyspace name.
+    pub keyspace: String,
+    /// Function name.
+    pub name: String,
+    /// Names of arguments.
+    pub argument_names: Vec<String>,
+    /// Types of arguments.
+    pub argument_types: Vec<ColTypeOption>,
+    /// Return type.
+    pub return_type: ColTypeOption,
+    /// Language of the function body, e.g. `java`.
+    pub language: String,
+    /// Body of the function.
+    pub body: String,
+    /// Indicates if the function is called when any of arguments is null.
+    pub called_on_null_input: bool
+}
+
+/// Metadata of a user defined aggregate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateMetadata {
+    /// Keyspace name.
+    pub keyspace: String,
+    /// Aggregate name.
+    pub name: String,
+    /// Types of arguments.
+    pub argument_types: Vec<ColTypeOption>,
+    /// Name of a state function.
+    pub state_func: String,
+    /// Type of the state.
+    pub state_type: ColTypeOption,
+    /// Name of a final function if it's set.
+    pub final_func: Option<String>,
+    /// Initial condition if it's set.
+    pub initcond: Option<String>,
+    /// Return type.
+    pub return_type: ColTypeOption
+}
+
+/// Metadata of a keyspace including all its schema objects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyspaceMetadata {
+    /// Keyspace name.
+    pub name: String,
+    /// Indicates if commit log is used for updates of the keyspace.
+    pub durable_writes: bool,
+    /// Replication strategy of the keyspace.
+    pub replication: ReplicationStrategy,
+    /// Tables by names.
+    pub tables: BTreeMap<String, TableMetadata>,
+    /// Materialized views by names.
+    pub views: BTreeMap<String, ViewMetadata>,
+    /// User defined types by names.
+    pub user_types: BTreeMap<String, UserTypeMetadata>,
+    /// User defined functions, there could be several overloads with the same name.
+    pub functions: Vec<FunctionMetadata>,
+    /// User defined aggregates, there could be several overloads with the same name.
+    pub aggregates: Vec<AggregateMetadata>
+}
+
+impl KeyspaceMetadata {
+    fn new(name: String, durable_writes: bool, replication: ReplicationStrategy) -> KeyspaceMetadata {
+        return KeyspaceMetadata {
+            name: name,
+            durable_writes: durable_writes,
+            replication: replication,
+            tables: BTreeMap::new(),
+            views: BTreeMap::new(),
+            user_types: BTreeMap::new(),
+            functions: vec![],
+            aggregates: vec![]
+        };
+    }
+}
+
+/// Schema metadata of a cluster. It's empty once created and should be loaded
+/// via `refresh`. Metadata could be shared between threads, readers get copies
+/// of keyspaces and tables.
+#[derive(Debug, Default)]
+pub struct Metadata {
+    keyspaces: RwLock<BTreeMap<String, KeyspaceMetadata>>
+}
+
+impl Metadata {
+    /// Creates empty metadata.
+    pub fn new() -> Metadata {
+        return Metadata { keyspaces: RwLock::new(BTreeMap::new()) };
+    }
+
+    /// Reloads metadata of all keyspaces.
+    pub fn refresh<T: Authenticator, X: CDRSTransport>(&self, session: &mut Session<T, X>)
+        -> error::Result<()> {
+        let keyspaces = try!(load_keyspaces(session, None));
+        *self.write() = keyspaces;
+        return Ok(());
+    }
+
+    /// Reloads metadata of a single keyspace. The keyspace is removed if it doesn't exist anymore.
+    pub fn refresh_keyspace<T: Authenticator, X: CDRSTransport>(&self,
+                                                                 session: &mut Session<T, X>,
+                                                                 keyspace: &str)
+                                                                 -> error::Result<()> {
+        let mut loaded = try!(load_keyspaces(session, Some(keyspace)));
+        let mut keyspaces = self.write();
+        match loaded.remove(keyspace) {
+            Some(metadata) => keyspaces.insert(keyspace.to_string(), metadata),
+            None => keyspaces.remove(keyspace)
+        };
+
+        return Ok(());
+    }
+
+    /// Updates metadata in accordance to a schema change, which is either a result
+    /// of a DDL query (see `Session::ddl`) or a `SCHEMA_CHANGE` event. A keyspace affected
+    /// by the change is reloaded, or removed if the keyspace was dropped.
+    pub fn process_schema_change<T: Authenticator, X: CDRSTransport>(&self,
+                                                                      session: &mut Session<T, X>,
+                                                                      change: &SchemaChangeOutcome)
+                                                                      -> error::Result<()> {
+        if let (&ChangeType::Dropped, &SchemaElement::Keyspace { ref keyspace }) =
+            (&change.change_type, &change.element) {
+            self.write().remove(keyspace);
+            return Ok(());
+        }
+
+        return self.refresh_keyspace(session, change.keyspace());
+    }
+
+    /// Updates metadata if provided event is a schema change, other events are ignored.
+    pub fn process_event<T: Authenticator, X: CDRSTransport>(&self,
+                                                              session: &mut Session<T, X>,
+                                                              event: &ServerEvent)
+                                                              -> error::Result<()> {
+        return match event.schema_change() {
+            Some(change) => self.process_schema_change(session, &change),
+            None => Ok(())
+        };
+    }
+
+    /// Returns names of all known keyspaces.
+    pub fn keyspace_names(&self) -> Vec<String> {
+        return self.read().keys().cloned().collect();
+    }
+
+    /// Returns metadata of a keyspace.
+    pub fn keyspace(&self, keyspace: &str) -> Option<KeyspaceMetadata> {
+        return self.read().get(keyspace).cloned();
+    }
+
+    /// Returns metadata of a table.
+    pub fn table(&self, keyspace: &str, table: &str) -> Option<TableMetadata> {
+        return self.read().get(keyspace).and_then(|keyspace| keyspace.tables.get(table).cloned());
+    }
+
+    /// Returns metadata of a user defined type.
+    pub fn user_type(&self, keyspace: &str, name: &str) -> Option<UserTypeMetadata> {
+        return self.read().get(keyspace).and_then(|keyspace| keyspace.user_types.get(name).cloned());
+    }
+
+    fn read(&self) -> RwLockReadGuard<BTreeMap<String, KeyspaceMetadata>> {
+        return match self.keyspaces.read() {
+            Ok(keyspaces) => keyspaces,
+            Err(poisoned) => poisoned.into_inner()
+        };
+    }
+
+    fn write(&self) -> RwLockWriteGuard<BTreeMap<String, KeyspaceMetadata>> {
+        return match self.keyspaces.write() {
+            Ok(keyspaces) => keyspaces,
+            Err(poisoned) => poisoned.into_inner()
+        };
+    }
+}
+
+/// Selects rows of a `system_schema` table, optionally of a single keyspace.
+fn select_schema<T: Authenticator, X: CDRSTransport>(session: &mut Session<T, X>,
+                                                      table: &str,
+                                                      keyspace: Option<&str>)
+                                                      -> error::Result<Vec<Row>> {
+    let query = match keyspace {
+        Some(keyspace) => format!("SELECT * FROM system_schema.{} WHERE keyspace_name = '{}'",
+            table, keyspace.replace("'", "''")),
+        None => format!("SELECT * FROM system_schema.{}", table)
+    };
+
+    let response = try!(session.query(QueryBuilder::new(query.as_str()).finalize(), false, false));
+    return Ok(response.get_body().into_rows().unwrap_or(vec![]));
+}
+
+fn load_keyspaces<T: Authenticator, X: CDRSTransport>(session: &mut Session<T, X>,
+                                                       keyspace: Option<&str>)
+                                                       -> error::Result<BTreeMap<String, KeyspaceMetadata>> {
+    let keyspace_rows = try!(select_schema(session, "keyspaces", keyspace));
+    let table_rows = try!(select_schema(session, "tables", keyspace));
+    let column_rows = try!(select_schema(session, "columns", keyspace));
+    let type_rows = try!(select_schema(session, "types", keyspace));
+    let function_rows = try!(select_schema(session, "functions", keyspace));
+    let aggregate_rows = try!(select_schema(session, "aggregates", keyspace));
+    let index_rows = try!(select_schema(session, "indexes", keyspace));
+    let view_rows = try!(select_schema(session, "views", keyspace));
+
+    let mut keyspaces = BTreeMap::new();
+    for row in keyspace_rows.iter() {
+        let name: String = try!(get_required(row, "keyspace_name"));
+        let durable_writes = try!(get_value(row, "durable_writes")).unwrap_or(true);
+        let replication = ReplicationStrategy::from_options(&try!(get_string_map(row, "replication")));
+        keyspaces.insert(name.clone(), KeyspaceMetadata::new(name, durable_writes, replication));
+    }
+
+    // user defined types are parsed once all of them are known as they could reference each other
+    let mut definitions: HashMap<String, UserTypeDefinitions> = HashMap::new();
+    for row in type_rows.iter() {
+        let keyspace: String = try!(get_required(row, "keyspace_name"));
+        let name: String = try!(get_required(row, "type_name"));
+        let field_names = try!(get_string_list(row, "field_names"));
+        let field_types = try!(get_string_list(row, "field_types"));
+        definitions.entry(keyspace)
+            .or_insert(HashMap::new())
+            .insert(name, field_names.into_iter().zip(field_types.into_iter()).collect());
+    }
+    let no_definitions = HashMap::new();
+    for (keyspace_name, keyspace_definitions) in definitions.iter() {
+        let keyspace = match keyspaces.get_mut(keyspace_name) {
+            Some(keyspace) => keyspace,
+            None => continue
+        };
+        for (name, fields) in keyspace_definitions.iter() {
+            let mut parsed_fields = vec![];
+            for &(ref field_name, ref field_type) in fields.iter() {
+                let field_type = try!(parse_type(field_type.as_str(), keyspace_name, keyspace_definitions));
+                parsed_fields.push((field_name.clone(), field_type));
+            }
+            keyspace.user_types.insert(name.clone(), UserTypeMetadata {
+                keyspace: keyspace_name.clone(),
+                name: name.clone(),
+                fields: parsed_fields
+            });
+        }
+    }
+
+    let mut columns: HashMap<(String, String), Vec<ColumnMetadata>> = HashMap::new();
+    for row in column_rows.iter() {
+        let keyspace: String = try!(get_required(row, "keyspace_name"));
+        let table: String = try!(get_required(row, "table_name"));
+        let type_string: String = try!(get_required(row, "type"));
+        let col_type = try!(parse_type(type_string.as_str(), keyspace.as_str(),
+                                       definitions.get(&keyspace).unwrap_or(&no_definitions)));
+        let kind: String = try!(get_required(row, "kind"));
+        let clustering_order: Option<String> = try!(get_value(row, "clustering_order"));
+        let column = ColumnMetadata {
+            name: try!(get_required(row, "column_name")),
+            kind: match kind.as_str() {
+                "partition_key" => ColumnKind::PartitionKey,
+                "clustering" => ColumnKind::Clustering,
+                "static" => ColumnKind::Static,
+                _ => ColumnKind::Regular
+            },
+            position: try!(get_value(row, "position")).unwrap_or(-1),
+            clustering_order: match clustering_order.as_ref().map(|order| order.as_str()) {
+                Some("asc") => ClusteringOrder::Asc,
+                Some("desc") => ClusteringOrder::Desc,
+                _ => ClusteringOrder::None
+            },
+            type_string: type_string,
+            col_type: col_type
+        };
+        columns.entry((keyspace, table)).or_insert(vec![]).push(column);
+    }
+
+    let mut indexes: HashMap<(String, String), Vec<IndexMetadata>> = HashMap::new();
+    for row in index_rows.iter() {
+        let keyspace: String = try!(get_required(row, "keyspace_name"));
+        let table: String = try!(get_required(row, "table_name"));
+        let index = IndexMetadata {
+            name: try!(get_required(row, "index_name")),
+            kind: try!(get_value(row, "kind")).unwrap_or(String::new()),
+            options: try!(get_string_map(row, "options"))
+        };
+        indexes.entry((keyspace, table)).or_insert(vec![]).push(index);
+    }
+
+    for row in table_rows.iter() {
+        let keyspace_name: String = try!(get_required(row, "keyspace_name"));
+        let name: String = try!(get_required(row, "table_name"));
+        let key = (keyspace_name.clone(), name.clone());
+        let mut table = TableMetadata::new(keyspace_name.as_str(), name.as_str(),
+                                           columns.remove(&key).unwrap_or(vec![]));
+        table.indexes = indexes.remove(&key).unwrap_or(vec![]);
+        if let Some(keyspace) = keyspaces.get_mut(&keyspace_name) {
+            keyspace.tables.insert(name, table);
+        }
+    }
+
+    for row in view_rows.iter() {
+        let keyspace_name: String = try!(get_required(row, "keyspace_name"));
+        let name: String = try!(get_required(row, "view_name"));
+        let key = (keyspace_name.clone(), name.clone());
+        let view = ViewMetadata {
+            base_table: try!(get_required(row, "base_table_name")),
+            include_all_columns: try!(get_value(row, "include_all_columns")).unwrap_or(false),
+            where_clause: try!(get_value(row, "where_clause")).unwrap_or(String::new()),
+            table: TableMetadata::new(keyspace_name.as_str(), name.as_str(),
+                                      columns.remove(&key).unwrap_or(vec![]))
+        };
+        if let Some(keyspace) = keyspaces.get_mut(&keyspace_name) {
+            keyspace.views.insert(name, view);
+        }
+    }
+
+    for row in function_rows.iter() {
+        let keyspace_name: String = try!(get_required(row, "keyspace_name"));
+        let keyspace_definitions = definitions.get(&keyspace_name).unwrap_or(&no_definitions);
+        let return_type: String = try!(get_required(row, "return_type"));
+        let function = FunctionMetadata {
+            keyspace: keyspace_name.clone(),
+            name: try!(get_required(row, "function_name")),
+            argument_names: try!(get_string_list(row, "argument_names")),
+            argument_types: try!(parse_types(try!(get_string_list(row, "argument_types")),
+                                             keyspace_name.as_str(), keyspace_definitions)),
+            return_type: try!(parse_type(return_type.as_str(), keyspace_name.as_str(), keyspace_definitions)),
+            language: try!(get_value(row, "language")).unwrap_or(String::new()),
+            body: try!(get_value(row, "body")).unwrap_or(String::new()),
+            called_on_null_input: try!(get_value(row, "called_on_null_input")).unwrap_or(false)
+        };
+        if let Some(keyspace) = keyspaces.get_mut(&keyspace_name) {
+            keyspace.functions.push(function);
+        }
+    }
+
+    for row in aggregate_rows.iter() {
+        let keyspace_name: String = try!(get_required(row, "keyspace_name"));
+        let keyspace_definitions = definitions.get(&keyspace_name).unwrap_or(&no_definitions);
+        let state_type: String = try!(get_required(row, "state_type"));
+        let return_type: String = try!(get_required(row, "return_type"));
+        let aggregate = AggregateMetadata {
+            keyspace: keyspace_name.clone(),
+            name: try!(get_required(row, "aggregate_name")),
+            argument_types: try!(parse_types(try!(get_string_list(row, "argument_types")),
+                                             keyspace_name.as_str(), keyspace_definitions)),
+            state_func: try!(get_required(row, "state_func")),
+            state_type: try!(parse_type(state_type.as_str(), keyspace_name.as_str(), keyspace_definitions)),
+            final_func: try!(get_value(row, "final_func")),
+            initcond: try!(get_value(row, "initcond")),
+            return_type: try!(parse_type(return_type.as_str(), keyspace_name.as_str(), keyspace_definitions))
+        };
+        if let Some(keyspace) = keyspaces.get_mut(&keyspace_name) {
+            keyspace.aggregates.push(aggregate);
+        }
+    }
+
+    return Ok(keyspaces);
+}
+
+fn parse_types(type_strings: Vec<String>, keyspace: &str, user_types: &UserTypeDefinitions)
+    -> error::Result<Vec<ColTypeOption>> {
+    let mut types = vec![];
+    for type_string in type_strings.iter() {
+        types.push(try!(parse_type(type_string.as_str(), keyspace, user_types)));
+    }
+    return Ok(types);
+}
+
+/// Returns a value of a column, `None` if the column is missing or null.
+fn get_value<R>(row: &Row, name: &str) -> error::Result<Option<R>>
+    where Row: IntoRustByName<R>
+{
+    if row.is_null(name).unwrap_or(true) {
+        return Ok(None);
+    }
+
+    return match row.get_by_name(name) {
+        Some(value) => value.map(Some),
+        None => Ok(None)
+    };
+}
+
+fn get_required<R>(row: &Row, name: &str) -> error::Result<R>
+    where Row: IntoRustByName<R>
+{
+    return match try!(get_value(row, name)) {
+        Some(value) => Ok(value),
+        None => Err(error::Error::General(format!("Schema column {} is missing or null", name)))
+    };
+}
+
+fn get_string_list(row: &Row, name: &str) -> error::Result<Vec<String>> {
+    return match try!(get_value::<List>(row, name)) {
+        Some(list) => list.as_rust(),
+        None => Ok(vec![])
+    };
+}
+
+fn get_string_map(row: &Row, name: &str) -> error::Result<BTreeMap<String, String>> {
+    return match try!(get_value::<Map>(row, name)) {
+        Some(map) => {
+            let map: HashMap<String, String> = try!(map.as_rust());
+            Ok(map.into_iter().collect())
+        },
+        None => Ok(BTreeMap::new())
+    };
+}
@@ -0,0 +1,270 @@
+//! The module contains a parser of CQL types as they're stored in `system_schema`
+//! tables, e.g. `map<text, frozen<list<int>>>` or `frozen<address>`.
+use std::collections::HashMap;
+
+use error;
+use frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue, CTuple, CUdt};
+use types::CString;
+pub use types::duration::DURATION_CLASS;
+
+/// Fields of user defined types of a keyspace which could be referenced by type strings.
+/// Keys are type names, values are pairs `(field name, field type string)`.
+pub type UserTypeDefinitions = HashMap<String, Vec<(String, String)>>;
+
+
+/// Max nesting of user defined types.
+const MAX_DEPTH: usize = 32;
+
+/// Parses a CQL type string into `ColTypeOption`. User defined types are looked up
+/// in `user_types` of provided keyspace and resolved together with their fields.
+/// `frozen` is accepted but isn't reflected in the result.
+pub fn parse_type(type_string: &str, keyspace: &str, user_types: &UserTypeDefinitions)
+    -> error::Result<ColTypeOption> {
+    return parse_type_with_depth(type_string, keyspace, user_types, 0);
+}
+
+fn parse_type_with_depth(type_string: &str,
+                         keyspace: &str,
+                         user_types: &UserTypeDefinitions,
+                         depth: usize)
+                         -> error::Result<ColTypeOption> {
+    let mut parser = TypeParser {
+        chars: type_string.chars().collect(),
+        pos: 0,
+        keyspace: keyspace,
+        user_types: user_types,
+        depth: depth
+    };
+
+    let col_type = try!(parser.parse());
+    parser.skip_whitespaces();
+    if parser.pos != parser.chars.len() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+
+    return Ok(col_type);
+}
+
+struct TypeParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    keyspace: &'a str,
+    user_types: &'a UserTypeDefinitions,
+    depth: usize
+}
+
+impl<'a> TypeParser<'a> {
+    fn parse(&mut self) -> error::Result<ColTypeOption> {
+        self.skip_whitespaces();
+        if self.peek() == Some('\'') {
+            let class_name = try!(self.read_quoted('\''));
+            return Ok(custom(class_name.as_str()));
+        }
+
+        let name = if self.peek() == Some('"') {
+            try!(self.read_quoted('"'))
+        } else {
+            try!(self.read_identifier()).to_lowercase()
+        };
+        let params = try!(self.read_params());
+
+        return match name.as_str() {
+            "frozen" => self.single_param(&name, params),
+            "list" => {
+                let item_type = try!(self.single_param(&name, params));
+                Ok(ColTypeOption {
+                    id: ColType::List,
+                    value: Some(ColTypeOptionValue::CList(Box::new(item_type)))
+                })
+            },
+            "set" => {
+                let item_type = try!(self.single_param(&name, params));
+                Ok(ColTypeOption {
+                    id: ColType::Set,
+                    value: Some(ColTypeOptionValue::CSet(Box::new(item_type)))
+                })
+            },
+            "map" => {
+                if params.len() != 2 {
+                    return Err(self.error("map should have key and value types"));
+                }
+                let mut params = params.into_iter();
+                let key_type = params.next().unwrap();
+                let value_type = params.next().unwrap();
+                Ok(ColTypeOption {
+                    id: ColType::Map,
+                    value: Some(ColTypeOptionValue::CMap((Box::new(key_type), Box::new(value_type))))
+                })
+            },
+            "tuple" => {
+                if params.is_empty() {
+                    return Err(self.error("tuple should have at least one type"));
+                }
+                Ok(ColTypeOption {
+                    id: ColType::Tuple,
+                    value: Some(ColTypeOptionValue::TupleType(CTuple { types: params }))
+                })
+            },
+            _ => {
+                if !params.is_empty() {
+                    return Err(self.error("unexpected type parameters"));
+                }
+                match native_type(name.as_str()) {
+                    Some(col_type) => Ok(col_type),
+                    None => self.user_type(name.as_str())
+                }
+            }
+        };
+    }
+
+    fn user_type(&self, name: &str) -> error::Result<ColTypeOption> {
+        let fields = match self.user_types.get(name) {
+            Some(fields) => fields,
+            None => return Err(self.error(format!("unknown type {}", name).as_str()))
+        };
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("too deeply nested user defined types"));
+        }
+
+        let mut descriptions = vec![];
+        for &(ref field_name, ref field_type) in fields.iter() {
+            let field_type = try!(parse_type_with_depth(field_type.as_str(),
+                                                        self.keyspace,
+                                                        self.user_types,
+                                                        self.depth + 1));
+            descriptions.push((CString::new(field_name.clone()), field_type));
+        }
+
+        return Ok(ColTypeOption {
+            id: ColType::Udt,
+            value: Some(ColTypeOptionValue::UdtType(CUdt {
+                ks: CString::new(self.keyspace.to_string()),
+                udt_name: CString::new(name.to_string()),
+                descriptions: descriptions
+            }))
+        });
+    }
+
+    fn single_param(&self, name: &str, params: Vec<ColTypeOption>) -> error::Result<ColTypeOption> {
+        if params.len() != 1 {
+            return Err(self.error(format!("{} should have exactly one type parameter", name).as_str()));
+        }
+        return Ok(params.into_iter().next().unwrap());
+    }
+
+    fn read_params(&mut self) -> error::Result<Vec<ColTypeOption>> {
+        self.skip_whitespaces();
+        if self.peek() != Some('<') {
+            return Ok(vec![]);
+        }
+        self.pos += 1;
+
+        let mut params = vec![];
+        loop {
+            params.push(try!(self.parse()));
+            self.skip_whitespaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('>') => {
+                    self.pos += 1;
+                    return Ok(params);
+                },
+                _ => return Err(self.error("expected ',' or '>'"))
+            }
+        }
+    }
+
+    fn read_identifier(&mut self) -> error::Result<String> {
+        let start = self.pos;
+        while self.peek().map_or(false, |c| c.is_alphanumeric() || c == '_' || c == '.') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected type name"));
+        }
+
+        return Ok(self.chars[start..self.pos].iter().cloned().collect());
+    }
+
+    /// Reads a string enclosed by `quote`, doubled quotes are unescaped.
+    fn read_quoted(&mut self, quote: char) -> error::Result<String> {
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    if self.peek() == Some(quote) {
+                        value.push(quote);
+                        self.pos += 1;
+                    } else {
+                        return Ok(value);
+                    }
+                },
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                },
+                None => return Err(self.error("unterminated quoted name"))
+            }
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.peek().map_or(false, |c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        return self.chars.get(self.pos).cloned();
+    }
+
+    fn error(&self, message: &str) -> error::Error {
+        let type_string: String = self.chars.iter().cloned().collect();
+        return error::Error::General(format!("Cannot parse type '{}' at {}: {}",
+            type_string, self.pos, message));
+    }
+}
+
+fn simple(id: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: id,
+        value: None
+    };
+}
+
+fn custom(class_name: &str) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Custom,
+        value: Some(ColTypeOptionValue::CString(CString::new(class_name.to_string())))
+    };
+}
+
+fn native_type(name: &str) -> Option<ColTypeOption> {
+    let id = match name {
+        "ascii" => ColType::Ascii,
+        "bigint" => ColType::Bigint,
+        "blob" => ColType::Blob,
+        "boolean" => ColType::Boolean,
+        "counter" => ColType::Counter,
+        "date" => ColType::Date,
+        "decimal" => ColType::Decimal,
+        "double" => ColType::Double,
+        "duration" => return Some(custom(DURATION_CLASS)),
+        "float" => ColType::Float,
+        "inet" => ColType::Inet,
+        "int" => ColType::Int,
+        "smallint" => ColType::Smallint,
+        "text" | "varchar" => ColType::Varchar,
+        "time" => ColType::Time,
+        "timestamp" => ColType::Timestamp,
+        "timeuuid" => ColType::Timeuuid,
+        "tinyint" => ColType::Tinyint,
+        "uuid" => ColType::Uuid,
+        "varint" => ColType::Varint,
+        _ => return None
+    };
+
+    return Some(simple(id));
+}
@@ -0,0 +1,111 @@
+//! The module contains state of a Cassandra node which is shared between
+//! all connections to the node, i.e. whether it's up or down and its location.
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// State of a node. Clones share the same state, so a node could be
+/// marked down by any of its connections, e.g. when a heartbeat fails.
+/// A node is considered to be up once created.
+#[derive(Debug, Clone)]
+pub struct NodeState {
+    up: Arc<AtomicBool>,
+    location: Arc<RwLock<NodeLocation>>
+}
+
+/// Datacenter and rack of a node as they're reported by the node,
+/// `None` if they aren't known yet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeLocation {
+    /// Datacenter of the node.
+    pub datacenter: Option<String>,
+    /// Rack of the node.
+    pub rack: Option<String>
+}
+
+impl NodeState {
+    /// Creates new state of a node which is up.
+    pub fn new() -> NodeState {
+        return NodeState {
+            up: Arc::new(AtomicBool::new(true)),
+            location: Arc::new(RwLock::new(NodeLocation::default()))
+        };
+    }
+
+    /// Indicates if the node is up.
+    pub fn is_up(&self) -> bool {
+        return self.up.load(Ordering::SeqCst);
+    }
+
+    /// Marks the node up. Returns `true` if the node was down before.
+    pub fn mark_up(&self) -> bool {
+        return !self.up.swap(true, Ordering::SeqCst);
+    }
+
+    /// Marks the node down. Returns `true` if the node was up before.
+    pub fn mark_down(&self) -> bool {
+        return self.up.swap(false, Ordering::SeqCst);
+    }
+
+    /// Returns location of the node.
+    pub fn location(&self) -> NodeLocation {
+        return match self.location.read() {
+            Ok(location) => location.clone(),
+            Err(poisoned) => poisoned.into_inner().clone()
+        };
+    }
+
+    /// Returns datacenter of the node if it's known.
+    pub fn datacenter(&self) -> Option<String> {
+        return self.location().datacenter;
+    }
+
+    /// Returns rack of the node if it's known.
+    pub fn rack(&self) -> Option<String> {
+        return self.location().rack;
+    }
+
+    /// Sets location of the node.
+    pub fn set_location(&self, location: NodeLocation) {
+        match self.location.write() {
+            Ok(mut current) => *current = location,
+            Err(poisoned) => *poisoned.into_inner() = location
+        }
+    }
+}
+
+impl Default for NodeState {
+    fn default() -> NodeState {
+        return NodeState::new();
+    }
+}
+
+/// States of nodes of a cluster by their addresses. Clones share the same states.
+#[derive(Debug, Clone, Default)]
+pub struct NodeStates {
+    states: Arc<RwLock<HashMap<IpAddr, NodeState>>>
+}
+
+impl NodeStates {
+    /// Creates new empty states.
+    pub fn new() -> NodeStates {
+        return NodeStates::default();
+    }
+
+    /// Adds state of a node with provided address.
+    pub fn insert(&self, address: IpAddr, state: NodeState) {
+        match self.states.write() {
+            Ok(mut states) => states.insert(address, state),
+            Err(poisoned) => poisoned.into_inner().insert(address, state)
+        };
+    }
+
+    /// Returns state of a node with provided address if it's known.
+    pub fn get(&self, address: &IpAddr) -> Option<NodeState> {
+        return match self.states.read() {
+            Ok(states) => states.get(address).cloned(),
+            Err(poisoned) => poisoned.into_inner().get(address).cloned()
+        };
+    }
+}
@@ -0,0 +1,94 @@
+//! The module contains Rust representation of query traces which Cassandra stores
+//! in `system_traces` keyspace for requests made with tracing flag.
+use std::collections::HashMap;
+use std::net;
+use uuid::Uuid;
+
+use types::{AsRust, IntoRustByName};
+use types::map::Map;
+use types::rows::Row;
+use error;
+
+/// Query trace which consists of a tracing session (`system_traces.sessions`)
+/// and its events (`system_traces.events`).
+#[derive(Debug, Clone)]
+pub struct QueryTrace {
+    /// Tracing id returned by a server in a response frame.
+    pub tracing_id: Uuid,
+    /// Request type, e.g. `Execute CQL3 query`.
+    pub request: String,
+    /// Node that coordinated the request.
+    pub coordinator: net::IpAddr,
+    /// Duration of the request in microseconds.
+    pub duration: i32,
+    /// Millisecond-precision timestamp when the request was started.
+    pub started_at: i64,
+    /// Request parameters, e.g. query string and consistency level.
+    pub parameters: HashMap<String, String>,
+    /// Trace events ordered by their occurrence.
+    pub events: Vec<TraceEvent>
+}
+
+/// Single event of a query trace.
+#[derive(Debug, Clone)]
+pub struct TraceEvent {
+    /// Node where the event occurred.
+    pub source: net::IpAddr,
+    /// Name of the thread where the event occurred.
+    pub thread: String,
+    /// Number of microseconds elapsed on the source node since the request
+    /// was started there.
+    pub source_elapsed: i32,
+    /// Activity description.
+    pub activity: String
+}
+
+impl QueryTrace {
+    /// Builds query trace basing on a row of `system_traces.sessions` and rows
+    /// of `system_traces.events` related to it. It returns `None` if the trace
+    /// is not complete yet, i.e. if session's duration has not been written.
+    pub fn from_rows(tracing_id: Uuid, session: &Row, events: &Vec<Row>)
+        -> error::Result<Option<QueryTrace>> {
+        if session.is_null("duration").unwrap_or(true) {
+            return Ok(None);
+        }
+
+        let duration: i32 = try!(required(session, "duration"));
+        let request: String = try!(required(session, "request"));
+        let coordinator: net::IpAddr = try!(required(session, "coordinator"));
+        let started_at: i64 = try!(required(session, "started_at"));
+        let parameters: HashMap<String, String> = if session.is_null("parameters").unwrap_or(true) {
+            HashMap::new()
+        } else {
+            let map: Map = try!(required(session, "parameters"));
+            try!(map.as_rust())
+        };
+
+        let mut trace_events = Vec::with_capacity(events.len());
+        for event in events.iter() {
+            trace_events.push(TraceEvent {
+                source: try!(required(event, "source")),
+                thread: try!(required(event, "thread")),
+                source_elapsed: try!(required(event, "source_elapsed")),
+                activity: try!(required(event, "activity"))
+            });
+        }
+
+        return Ok(Some(QueryTrace {
+            tracing_id: tracing_id,
+            request: request,
+            coordinator: coordinator,
+            duration: duration,
+            started_at: started_at,
+            parameters: parameters,
+            events: trace_events
+        }));
+    }
+}
+
+fn required<T>(row: &Row, name: &str) -> error::Result<T> where Row: IntoRustByName<T> {
+    return match row.get_by_name(name) {
+        Some(value) => value,
+        None => Err(error::Error::General(format!("Column {} was not found in trace", name)))
+    };
+}
@@ -0,0 +1,114 @@
+//! The module contains policies which define how often a driver tries to reconnect
+//! to a node which is down.
+use std::cmp;
+use std::time::Duration;
+
+use rand::{self, Rng};
+
+/// Policy which decides when next reconnection attempt to a downed node should be made.
+/// A new schedule is created each time a node goes down.
+pub trait ReconnectionPolicy: Send + Sync {
+    /// Creates new schedule of reconnection attempts.
+    fn new_schedule(&self) -> Box<ReconnectionSchedule>;
+}
+
+/// Schedule of reconnection attempts to a node.
+pub trait ReconnectionSchedule: Send {
+    /// Returns a delay before next reconnection attempt.
+    fn next_delay(&mut self) -> Duration;
+}
+
+/// Policy which waits the same delay before each reconnection attempt.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantReconnectionPolicy {
+    delay: Duration
+}
+
+impl ConstantReconnectionPolicy {
+    /// Creates new policy which waits provided delay between reconnection attempts.
+    pub fn new(delay: Duration) -> ConstantReconnectionPolicy {
+        return ConstantReconnectionPolicy { delay: delay };
+    }
+}
+
+impl ReconnectionPolicy for ConstantReconnectionPolicy {
+    fn new_schedule(&self) -> Box<ReconnectionSchedule> {
+        return Box::new(ConstantSchedule { delay: self.delay });
+    }
+}
+
+struct ConstantSchedule {
+    delay: Duration
+}
+
+impl ReconnectionSchedule for ConstantSchedule {
+    fn next_delay(&mut self) -> Duration {
+        return self.delay;
+    }
+}
+
+/// Default jitter of `ExponentialReconnectionPolicy`, i.e. delays vary within +/- 15%.
+pub const DEFAULT_JITTER: f64 = 0.15;
+
+/// Policy which doubles a delay after each failed attempt starting from base delay
+/// until max delay is reached. Each delay is randomly adjusted within jitter
+/// so nodes which went down at the same time aren't reconnected simultaneously.
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialReconnectionPolicy {
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: f64
+}
+
+impl ExponentialReconnectionPolicy {
+    /// Creates new policy with provided base and max delays and default jitter.
+    pub fn new(base_delay: Duration, max_delay: Duration) -> ExponentialReconnectionPolicy {
+        return ExponentialReconnectionPolicy {
+            base_delay: base_delay,
+            max_delay: cmp::max(base_delay, max_delay),
+            jitter: DEFAULT_JITTER
+        };
+    }
+
+    /// Sets jitter as a fraction of a delay, e.g. `0.15` means +/- 15%.
+    /// `0.0` disables jitter. Values are limited by `[0.0, 1.0]`.
+    pub fn jitter(&mut self, jitter: f64) -> &mut Self {
+        self.jitter = jitter.max(0.0).min(1.0);
+        self
+    }
+}
+
+impl ReconnectionPolicy for ExponentialReconnectionPolicy {
+    fn new_schedule(&self) -> Box<ReconnectionSchedule> {
+        return Box::new(ExponentialSchedule {
+            policy: *self,
+            attempt: 0
+        });
+    }
+}
+
+struct ExponentialSchedule {
+    policy: ExponentialReconnectionPolicy,
+    attempt: u32
+}
+
+impl ReconnectionSchedule for ExponentialSchedule {
+    fn next_delay(&mut self) -> Duration {
+        let base = self.policy.base_delay;
+        let max = self.policy.max_delay;
+        // 2^31 is big enough to reach any reasonable max delay
+        let factor = 1u32 << cmp::min(self.attempt, 31);
+        let delay = base.checked_mul(factor).map_or(max, |delay| cmp::min(delay, max));
+        self.attempt = self.attempt.saturating_add(1);
+
+        if self.policy.jitter == 0.0 {
+            return delay;
+        }
+
+        let jitter = self.policy.jitter;
+        let ratio = rand::thread_rng().gen_range(1.0 - jitter, 1.0 + jitter);
+        let nanos = (delay.as_secs() as f64 * 1e9 + delay.subsec_nanos() as f64) * ratio;
+        let jittered = Duration::new((nanos / 1e9) as u64, (nanos % 1e9) as u32);
+        return cmp::min(jittered, max);
+    }
+}
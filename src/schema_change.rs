@@ -0,0 +1,89 @@
+//! The module contains a typed outcome of a schema change. The same outcome is produced
+//! by a result of a DDL query (see `Session::ddl`) and by a `SCHEMA_CHANGE` event
+//! (see `ServerEvent::schema_change`), so both could be handled in the same way.
+use frame::Frame;
+use frame::frame_response::ResponseBody;
+use frame::frame_result::{BodyResResultSchemaChange, ChangeSchemeOptions, ChangeType, ResResultBody,
+                          Target};
+
+/// Schema element which was changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaElement {
+    Keyspace { keyspace: String },
+    Table { keyspace: String, table: String },
+    Type { keyspace: String, name: String },
+    /// A function, there could be several overloads with the same name
+    /// which differ by argument types (as CQL types).
+    Function { keyspace: String, name: String, argument_types: Vec<String> },
+    /// An aggregate, there could be several overloads with the same name
+    /// which differ by argument types (as CQL types).
+    Aggregate { keyspace: String, name: String, argument_types: Vec<String> }
+}
+
+impl SchemaElement {
+    /// Returns a keyspace of the element, or the keyspace itself.
+    pub fn keyspace(&self) -> &str {
+        return match *self {
+            SchemaElement::Keyspace { ref keyspace } => keyspace,
+            SchemaElement::Table { ref keyspace, .. } => keyspace,
+            SchemaElement::Type { ref keyspace, .. } => keyspace,
+            SchemaElement::Function { ref keyspace, .. } => keyspace,
+            SchemaElement::Aggregate { ref keyspace, .. } => keyspace
+        };
+    }
+}
+
+/// Outcome of a schema change: what was done and to which element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaChangeOutcome {
+    pub change_type: ChangeType,
+    pub element: SchemaElement
+}
+
+impl SchemaChangeOutcome {
+    /// Returns an outcome of a schema change if provided frame is a schema change result.
+    pub fn from_response(response: &Frame) -> Option<SchemaChangeOutcome> {
+        return match response.get_body() {
+            ResponseBody::Result(ResResultBody::SchemaChange(change)) => Some(SchemaChangeOutcome::from(change)),
+            _ => None
+        };
+    }
+
+    /// Returns a keyspace which was changed or which contains changed element.
+    pub fn keyspace(&self) -> &str {
+        return self.element.keyspace();
+    }
+}
+
+impl From<BodyResResultSchemaChange> for SchemaChangeOutcome {
+    fn from(change: BodyResResultSchemaChange) -> SchemaChangeOutcome {
+        let element = match (change.target, change.options) {
+            (_, ChangeSchemeOptions::Keyspace(keyspace)) => SchemaElement::Keyspace { keyspace: keyspace },
+            (Target::Type, ChangeSchemeOptions::Table((keyspace, name))) => SchemaElement::Type {
+                keyspace: keyspace,
+                name: name
+            },
+            (_, ChangeSchemeOptions::Table((keyspace, table))) => SchemaElement::Table {
+                keyspace: keyspace,
+                table: table
+            },
+            (Target::Aggregate, ChangeSchemeOptions::Function((keyspace, name, argument_types))) => {
+                SchemaElement::Aggregate {
+                    keyspace: keyspace,
+                    name: name,
+                    argument_types: argument_types
+                }
+            },
+            (_, ChangeSchemeOptions::Function((keyspace, name, argument_types))) => SchemaElement::Function {
+                keyspace: keyspace,
+                name: name,
+                argument_types: argument_types
+            }
+        };
+
+        return SchemaChangeOutcome {
+            change_type: change.change_type,
+            element: element
+        };
+    }
+}
@@ -0,0 +1,705 @@
+//! Integration with [serde](https://serde.rs), available with `serde` feature.
+//!
+//! Rows, result sets and `CqlValue`s could be deserialized into any `Deserialize` type:
+//! rows and UDTs are read as structs (or maps) by names of columns and fields, lists,
+//! sets and tuples as sequences, maps as maps and nulls as `None`.
+//!
+//! Any `Serialize` type could be bound as a query value. Cassandra types are richer
+//! than serde data model, so a value is encoded basing on a type of a bind marker,
+//! e.g. taken from metadata of a prepared query. Decimals are read and encoded as
+//! `{unscaled, scale}` maps, unit variants of enums as their names.
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::net::IpAddr;
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::ser::{self, Serialize};
+use uuid::Uuid;
+
+use frame::Frame;
+use frame::frame_result::{ColSpec, ColType, ColTypeOption, ColTypeOptionValue};
+use types::to_int;
+use types::cql_value::CqlValue;
+use types::duration::Duration;
+use types::rows::Row;
+use types::udt::UdtBuilder;
+use types::value::{Bytes, Value};
+use IntoBytes;
+use error::{Error, Result};
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Error {
+        return Error::General(msg.to_string());
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Error {
+        return Error::General(msg.to_string());
+    }
+}
+
+/// Deserializes a value decoded without a known schema.
+pub fn from_value<T: DeserializeOwned>(value: CqlValue) -> Result<T> {
+    return T::deserialize(value);
+}
+
+/// Deserializes a row, columns are read by their names as fields of a struct
+/// or entries of a map.
+pub fn from_row<T: DeserializeOwned>(row: &Row) -> Result<T> {
+    let values = try!(row.values());
+    return T::deserialize(MapDeserializer::new(values.into_iter()));
+}
+
+/// Deserializes every row of a result set.
+pub fn from_rows<T: DeserializeOwned>(rows: &[Row]) -> Result<Vec<T>> {
+    return rows.iter().map(from_row).collect();
+}
+
+/// Deserializes every row of a result frame.
+pub fn from_frame<T: DeserializeOwned>(frame: &Frame) -> Result<Vec<T>> {
+    return match frame.get_body().into_rows() {
+        Some(rows) => from_rows(&rows),
+        None => Err(Error::General("Frame doesn't contain rows".to_string()))
+    };
+}
+
+impl<'de> IntoDeserializer<'de, Error> for CqlValue {
+    type Deserializer = CqlValue;
+
+    fn into_deserializer(self) -> CqlValue {
+        return self;
+    }
+}
+
+impl<'de> de::Deserializer<'de> for CqlValue {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        return match self {
+            CqlValue::Custom { bytes, .. } => visitor.visit_byte_buf(bytes),
+            CqlValue::Ascii(value) => visitor.visit_string(value),
+            CqlValue::Bigint(value) => visitor.visit_i64(value),
+            CqlValue::Blob(value) => visitor.visit_byte_buf(value),
+            CqlValue::Boolean(value) => visitor.visit_bool(value),
+            CqlValue::Counter(value) => visitor.visit_i64(value),
+            CqlValue::Decimal { unscaled, scale } => {
+                let fields = vec![("unscaled", CqlValue::Varint(unscaled)), ("scale", CqlValue::Int(scale))];
+                de::Deserializer::deserialize_any(MapDeserializer::new(fields.into_iter()), visitor)
+            },
+            CqlValue::Double(value) => visitor.visit_f64(value),
+            CqlValue::Float(value) => visitor.visit_f32(value),
+            CqlValue::Int(value) => visitor.visit_i32(value),
+            CqlValue::Timestamp(value) => visitor.visit_i64(value),
+            CqlValue::Uuid(value) => visitor.visit_string(value.hyphenated().to_string()),
+            CqlValue::Text(value) => visitor.visit_string(value),
+            CqlValue::Varint(value) => visitor.visit_byte_buf(value),
+            CqlValue::Timeuuid(value) => visitor.visit_string(value.hyphenated().to_string()),
+            CqlValue::Inet(value) => visitor.visit_string(value.to_string()),
+            CqlValue::Date(value) => visitor.visit_i32(value),
+            CqlValue::Time(value) => visitor.visit_i64(value),
+            CqlValue::Smallint(value) => visitor.visit_i16(value),
+            CqlValue::Tinyint(value) => visitor.visit_i8(value),
+            CqlValue::Duration(value) => {
+                let fields = vec![("months", CqlValue::Int(value.months)),
+                                  ("days", CqlValue::Int(value.days)),
+                                  ("nanoseconds", CqlValue::Bigint(value.nanoseconds))];
+                de::Deserializer::deserialize_any(MapDeserializer::new(fields.into_iter()), visitor)
+            },
+            CqlValue::List(items) | CqlValue::Set(items) | CqlValue::Tuple(items) => {
+                de::Deserializer::deserialize_any(SeqDeserializer::new(items.into_iter()), visitor)
+            },
+            CqlValue::Map(entries) => {
+                de::Deserializer::deserialize_any(MapDeserializer::new(entries.into_iter()), visitor)
+            },
+            CqlValue::Udt { fields, .. } => {
+                de::Deserializer::deserialize_any(MapDeserializer::new(fields.into_iter()), visitor)
+            },
+            CqlValue::Null => visitor.visit_unit()
+        };
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        return match self {
+            CqlValue::Null => visitor.visit_none(),
+            value => visitor.visit_some(value)
+        };
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        return visitor.visit_newtype_struct(self);
+    }
+
+    /// Blobs and varints (e.g. unscaled values of decimals) could be read as `Vec<u8>` as well.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        return match self {
+            CqlValue::Blob(bytes) | CqlValue::Varint(bytes) => de::Deserializer::deserialize_any(SeqDeserializer::new(bytes.into_iter()), visitor),
+            value => value.deserialize_any(visitor)
+        };
+    }
+
+    /// Unit variants of enums are read from text values by their names.
+    fn deserialize_enum<V: Visitor<'de>>(self,
+                                         _name: &'static str,
+                                         _variants: &'static [&'static str],
+                                         visitor: V) -> Result<V::Value> {
+        return match self {
+            CqlValue::Ascii(value) | CqlValue::Text(value) => visitor.visit_enum(value.into_deserializer()),
+            value => value.deserialize_any(visitor)
+        };
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Encodes a value as a value of provided type, e.g. of a bind marker of a prepared query.
+/// `None` and `()` are encoded as nulls, UDTs and durations could be encoded from structs
+/// and maps, uuids and inet addresses from strings.
+pub fn to_value<T: Serialize + ?Sized>(value: &T, col_type: &ColTypeOption) -> Result<Value> {
+    return encode(try!(value.serialize(Serializer)), col_type);
+}
+
+/// Encodes values of bind markers. If a value is serialized as a struct or a map, its fields
+/// are bound by names of markers, fields which don't match any marker are ignored and
+/// markers which don't match any field are not set. A sequence, e.g. a tuple, is bound
+/// by positions of markers.
+pub fn to_values<T: Serialize + ?Sized>(value: &T, col_specs: &[ColSpec]) -> Result<Vec<Value>> {
+    let items = match try!(value.serialize(Serializer)) {
+        Untyped::Seq(items) => {
+            if items.len() != col_specs.len() {
+                return Err(Error::General(format!("{} values are provided for {} bind markers",
+                                                  items.len(),
+                                                  col_specs.len())));
+            }
+            items
+        },
+        Untyped::Map(entries) => {
+            let mut fields = HashMap::new();
+            for (key, value) in entries {
+                fields.insert(try!(key.into_name()), value);
+            }
+            col_specs.iter()
+                .map(|col_spec| fields.remove(col_spec.name.as_str()).unwrap_or(Untyped::NotSet))
+                .collect()
+        },
+        value => return Err(Error::General(format!("{} cannot be bound by bind markers", value.kind())))
+    };
+
+    return items.into_iter()
+        .zip(col_specs.iter())
+        .map(|(item, col_spec)| encode(item, &col_spec.col_type))
+        .collect();
+}
+
+/// Untyped representation of a serialized value, it's encoded once a type is known.
+enum Untyped {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<Untyped>),
+    Map(Vec<(Untyped, Untyped)>),
+    Null,
+    NotSet
+}
+
+impl Untyped {
+    fn kind(&self) -> &'static str {
+        return match *self {
+            Untyped::Bool(_) => "Boolean",
+            Untyped::Int(_) | Untyped::UInt(_) => "Integer",
+            Untyped::Float(_) => "Float",
+            Untyped::Str(_) => "String",
+            Untyped::Bytes(_) => "Bytes",
+            Untyped::Seq(_) => "Sequence",
+            Untyped::Map(_) => "Map",
+            Untyped::Null | Untyped::NotSet => "Null"
+        };
+    }
+
+    fn into_name(self) -> Result<String> {
+        return match self {
+            Untyped::Str(name) => Ok(name),
+            value => Err(Error::General(format!("{} cannot be a name of a field", value.kind())))
+        };
+    }
+
+    fn as_integer(&self) -> Option<i64> {
+        return match *self {
+            Untyped::Int(value) => Some(value),
+            Untyped::UInt(value) => i64::try_from(value).ok(),
+            _ => None
+        };
+    }
+}
+
+fn mismatch<T>(value: &Untyped, col_type: &ColTypeOption) -> Result<T> {
+    return Err(Error::General(format!("{} cannot be encoded as {:?}", value.kind(), col_type.id)));
+}
+
+fn integer<T: TryFrom<i64>>(value: &Untyped, col_type: &ColTypeOption) -> Result<T> {
+    return match value.as_integer() {
+        Some(integer) => T::try_from(integer)
+            .map_err(|_| Error::General(format!("{} is out of range of {:?}", integer, col_type.id))),
+        None => mismatch(value, col_type)
+    };
+}
+
+fn encode(value: Untyped, col_type: &ColTypeOption) -> Result<Value> {
+    return match value {
+        Untyped::Null => Ok(Value::new_null()),
+        Untyped::NotSet => Ok(Value::new_not_set()),
+        value => encode_bytes(value, col_type).map(Value::from)
+    };
+}
+
+fn encode_bytes(value: Untyped, col_type: &ColTypeOption) -> Result<Bytes> {
+    if Duration::is_duration_type(col_type) {
+        return encode_duration(value, col_type).map(Bytes::from);
+    }
+
+    let bytes = match (&col_type.id, value) {
+        (&ColType::Custom, Untyped::Bytes(bytes)) | (&ColType::Blob, Untyped::Bytes(bytes)) => Bytes::new(bytes),
+        (&ColType::Blob, Untyped::Seq(items)) => {
+            let mut bytes = vec![];
+            for item in items.iter() {
+                bytes.push(try!(integer::<u8>(item, col_type)));
+            }
+            Bytes::new(bytes)
+        },
+        (&ColType::Ascii, Untyped::Str(value)) | (&ColType::Varchar, Untyped::Str(value)) => Bytes::from(value),
+        (&ColType::Boolean, Untyped::Bool(value)) => Bytes::from(value),
+        (&ColType::Tinyint, ref value) => Bytes::from(try!(integer::<i8>(value, col_type))),
+        (&ColType::Smallint, ref value) => Bytes::from(try!(integer::<i16>(value, col_type))),
+        (&ColType::Int, ref value) => Bytes::from(try!(integer::<i32>(value, col_type))),
+        (&ColType::Date, ref value) => {
+            // dates are unsigned, but they're decoded as `i32`
+            let date = try!(integer::<i64>(value, col_type));
+            if date < i32::min_value() as i64 || date > u32::max_value() as i64 {
+                return Err(Error::General(format!("{} is out of range of {:?}", date, col_type.id)));
+            }
+            Bytes::from(date as i32)
+        },
+        (&ColType::Bigint, ref value) |
+        (&ColType::Counter, ref value) |
+        (&ColType::Timestamp, ref value) |
+        (&ColType::Time, ref value) => Bytes::from(try!(integer::<i64>(value, col_type))),
+        (&ColType::Varint, Untyped::Bytes(bytes)) => Bytes::new(bytes),
+        (&ColType::Varint, ref value) => Bytes::new(varint(try!(integer::<i64>(value, col_type)))),
+        (&ColType::Float, Untyped::Float(value)) => Bytes::from(value as f32),
+        (&ColType::Float, ref value) => Bytes::from(try!(integer::<i64>(value, col_type)) as f32),
+        (&ColType::Double, Untyped::Float(value)) => Bytes::from(value),
+        (&ColType::Double, ref value) => Bytes::from(try!(integer::<i64>(value, col_type)) as f64),
+        (&ColType::Uuid, Untyped::Str(value)) | (&ColType::Timeuuid, Untyped::Str(value)) => {
+            Bytes::from(try!(Uuid::parse_str(value.as_str())
+                .map_err(|err| Error::General(format!("Invalid uuid {}: {:?}", value, err)))))
+        },
+        (&ColType::Uuid, Untyped::Bytes(bytes)) | (&ColType::Timeuuid, Untyped::Bytes(bytes)) => {
+            Bytes::from(try!(Uuid::from_bytes(bytes.as_slice())
+                .map_err(|err| Error::General(format!("Invalid uuid: {:?}", err)))))
+        },
+        (&ColType::Inet, Untyped::Str(value)) => {
+            Bytes::from(try!(value.parse::<IpAddr>()
+                .map_err(|err| Error::General(format!("Invalid inet {}: {}", value, err)))))
+        },
+        (&ColType::List, Untyped::Seq(items)) | (&ColType::Set, Untyped::Seq(items)) => {
+            let item_type = match col_type.value {
+                Some(ColTypeOptionValue::CList(ref item_type)) |
+                Some(ColTypeOptionValue::CSet(ref item_type)) => item_type,
+                _ => return Err(Error::General(format!("{:?} misses its item type", col_type.id)))
+            };
+            let mut bytes = to_int(items.len() as i64);
+            for item in items {
+                bytes.extend_from_slice(try!(encode(item, item_type)).into_cbytes().as_slice());
+            }
+            Bytes::new(bytes)
+        },
+        (&ColType::Map, Untyped::Map(entries)) => {
+            let (key_type, value_type) = match col_type.value {
+                Some(ColTypeOptionValue::CMap((ref key_type, ref value_type))) => (key_type, value_type),
+                _ => return Err(Error::General("Map misses its key and value types".to_string()))
+            };
+            let mut bytes = to_int(entries.len() as i64);
+            for (key, value) in entries {
+                bytes.extend_from_slice(try!(encode(key, key_type)).into_cbytes().as_slice());
+                bytes.extend_from_slice(try!(encode(value, value_type)).into_cbytes().as_slice());
+            }
+            Bytes::new(bytes)
+        },
+        (&ColType::Udt, Untyped::Map(fields)) => {
+            let mut builder = try!(UdtBuilder::from_col_type(col_type));
+            for (name, value) in fields {
+                let name = try!(name.into_name());
+                let value = match builder.field_type(name.as_str()) {
+                    Some(field_type) => try!(encode(value, field_type)),
+                    None => return Err(Error::General(format!("UDT doesn't have field {}", name)))
+                };
+                builder = builder.field(name.as_str(), value);
+            }
+            try!(builder.finalize())
+        },
+        (&ColType::Decimal, Untyped::Map(fields)) => try!(encode_decimal(fields, col_type)),
+        (&ColType::Tuple, Untyped::Seq(elements)) => {
+            let element_types = match col_type.value {
+                Some(ColTypeOptionValue::TupleType(ref tuple)) => &tuple.types,
+                _ => return Err(Error::General("Tuple misses its element types".to_string()))
+            };
+            if elements.len() != element_types.len() {
+                return Err(Error::General(format!("{} elements are provided for a tuple of {}",
+                                                  elements.len(),
+                                                  element_types.len())));
+            }
+            let mut bytes = vec![];
+            for (element, element_type) in elements.into_iter().zip(element_types.iter()) {
+                bytes.extend_from_slice(try!(encode(element, element_type)).into_cbytes().as_slice());
+            }
+            Bytes::new(bytes)
+        },
+        (_, value) => return mismatch(&value, col_type)
+    };
+
+    return Ok(bytes);
+}
+
+/// Encodes a decimal from `{unscaled, scale}` map in the same way it's decoded. Unscaled
+/// value is either an integer or big-endian two's complement bytes.
+fn encode_decimal(fields: Vec<(Untyped, Untyped)>, col_type: &ColTypeOption) -> Result<Bytes> {
+    let mut unscaled = None;
+    let mut scale = None;
+    for (name, value) in fields {
+        match try!(name.into_name()).as_str() {
+            "unscaled" => {
+                let unscaled_type = ColTypeOption {
+                    id: match value {
+                        Untyped::Seq(_) => ColType::Blob,
+                        _ => ColType::Varint
+                    },
+                    value: None
+                };
+                unscaled = Some(try!(encode_bytes(value, &unscaled_type)).into_plain());
+            },
+            "scale" => scale = Some(try!(integer::<i32>(&value, col_type))),
+            name => return Err(Error::General(format!("Decimal doesn't have field {}", name)))
+        }
+    }
+
+    return match (unscaled, scale) {
+        (Some(unscaled), Some(scale)) => {
+            let mut bytes = to_int(scale as i64);
+            bytes.extend_from_slice(unscaled.as_slice());
+            Ok(Bytes::new(bytes))
+        },
+        _ => Err(Error::General("Decimal requires both unscaled and scale fields".to_string()))
+    };
+}
+
+fn encode_duration(value: Untyped, col_type: &ColTypeOption) -> Result<Duration> {
+    let fields = match value {
+        Untyped::Map(fields) => fields,
+        value => return mismatch(&value, col_type)
+    };
+    let mut duration = Duration::new(0, 0, 0);
+    for (name, value) in fields {
+        match try!(name.into_name()).as_str() {
+            "months" => duration.months = try!(integer(&value, col_type)),
+            "days" => duration.days = try!(integer(&value, col_type)),
+            "nanoseconds" => duration.nanoseconds = try!(integer(&value, col_type)),
+            name => return Err(Error::General(format!("Duration doesn't have field {}", name)))
+        }
+    }
+    return Ok(duration);
+}
+
+/// Big-endian two's complement representation without redundant leading bytes.
+fn varint(value: i64) -> Vec<u8> {
+    let mut bytes = Bytes::from(value).into_plain();
+    while bytes.len() > 1 {
+        let redundant = (bytes[0] == 0 && bytes[1] & 0x80 == 0) || (bytes[0] == 0xff && bytes[1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        bytes.remove(0);
+    }
+    return bytes;
+}
+
+/// Serializes values into `Untyped` ones.
+struct Serializer;
+
+struct SeqSerializer {
+    items: Vec<Untyped>
+}
+
+struct MapSerializer {
+    entries: Vec<(Untyped, Untyped)>,
+    key: Option<Untyped>
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Untyped;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Untyped> {
+        return Ok(Untyped::Bool(v));
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Untyped> {
+        return Ok(Untyped::Int(v as i64));
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Untyped> {
+        return Ok(Untyped::Int(v as i64));
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Untyped> {
+        return Ok(Untyped::Int(v as i64));
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Untyped> {
+        return Ok(Untyped::Int(v));
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Untyped> {
+        return Ok(Untyped::UInt(v as u64));
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Untyped> {
+        return Ok(Untyped::UInt(v as u64));
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Untyped> {
+        return Ok(Untyped::UInt(v as u64));
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Untyped> {
+        return Ok(Untyped::UInt(v));
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Untyped> {
+        return Ok(Untyped::Float(v as f64));
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Untyped> {
+        return Ok(Untyped::Float(v));
+    }
+
+    fn serialize_char(self, v: char) -> Result<Untyped> {
+        return Ok(Untyped::Str(v.to_string()));
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Untyped> {
+        return Ok(Untyped::Str(v.to_string()));
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Untyped> {
+        return Ok(Untyped::Bytes(v.to_vec()));
+    }
+
+    fn serialize_none(self) -> Result<Untyped> {
+        return Ok(Untyped::Null);
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Untyped> {
+        return value.serialize(self);
+    }
+
+    fn serialize_unit(self) -> Result<Untyped> {
+        return Ok(Untyped::Null);
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Untyped> {
+        return Ok(Untyped::Null);
+    }
+
+    /// Unit variants are encoded as their names.
+    fn serialize_unit_variant(self,
+                              _name: &'static str,
+                              _variant_index: u32,
+                              variant: &'static str) -> Result<Untyped> {
+        return Ok(Untyped::Str(variant.to_string()));
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Untyped> {
+        return value.serialize(self);
+    }
+
+    /// Variants with data can't be encoded, as there is no place for their names.
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self,
+                                                        name: &'static str,
+                                                        _variant_index: u32,
+                                                        variant: &'static str,
+                                                        _value: &T) -> Result<Untyped> {
+        return Err(variant_error(name, variant));
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer> {
+        return Ok(SeqSerializer { items: Vec::with_capacity(len.unwrap_or(0)) });
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer> {
+        return self.serialize_seq(Some(len));
+    }
+
+    fn serialize_tuple_variant(self,
+                               name: &'static str,
+                               _variant_index: u32,
+                               variant: &'static str,
+                               _len: usize) -> Result<SeqSerializer> {
+        return Err(variant_error(name, variant));
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer> {
+        return Ok(MapSerializer {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None
+        });
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer> {
+        return self.serialize_map(Some(len));
+    }
+
+    fn serialize_struct_variant(self,
+                                name: &'static str,
+                                _variant_index: u32,
+                                variant: &'static str,
+                                _len: usize) -> Result<MapSerializer> {
+        return Err(variant_error(name, variant));
+    }
+}
+
+fn variant_error(name: &str, variant: &str) -> Error {
+    return Error::General(format!("Enum variant with data {}::{} cannot be encoded", name, variant));
+}
+
+impl SeqSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.items.push(try!(value.serialize(Serializer)));
+        return Ok(());
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Untyped;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<Untyped> {
+        return Ok(Untyped::Seq(self.items));
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Untyped;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<Untyped> {
+        return Ok(Untyped::Seq(self.items));
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Untyped;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<Untyped> {
+        return Ok(Untyped::Seq(self.items));
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Untyped;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        return self.push(value);
+    }
+
+    fn end(self) -> Result<Untyped> {
+        return Ok(Untyped::Seq(self.items));
+    }
+}
+
+impl MapSerializer {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<()> {
+        self.entries.push((Untyped::Str(key.to_string()), try!(value.serialize(Serializer))));
+        return Ok(());
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Untyped;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        self.key = Some(try!(key.serialize(Serializer)));
+        return Ok(());
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = match self.key.take() {
+            Some(key) => key,
+            None => return Err(Error::General("A value of a map is serialized before its key".to_string()))
+        };
+        self.entries.push((key, try!(value.serialize(Serializer))));
+        return Ok(());
+    }
+
+    fn end(self) -> Result<Untyped> {
+        return Ok(Untyped::Map(self.entries));
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Untyped;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        return self.insert(key, value);
+    }
+
+    fn end(self) -> Result<Untyped> {
+        return Ok(Untyped::Map(self.entries));
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = Untyped;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        return self.insert(key, value);
+    }
+
+    fn end(self) -> Result<Untyped> {
+        return Ok(Untyped::Map(self.entries));
+    }
+}
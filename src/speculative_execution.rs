@@ -0,0 +1,76 @@
+//! The module contains policies of speculative execution. If an idempotent request
+//! isn't answered within a delay, the same request is sent to the next node of
+//! a query plan and the first response is used.
+use std::time::Duration;
+
+/// Policy which decides when speculative executions of a request should be started.
+/// A new plan is created for each request.
+pub trait SpeculativeExecutionPolicy: Send + Sync {
+    /// Creates new plan of speculative executions of a request.
+    fn new_plan(&self) -> Box<SpeculativeExecutionPlan>;
+}
+
+/// Plan of speculative executions of a single request.
+pub trait SpeculativeExecutionPlan: Send {
+    /// Returns a delay after which next speculative execution should be started
+    /// or `None` if no more executions should be started.
+    fn next_execution(&mut self) -> Option<Duration>;
+}
+
+/// Policy which never starts speculative executions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoSpeculativeExecutionPolicy;
+
+impl SpeculativeExecutionPolicy for NoSpeculativeExecutionPolicy {
+    fn new_plan(&self) -> Box<SpeculativeExecutionPlan> {
+        return Box::new(ConstantPlan {
+            delay: Duration::from_secs(0),
+            remaining: 0
+        });
+    }
+}
+
+/// Policy which starts up to `max_executions` speculative executions with the same
+/// delay between them, i.e. the first one is started `delay` after the initial request,
+/// the second one after another `delay` and so on.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantSpeculativeExecutionPolicy {
+    delay: Duration,
+    max_executions: usize
+}
+
+impl ConstantSpeculativeExecutionPolicy {
+    /// Creates new policy with provided delay and max number of speculative executions
+    /// per request (the initial request isn't counted).
+    pub fn new(delay: Duration, max_executions: usize) -> ConstantSpeculativeExecutionPolicy {
+        return ConstantSpeculativeExecutionPolicy {
+            delay: delay,
+            max_executions: max_executions
+        };
+    }
+}
+
+impl SpeculativeExecutionPolicy for ConstantSpeculativeExecutionPolicy {
+    fn new_plan(&self) -> Box<SpeculativeExecutionPlan> {
+        return Box::new(ConstantPlan {
+            delay: self.delay,
+            remaining: self.max_executions
+        });
+    }
+}
+
+struct ConstantPlan {
+    delay: Duration,
+    remaining: usize
+}
+
+impl SpeculativeExecutionPlan for ConstantPlan {
+    fn next_execution(&mut self) -> Option<Duration> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        self.remaining -= 1;
+        return Some(self.delay);
+    }
+}
@@ -0,0 +1,67 @@
+use frame::Opcode;
+use frame::frame_batch::BatchQuerySubject;
+use frame::frame_request::RequestBody;
+use testing::MockRequest;
+
+/// Predicate which decides if a scripted rule should be applied to a request.
+pub struct RequestMatcher {
+    predicate: Box<Fn(&MockRequest) -> bool + Send + Sync>
+}
+
+impl RequestMatcher {
+    /// Matches any request.
+    pub fn any() -> RequestMatcher {
+        return RequestMatcher::custom(|_| true);
+    }
+
+    /// Matches requests of provided type, e.g. `Opcode::Options`.
+    pub fn opcode(opcode: Opcode) -> RequestMatcher {
+        return RequestMatcher::custom(move |request| request.opcode == opcode);
+    }
+
+    /// Matches `query` and `prepare` requests which query string is equal to `query`.
+    pub fn query(query: &str) -> RequestMatcher {
+        let query = query.to_string();
+        return RequestMatcher::custom(move |request| {
+            return request.body.query_string() == Some(query.as_str());
+        });
+    }
+
+    /// Matches `query` and `prepare` requests which query string contains `pattern`,
+    /// as well as batches which contain such query string.
+    pub fn query_contains(pattern: &str) -> RequestMatcher {
+        let pattern = pattern.to_string();
+        return RequestMatcher::custom(move |request| {
+            if let RequestBody::Batch(ref batch) = request.body {
+                return batch.queries.iter().any(|query| match query.subject {
+                    BatchQuerySubject::QueryString(ref query) => query.as_str().contains(pattern.as_str()),
+                    BatchQuerySubject::PreparedId(_) => false
+                });
+            }
+
+            return request.body
+                .query_string()
+                .map(|query| query.contains(pattern.as_str()))
+                .unwrap_or(false);
+        });
+    }
+
+    /// Matches `execute` requests of a prepared query with provided id.
+    pub fn execute(id: Vec<u8>) -> RequestMatcher {
+        return RequestMatcher::custom(move |request| match request.body {
+            RequestBody::Execute(ref body) => body.id.as_slice() == id.as_slice(),
+            _ => false
+        });
+    }
+
+    /// Matches requests which satisfy provided predicate.
+    pub fn custom<F>(predicate: F) -> RequestMatcher
+        where F: Fn(&MockRequest) -> bool + Send + Sync + 'static {
+        return RequestMatcher { predicate: Box::new(predicate) };
+    }
+
+    /// Indicates if the matcher matches provided request.
+    pub fn matches(&self, request: &MockRequest) -> bool {
+        return (self.predicate)(request);
+    }
+}
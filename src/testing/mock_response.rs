@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+use IntoBytes;
+use frame::Opcode;
+use frame::frame_auth_success::BodyReqAuthSuccess;
+use frame::frame_authenticate::BodyResAuthenticate;
+use frame::frame_error::{CDRSError, AdditionalErrorInfo, SimpleError};
+use frame::frame_result::*;
+use frame::frame_supported::BodyResSupported;
+use types::{CBytes, CBytesMap, CBytesShort, CString};
+
+/// Action which a mock server performs in response to a matched request.
+#[derive(Debug, Clone)]
+pub enum MockAction {
+    /// Sends a response immediately.
+    Respond(MockResponse),
+    /// Sends a response after a delay, e.g. to test request timeouts.
+    Delay(Duration, MockResponse),
+    /// Sends only the first half of a response frame and stalls, e.g. to test reads
+    /// which time out in the middle of a frame.
+    RespondPartially(MockResponse),
+    /// Doesn't respond at all.
+    Ignore,
+    /// Closes the connection which the request was received on.
+    CloseConnection
+}
+
+impl From<MockResponse> for MockAction {
+    fn from(response: MockResponse) -> MockAction {
+        return MockAction::Respond(response);
+    }
+}
+
+/// Canned response of a mock server. Its body is encoded with the same frame
+/// types which the driver uses to decode responses.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub opcode: Opcode,
+    pub body: Vec<u8>,
+    pub tracing_id: Option<Uuid>,
+    pub warnings: Vec<String>,
+    pub custom_payload: CBytesMap
+}
+
+impl MockResponse {
+    /// Creates new response of provided type and body.
+    pub fn new(opcode: Opcode, body: Vec<u8>) -> MockResponse {
+        return MockResponse {
+            opcode: opcode,
+            body: body,
+            tracing_id: None,
+            warnings: vec![],
+            custom_payload: HashMap::new()
+        };
+    }
+
+    /// `READY` response.
+    pub fn ready() -> MockResponse {
+        return MockResponse::new(Opcode::Ready, vec![]);
+    }
+
+    /// `SUPPORTED` response with provided options.
+    pub fn supported(options: HashMap<String, Vec<String>>) -> MockResponse {
+        return MockResponse::new(Opcode::Supported, BodyResSupported { data: options }.into_cbytes());
+    }
+
+    /// `AUTHENTICATE` response which requires authentication with provided authenticator class.
+    pub fn authenticate(authenticator: &str) -> MockResponse {
+        let body = BodyResAuthenticate { data: CString::new(authenticator.to_string()) };
+        return MockResponse::new(Opcode::Authenticate, body.into_cbytes());
+    }
+
+    /// `AUTH_SUCCESS` response.
+    pub fn auth_success() -> MockResponse {
+        return MockResponse::new(Opcode::AuthSuccess, BodyReqAuthSuccess {}.into_cbytes());
+    }
+
+    /// `RESULT` response with provided body.
+    pub fn result(result: ResResultBody) -> MockResponse {
+        return MockResponse::new(Opcode::Result, result.into_cbytes());
+    }
+
+    /// `RESULT` response of kind `Void`.
+    pub fn void() -> MockResponse {
+        return MockResponse::result(ResResultBody::Void(BodyResResultVoid::new()));
+    }
+
+    /// `RESULT` response of kind `Rows`.
+    pub fn rows(rows: MockRows) -> MockResponse {
+        return MockResponse::result(ResResultBody::Rows(rows.into_body()));
+    }
+
+    /// `RESULT` response of kind `SetKeyspace`.
+    pub fn set_keyspace(keyspace: &str) -> MockResponse {
+        let body = BodyResResultSetKeyspace::new(CString::new(keyspace.to_string()));
+        return MockResponse::result(ResResultBody::SetKeyspace(body));
+    }
+
+    /// `RESULT` response of kind `Prepared`. `bind_markers` are names and types of
+    /// query's bind markers, result metadata is not sent.
+    pub fn prepared(id: Vec<u8>, bind_markers: Vec<(&str, ColTypeOption)>) -> MockResponse {
+        let col_specs: Vec<ColSpec> = bind_markers.into_iter()
+            .map(|(name, col_type)| ColSpec {
+                ksname: None,
+                tablename: None,
+                name: CString::new(name.to_string()),
+                col_type: col_type
+            })
+            .collect();
+        let metadata = PreparedMetadata {
+            flags: RowsMetadataFlag::set_global_table_space(0),
+            columns_count: col_specs.len() as i32,
+            pk_count: 0,
+            pk_indexes: vec![],
+            global_table_spec: Some((CString::new(MOCK_KEYSPACE.to_string()),
+                CString::new(MOCK_TABLE.to_string()))),
+            col_specs: col_specs
+        };
+        let result_metadata = RowsMetadata {
+            flags: RowsMetadataFlag::set_no_metadata(0),
+            columns_count: 0,
+            paging_state: None,
+            global_table_space: None,
+            col_specs: vec![]
+        };
+        let body = BodyResResultPrepared {
+            id: CBytesShort::new(id),
+            metadata: metadata,
+            result_metadata: result_metadata
+        };
+
+        return MockResponse::result(ResResultBody::Prepared(body));
+    }
+
+    /// `RESULT` response of kind `SchemaChange`.
+    pub fn schema_change(change: BodyResResultSchemaChange) -> MockResponse {
+        return MockResponse::result(ResResultBody::SchemaChange(change));
+    }
+
+    /// `ERROR` response.
+    pub fn error(error: CDRSError) -> MockResponse {
+        return MockResponse::new(Opcode::Error, error.into_cbytes());
+    }
+
+    /// `ERROR` response which doesn't carry additional info, e.g. `Server`, `Overloaded`
+    /// or `Invalid` error.
+    pub fn simple_error(additional_info: fn(SimpleError) -> AdditionalErrorInfo, message: &str)
+        -> MockResponse {
+        let additional_info = additional_info(SimpleError {});
+        return MockResponse::error(CDRSError {
+            error_code: additional_info.error_code(),
+            message: CString::new(message.to_string()),
+            additional_info: additional_info
+        });
+    }
+
+    /// Adds tracing id to the response.
+    pub fn with_tracing_id(mut self, tracing_id: Uuid) -> MockResponse {
+        self.tracing_id = Some(tracing_id);
+        return self;
+    }
+
+    /// Adds warnings to the response.
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> MockResponse {
+        self.warnings = warnings;
+        return self;
+    }
+
+    /// Adds custom payload to the response.
+    pub fn with_custom_payload(mut self, custom_payload: CBytesMap) -> MockResponse {
+        self.custom_payload = custom_payload;
+        return self;
+    }
+}
+
+/// Keyspace name which is used in metadata of mock responses by default.
+pub const MOCK_KEYSPACE: &'static str = "mock";
+/// Table name which is used in metadata of mock responses by default.
+pub const MOCK_TABLE: &'static str = "mock";
+
+/// Builder of `Rows` results.
+#[derive(Debug, Clone)]
+pub struct MockRows {
+    keyspace: String,
+    table: String,
+    columns: Vec<(String, ColTypeOption)>,
+    rows: Vec<Vec<CBytes>>,
+    paging_state: Option<CBytes>
+}
+
+impl MockRows {
+    /// Creates new empty result of a table `keyspace.table`.
+    pub fn new(keyspace: &str, table: &str) -> MockRows {
+        return MockRows {
+            keyspace: keyspace.to_string(),
+            table: table.to_string(),
+            columns: vec![],
+            rows: vec![],
+            paging_state: None
+        };
+    }
+
+    /// Adds a column of a simple type, e.g. `ColType::Int`.
+    pub fn column(self, name: &str, col_type: ColType) -> MockRows {
+        return self.column_with_type(name, ColTypeOption { id: col_type, value: None });
+    }
+
+    /// Adds a column of any type including collections and UDTs.
+    pub fn column_with_type(mut self, name: &str, col_type: ColTypeOption) -> MockRows {
+        self.columns.push((name.to_string(), col_type));
+        return self;
+    }
+
+    /// Adds a row. Values should be serialized in accordance to column types.
+    pub fn row(mut self, values: Vec<CBytes>) -> MockRows {
+        self.rows.push(values);
+        return self;
+    }
+
+    /// Sets paging state, i.e. indicates that there are more pages.
+    pub fn paging_state(mut self, paging_state: Vec<u8>) -> MockRows {
+        self.paging_state = Some(CBytes::new(paging_state));
+        return self;
+    }
+
+    /// Converts the builder into a body of `Rows` result.
+    pub fn into_body(self) -> BodyResResultRows {
+        let mut flags = RowsMetadataFlag::set_global_table_space(0);
+        if self.paging_state.is_some() {
+            flags = RowsMetadataFlag::set_has_more_pages(flags);
+        }
+        let col_specs: Vec<ColSpec> = self.columns.into_iter()
+            .map(|(name, col_type)| ColSpec {
+                ksname: None,
+                tablename: None,
+                name: CString::new(name),
+                col_type: col_type
+            })
+            .collect();
+
+        return BodyResResultRows {
+            metadata: RowsMetadata {
+                flags: flags,
+                columns_count: col_specs.len() as i32,
+                paging_state: self.paging_state,
+                global_table_space: Some(vec![CString::new(self.keyspace), CString::new(self.table)]),
+                col_specs: col_specs
+            },
+            rows_count: self.rows.len() as i32,
+            rows_content: self.rows
+        };
+    }
+}
@@ -0,0 +1,437 @@
+//! The module contains a mock Cassandra server which could be used to test
+//! applications built on top of CDRS without a real cluster.
+//!
+//! The server listens on localhost, speaks native protocol v4 using the crate's own
+//! frame types and answers requests in accordance to scripted rules. Requests which
+//! don't match any rule get reasonable default responses, e.g. `READY` for `STARTUP`
+//! and `Void` result for queries.
+//!
+//! ```no_run
+//! use cdrs::testing::{MockServer, MockResponse, RequestMatcher};
+//!
+//! let server = MockServer::start().unwrap();
+//! server.when(RequestMatcher::query_contains("FROM users"))
+//!     .times(1)
+//!     .then(MockResponse::void());
+//! let addr = server.addr().to_string();
+//! ```
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, Shutdown};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+
+use compression::Compression;
+use frame::{Frame, Flag, Opcode, Version};
+use frame::frame_event::{BodyResEvent, ServerEvent, EVENT_STREAM_ID};
+use frame::frame_register::SimpleServerEvent;
+use frame::frame_request::RequestBody;
+use frame::parser::parse_raw_frame;
+use types::CBytesMap;
+use IntoBytes;
+
+mod matcher;
+mod mock_response;
+
+pub use self::matcher::RequestMatcher;
+pub use self::mock_response::{MockAction, MockResponse, MockRows, MOCK_KEYSPACE, MOCK_TABLE};
+
+/// Request received by a mock server.
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Id of a connection the request was received on. Connections are numbered
+    /// in order they were accepted starting from 0.
+    pub connection_id: usize,
+    /// Stream id of the request.
+    pub stream: u64,
+    /// Type of the request.
+    pub opcode: Opcode,
+    /// Indicates if a client asked to trace the request.
+    pub tracing: bool,
+    /// Custom payload of the request.
+    pub custom_payload: CBytesMap,
+    /// Decoded body of the request.
+    pub body: RequestBody
+}
+
+struct Rule {
+    matcher: RequestMatcher,
+    action: MockAction,
+    remaining: Option<usize>
+}
+
+struct MockConnection {
+    id: usize,
+    writer: Arc<Mutex<TcpStream>>,
+    compression: Arc<Mutex<Compression>>,
+    events: Arc<Mutex<Vec<SimpleServerEvent>>>
+}
+
+struct ServerState {
+    rules: Mutex<Vec<Rule>>,
+    requests: Mutex<Vec<MockRequest>>,
+    connections: Mutex<Vec<MockConnection>>,
+    next_connection_id: AtomicUsize,
+    stopped: AtomicBool
+}
+
+impl ServerState {
+    fn action_for(&self, request: &MockRequest) -> MockAction {
+        let mut rules = self.rules.lock().unwrap();
+        for rule in rules.iter_mut() {
+            if rule.remaining == Some(0) || !rule.matcher.matches(request) {
+                continue;
+            }
+            if let Some(ref mut remaining) = rule.remaining {
+                *remaining -= 1;
+            }
+            return rule.action.clone();
+        }
+
+        return MockAction::Respond(default_response(request));
+    }
+
+    fn remove_connection(&self, id: usize) {
+        self.connections.lock().unwrap().retain(|connection| connection.id != id);
+    }
+}
+
+/// Mock Cassandra server. It's stopped when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<ServerState>,
+    acceptor: Option<thread::JoinHandle<()>>
+}
+
+impl MockServer {
+    /// Starts new server on a random port of `127.0.0.1`.
+    pub fn start() -> io::Result<MockServer> {
+        return MockServer::bind("127.0.0.1:0");
+    }
+
+    /// Starts new server on provided address.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<MockServer> {
+        let listener = try!(TcpListener::bind(addr));
+        let addr = try!(listener.local_addr());
+        let state = Arc::new(ServerState {
+            rules: Mutex::new(vec![]),
+            requests: Mutex::new(vec![]),
+            connections: Mutex::new(vec![]),
+            next_connection_id: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false)
+        });
+
+        let acceptor_state = state.clone();
+        let acceptor = thread::spawn(move || accept_connections(listener, acceptor_state));
+
+        return Ok(MockServer {
+            addr: addr,
+            state: state,
+            acceptor: Some(acceptor)
+        });
+    }
+
+    /// Returns an address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        return self.addr;
+    }
+
+    /// Starts a scripted rule for requests which match provided matcher.
+    /// Rules are checked in order they were added.
+    pub fn when<'a>(&'a self, matcher: RequestMatcher) -> RuleBuilder<'a> {
+        return RuleBuilder {
+            state: &self.state,
+            matcher: matcher,
+            times: None
+        };
+    }
+
+    /// Removes all scripted rules.
+    pub fn reset_rules(&self) {
+        self.state.rules.lock().unwrap().clear();
+    }
+
+    /// Returns all requests received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        return self.state.requests.lock().unwrap().clone();
+    }
+
+    /// Returns a number of currently open connections.
+    pub fn connections_count(&self) -> usize {
+        return self.state.connections.lock().unwrap().len();
+    }
+
+    /// Pushes an event to all connections which have registered for events of its type.
+    /// Returns a number of connections the event was sent to.
+    pub fn push_event(&self, event: ServerEvent) -> usize {
+        let event_type = event.event_type();
+        let body = BodyResEvent { event: event }.into_cbytes();
+        let connections = self.state.connections.lock().unwrap();
+        let mut sent = 0;
+
+        for connection in connections.iter() {
+            if !connection.events.lock().unwrap().contains(&event_type) {
+                continue;
+            }
+            let response = MockResponse::new(Opcode::Event, body.clone());
+            let compression = connection.compression.lock().unwrap().clone();
+            if write_response(&connection.writer, EVENT_STREAM_ID, &response, compression).is_ok() {
+                sent += 1;
+            }
+        }
+
+        return sent;
+    }
+
+    /// Closes all open connections, e.g. to simulate node restart.
+    pub fn close_connections(&self) {
+        let connections = self.state.connections.lock().unwrap();
+        for connection in connections.iter() {
+            let _ = connection.writer.lock().unwrap().shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Stops the server and closes all its connections.
+    pub fn stop(&mut self) {
+        if self.state.stopped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        // wake up the acceptor
+        let _ = TcpStream::connect(self.addr);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+        self.close_connections();
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Builder of a scripted rule.
+pub struct RuleBuilder<'a> {
+    state: &'a Arc<ServerState>,
+    matcher: RequestMatcher,
+    times: Option<usize>
+}
+
+impl<'a> RuleBuilder<'a> {
+    /// Limits a number of requests the rule is applied to. By default it's applied
+    /// to all matching requests.
+    pub fn times(mut self, times: usize) -> RuleBuilder<'a> {
+        self.times = Some(times);
+        return self;
+    }
+
+    /// Finishes the rule with an action which should be performed.
+    pub fn then<A: Into<MockAction>>(self, action: A) {
+        self.state.rules.lock().unwrap().push(Rule {
+            matcher: self.matcher,
+            action: action.into(),
+            remaining: self.times
+        });
+    }
+}
+
+fn accept_connections(listener: TcpListener, state: Arc<ServerState>) {
+    for socket in listener.incoming() {
+        if state.stopped.load(Ordering::SeqCst) {
+            return;
+        }
+        let socket = match socket {
+            Ok(socket) => socket,
+            Err(err) => {
+                debug!("Mock server could not accept a connection: {}", err);
+                continue;
+            }
+        };
+        let writer = match socket.try_clone() {
+            Ok(writer) => Arc::new(Mutex::new(writer)),
+            Err(_) => continue
+        };
+
+        let connection = MockConnection {
+            id: state.next_connection_id.fetch_add(1, Ordering::SeqCst),
+            writer: writer,
+            compression: Arc::new(Mutex::new(Compression::None)),
+            events: Arc::new(Mutex::new(vec![]))
+        };
+        let handler = MockConnection {
+            id: connection.id,
+            writer: connection.writer.clone(),
+            compression: connection.compression.clone(),
+            events: connection.events.clone()
+        };
+        state.connections.lock().unwrap().push(connection);
+
+        let handler_state = state.clone();
+        thread::spawn(move || {
+            let id = handler.id;
+            handle_connection(socket, handler, &handler_state);
+            handler_state.remove_connection(id);
+        });
+    }
+}
+
+fn handle_connection(mut socket: TcpStream, connection: MockConnection, state: &ServerState) {
+    loop {
+        let compression = connection.compression.lock().unwrap().clone();
+        let frame = match parse_raw_frame(&mut socket, &compression) {
+            Ok(frame) => frame,
+            Err(err) => {
+                debug!("Mock server connection {} is closed: {}", connection.id, err);
+                return;
+            }
+        };
+        if frame.version != Version::Request {
+            error!("Mock server received a response frame {:?}", frame.opcode);
+            let _ = socket.shutdown(Shutdown::Both);
+            return;
+        }
+
+        // frame bodies are decoded with infallible `FromCursor` which panics on some
+        // malformed input, the connection is closed in this case as well
+        let body = match panic::catch_unwind(AssertUnwindSafe(|| frame.get_request_body())) {
+            Ok(Ok(body)) => body,
+            Ok(Err(err)) => {
+                error!("Mock server received an invalid request: {}", err);
+                let _ = socket.shutdown(Shutdown::Both);
+                return;
+            },
+            Err(_) => {
+                error!("Mock server received a malformed {:?} request", frame.opcode);
+                let _ = socket.shutdown(Shutdown::Both);
+                return;
+            }
+        };
+
+        let request = MockRequest {
+            connection_id: connection.id,
+            stream: frame.stream,
+            opcode: frame.opcode.clone(),
+            tracing: frame.flags.contains(&Flag::Tracing),
+            custom_payload: frame.custom_payload.clone(),
+            body: body
+        };
+        state.requests.lock().unwrap().push(request.clone());
+
+        let action = state.action_for(&request);
+        let response = match action {
+            MockAction::RespondPartially(response) => {
+                let _ = write_partially(&connection.writer, request.stream, &response, compression);
+                continue;
+            },
+            MockAction::Respond(response) => response,
+            MockAction::Delay(delay, response) => {
+                thread::sleep(delay);
+                response
+            },
+            MockAction::Ignore => continue,
+            MockAction::CloseConnection => {
+                let _ = socket.shutdown(Shutdown::Both);
+                return;
+            }
+        };
+
+        // the connection is registered before the response is written, so events could be
+        // pushed as soon as a client receives READY
+        match request.body {
+            // compression is used for all frames which follow STARTUP
+            RequestBody::Startup(ref startup) if response.opcode == Opcode::Ready => {
+                if let Some(compression) = startup.map.get("COMPRESSION") {
+                    *connection.compression.lock().unwrap() = Compression::from(compression.clone());
+                }
+            },
+            RequestBody::Register(ref register) if response.opcode == Opcode::Ready => {
+                connection.events.lock().unwrap().extend(register.events.iter().cloned());
+            },
+            _ => {}
+        }
+
+        if write_response(&connection.writer, request.stream, &response, compression).is_err() {
+            return;
+        }
+    }
+}
+
+fn write_response(writer: &Arc<Mutex<TcpStream>>,
+    stream: u64,
+    response: &MockResponse,
+    compression: Compression) -> io::Result<()> {
+    use std::io::Write;
+
+    let bytes = try!(encode_response(stream, response, compression));
+    let mut writer = writer.lock().unwrap();
+    return writer.write_all(bytes.as_slice());
+}
+
+/// Writes the first half of a response frame, the rest of it is never sent.
+fn write_partially(writer: &Arc<Mutex<TcpStream>>,
+    stream: u64,
+    response: &MockResponse,
+    compression: Compression) -> io::Result<()> {
+    use std::io::Write;
+
+    let bytes = try!(encode_response(stream, response, compression));
+    let mut writer = writer.lock().unwrap();
+    return writer.write_all(&bytes[..bytes.len() / 2]);
+}
+
+fn encode_response(stream: u64, response: &MockResponse, compression: Compression) -> io::Result<Vec<u8>> {
+    let flags = match compression {
+        Compression::None => vec![],
+        _ => vec![Flag::Compression]
+    };
+    let frame = Frame {
+        version: Version::Response,
+        flags: flags,
+        opcode: response.opcode.clone(),
+        stream: stream,
+        body: response.body.clone(),
+        tracing_id: response.tracing_id,
+        warnings: response.warnings.clone(),
+        custom_payload: response.custom_payload.clone()
+    };
+    return frame.encode_with(compression)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()));
+}
+
+fn default_response(request: &MockRequest) -> MockResponse {
+    return match request.body {
+        RequestBody::Startup(_) | RequestBody::Register(_) => MockResponse::ready(),
+        RequestBody::Options => {
+            let mut options = HashMap::new();
+            options.insert("CQL_VERSION".to_string(), vec!["3.0.0".to_string()]);
+            options.insert("COMPRESSION".to_string(), vec!["lz4".to_string(), "snappy".to_string()]);
+            MockResponse::supported(options)
+        },
+        RequestBody::Query(ref query) => {
+            let query = query.query.as_str().trim();
+            if query.to_uppercase().starts_with("USE ") {
+                let keyspace = query[4..].trim().trim_end_matches(';').trim_matches('"');
+                MockResponse::set_keyspace(keyspace)
+            } else {
+                MockResponse::void()
+            }
+        },
+        RequestBody::Prepare(ref prepare) => MockResponse::prepared(prepared_id(prepare.query.as_str()), vec![]),
+        RequestBody::Execute(_) | RequestBody::Batch(_) => MockResponse::void(),
+        RequestBody::AuthResponse(_) => MockResponse::auth_success()
+    };
+}
+
+/// Returns an id which mock server assigns by default to a prepared query.
+pub fn prepared_id(query: &str) -> Vec<u8> {
+    let mut hasher = DefaultHasher::new();
+    query.hash(&mut hasher);
+    let hash = hasher.finish();
+    return (0..8).map(|i| (hash >> (8 * (7 - i))) as u8).collect();
+}
@@ -0,0 +1,82 @@
+//! The module contains TLS options which are shared by TLS transports
+//! regardless of a backend (OpenSSL or rustls) used under hood.
+use std::path::{Path, PathBuf};
+
+/// Options of TLS connections. By default server's certificate is verified
+/// against system trust store (OpenSSL) or Mozilla's root certificates (rustls)
+/// and its hostname is checked.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    ca_file: Option<PathBuf>,
+    cert_file: Option<PathBuf>,
+    key_file: Option<PathBuf>,
+    verify_hostname: bool
+}
+
+impl Default for TlsConfig {
+    fn default() -> TlsConfig {
+        return TlsConfig {
+            ca_file: None,
+            cert_file: None,
+            key_file: None,
+            verify_hostname: true
+        };
+    }
+}
+
+impl TlsConfig {
+    /// Creates new config with default options.
+    pub fn new() -> TlsConfig {
+        return TlsConfig::default();
+    }
+
+    /// Sets path to a PEM file with a bundle of trusted CA certificates.
+    pub fn ca_file<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.ca_file = Some(path.as_ref().to_path_buf());
+        return self;
+    }
+
+    /// Sets paths to PEM files with client's certificate chain and private key
+    /// which are presented to a server for mutual TLS authentication.
+    pub fn client_cert<P: AsRef<Path>, K: AsRef<Path>>(&mut self, cert_file: P, key_file: K)
+        -> &mut Self {
+        self.cert_file = Some(cert_file.as_ref().to_path_buf());
+        self.key_file = Some(key_file.as_ref().to_path_buf());
+        return self;
+    }
+
+    /// Enables or disables verification of server's hostname.
+    pub fn verify_hostname(&mut self, verify_hostname: bool) -> &mut Self {
+        self.verify_hostname = verify_hostname;
+        return self;
+    }
+
+    /// Returns path to CA bundle if it was set.
+    pub fn get_ca_file(&self) -> Option<&Path> {
+        return self.ca_file.as_ref().map(|path| path.as_path());
+    }
+
+    /// Returns paths to client's certificate chain and private key if they were set.
+    pub fn get_client_cert(&self) -> Option<(&Path, &Path)> {
+        return match (self.cert_file.as_ref(), self.key_file.as_ref()) {
+            (Some(cert), Some(key)) => Some((cert.as_path(), key.as_path())),
+            _ => None
+        };
+    }
+
+    /// Returns `true` if server's hostname should be verified.
+    pub fn get_verify_hostname(&self) -> bool {
+        return self.verify_hostname;
+    }
+}
+
+/// Extracts host part of an address in `host:port` form. IPv6 addresses
+/// may be enclosed in square brackets, e.g. `[::1]:9042`.
+pub fn host_from_addr(addr: &str) -> &str {
+    let host = match addr.rfind(':') {
+        Some(idx) if !addr[idx + 1..].contains(']') => &addr[..idx],
+        _ => addr
+    };
+
+    return host.trim_matches(|c| c == '[' || c == ']');
+}
@@ -2,44 +2,102 @@ use std::io;
 use std::io::{Read, Write};
 use std::net;
 use std::net::TcpStream;
+use std::time::Duration;
 
-pub struct Transport {
-    tcp: TcpStream
+use transport_config::TransportConfig;
+
+/// General CDRS transport trait. It's implemented by TCP, TLS and Unix domain socket
+/// transports, and it could be implemented by any other one, e.g. by an in-memory
+/// transport for tests. `CDRS`, `Session` and `ConnectionManager` are generic over it.
+pub trait CDRSTransport: Sized + Read + Write + Send + Sync {
+    /// In opposite to `TcpStream`'s `try_clone` this method should
+    /// create absolutely new connection to the same peer.
+    fn try_clone(&self) -> io::Result<Self>;
+
+    /// Closes the connection.
+    fn close(&mut self, close: net::Shutdown) -> io::Result<()>;
+
+    /// Returns the socket address of the remote peer of this connection.
+    fn peer_addr(&self) -> io::Result<net::SocketAddr>;
+
+    /// Sets the read timeout of underlying connection. `None` means that reads
+    /// will block indefinitely.
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
 }
 
-impl Transport {
-    pub fn new(addr: &str) -> io::Result<Transport> {
-        return net::TcpStream::connect(addr)
-            .map(|socket| Transport {
-                tcp: socket
+/// Plain TCP transport.
+pub struct TransportTcp {
+    tcp: TcpStream,
+    config: TransportConfig
+}
+
+/// Former name of `TransportTcp`.
+#[deprecated(note = "Use `TransportTcp` instead")]
+pub type Transport = TransportTcp;
+
+impl TransportTcp {
+    /// Creates new transport with default socket options. `addr` may be a host name
+    /// which is resolved into several IP addresses, each of them is tried in turn.
+    pub fn new(addr: &str) -> io::Result<TransportTcp> {
+        return TransportTcp::with_config(addr, TransportConfig::default());
+    }
+
+    /// Creates new transport in the same way as `new` does but fails with
+    /// `io::ErrorKind::TimedOut` error if connection was not established
+    /// within provided `connect_timeout`. The timeout is also applied
+    /// to connections created by `try_clone`.
+    pub fn with_connect_timeout(addr: &str, connect_timeout: Duration) -> io::Result<TransportTcp> {
+        let mut config = TransportConfig::default();
+        config.connect_timeout(connect_timeout);
+        return TransportTcp::with_config(addr, config);
+    }
+
+    /// Creates new transport which socket is configured in accordance to provided
+    /// `config`. The config is also applied to connections created by `try_clone`.
+    pub fn with_config(addr: &str, config: TransportConfig) -> io::Result<TransportTcp> {
+        return config.connect(addr)
+            .map(|socket| TransportTcp {
+                tcp: socket,
+                config: config
             });
     }
+}
 
+impl CDRSTransport for TransportTcp {
     /// In opposite to `TcpStream`'s `try_clone` this method
     /// creates absolutely new connection - it gets an address
-    /// of a peer from `Transport` and creates a new encrypted
+    /// of a peer from `TransportTcp` and creates a new
     /// transport with new TCP stream under hood.
-    pub fn try_clone(&self) -> io::Result<Transport> {
+    fn try_clone(&self) -> io::Result<Self> {
         let addr = try!(self.tcp.peer_addr());
 
-        net::TcpStream::connect(addr)
-            .map(|socket| Transport {
-                tcp: socket
+        self.config.connect_addr(&addr)
+            .map(|socket| TransportTcp {
+                tcp: socket,
+                config: self.config.clone()
             })
     }
 
-    pub fn close(&mut self, close: net::Shutdown) -> io::Result<()> {
+    fn peer_addr(&self) -> io::Result<net::SocketAddr> {
+        return self.tcp.peer_addr();
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        return self.tcp.set_read_timeout(timeout);
+    }
+
+    fn close(&mut self, close: net::Shutdown) -> io::Result<()> {
         return self.tcp.shutdown(close);
     }
 }
 
-impl Read for Transport {
+impl Read for TransportTcp {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         return self.tcp.read(buf);
     }
 }
 
-impl Write for Transport {
+impl Write for TransportTcp {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         return self.tcp.write(buf);
     }
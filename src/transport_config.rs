@@ -0,0 +1,127 @@
+//! The module contains TCP socket options which are used by transports
+//! to establish new connections.
+use std::io;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use socket2::{Socket, Domain, Type, Protocol, SockAddr};
+
+/// Options of TCP sockets which are created by a transport.
+/// By default Nagle's algorithm is disabled (`TCP_NODELAY` is set)
+/// as requests are usually small and latency sensitive.
+#[derive(Debug, Clone)]
+pub struct TransportConfig {
+    connect_timeout: Option<Duration>,
+    nodelay: bool,
+    keepalive: Option<Duration>,
+    send_buffer_size: Option<usize>,
+    recv_buffer_size: Option<usize>,
+    local_addr: Option<SocketAddr>
+}
+
+impl Default for TransportConfig {
+    fn default() -> TransportConfig {
+        return TransportConfig {
+            connect_timeout: None,
+            nodelay: true,
+            keepalive: None,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            local_addr: None
+        };
+    }
+}
+
+impl TransportConfig {
+    /// Creates new config with default options.
+    pub fn new() -> TransportConfig {
+        return TransportConfig::default();
+    }
+
+    /// Sets connect timeout which is applied to each of resolved addresses.
+    pub fn connect_timeout(&mut self, connect_timeout: Duration) -> &mut Self {
+        self.connect_timeout = Some(connect_timeout);
+        return self;
+    }
+
+    /// Sets `TCP_NODELAY` option.
+    pub fn nodelay(&mut self, nodelay: bool) -> &mut Self {
+        self.nodelay = nodelay;
+        return self;
+    }
+
+    /// Enables `SO_KEEPALIVE` option with provided idle time before the first probe.
+    pub fn keepalive(&mut self, keepalive: Duration) -> &mut Self {
+        self.keepalive = Some(keepalive);
+        return self;
+    }
+
+    /// Sets `SO_SNDBUF` option.
+    pub fn send_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.send_buffer_size = Some(size);
+        return self;
+    }
+
+    /// Sets `SO_RCVBUF` option.
+    pub fn recv_buffer_size(&mut self, size: usize) -> &mut Self {
+        self.recv_buffer_size = Some(size);
+        return self;
+    }
+
+    /// Sets local address which sockets will be bound to.
+    pub fn local_addr(&mut self, local_addr: SocketAddr) -> &mut Self {
+        self.local_addr = Some(local_addr);
+        return self;
+    }
+
+    /// Resolves provided address and tries to connect to each of resolved socket
+    /// addresses one by one. It returns the first successfully established
+    /// connection or the last error if none succeeded.
+    pub fn connect<A: ToSocketAddrs>(&self, addr: A) -> io::Result<TcpStream> {
+        let mut last_err = None;
+
+        for socket_addr in try!(addr.to_socket_addrs()) {
+            match self.connect_addr(&socket_addr) {
+                Ok(stream) => return Ok(stream),
+                Err(err) => {
+                    debug!("Could not connect to {}: {}", socket_addr, err);
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        return Err(last_err.unwrap_or(io::Error::new(io::ErrorKind::InvalidInput,
+            "Could not resolve any address")));
+    }
+
+    /// Connects to a single socket address applying all configured options.
+    pub fn connect_addr(&self, addr: &SocketAddr) -> io::Result<TcpStream> {
+        let domain = match *addr {
+            SocketAddr::V4(_) => Domain::ipv4(),
+            SocketAddr::V6(_) => Domain::ipv6()
+        };
+        let socket = try!(Socket::new(domain, Type::stream(), Some(Protocol::tcp())));
+
+        try!(socket.set_nodelay(self.nodelay));
+        if let Some(keepalive) = self.keepalive {
+            try!(socket.set_keepalive(Some(keepalive)));
+        }
+        if let Some(size) = self.send_buffer_size {
+            try!(socket.set_send_buffer_size(size));
+        }
+        if let Some(size) = self.recv_buffer_size {
+            try!(socket.set_recv_buffer_size(size));
+        }
+        if let Some(local_addr) = self.local_addr {
+            try!(socket.bind(&SockAddr::from(local_addr)));
+        }
+
+        let remote_addr = SockAddr::from(*addr);
+        match self.connect_timeout {
+            Some(timeout) => try!(socket.connect_timeout(&remote_addr, timeout)),
+            None => try!(socket.connect(&remote_addr))
+        }
+
+        return Ok(socket.into_tcp_stream());
+    }
+}
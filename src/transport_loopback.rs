@@ -0,0 +1,219 @@
+//! The module contains in-memory transport which connects a client
+//! with a server living in the same process, e.g. a test harness.
+use std::collections::VecDeque;
+use std::io;
+use std::io::{Read, Write};
+use std::net;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use transport::CDRSTransport;
+
+/// Listener which accepts in-memory connections made via `connect` or via
+/// `try_clone` of any transport created by this listener.
+#[derive(Clone)]
+pub struct LoopbackListener {
+    pending: Arc<(Mutex<VecDeque<TransportLoopback>>, Condvar)>
+}
+
+impl LoopbackListener {
+    /// Creates new listener without pending connections.
+    pub fn new() -> LoopbackListener {
+        return LoopbackListener { pending: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())) };
+    }
+
+    /// Creates new connection and returns its client side. Server side
+    /// is returned by `accept`.
+    pub fn connect(&self) -> io::Result<TransportLoopback> {
+        let (mut client, server) = TransportLoopback::pair();
+        client.listener = Some(self.clone());
+        let &(ref lock, ref cvar) = &*self.pending;
+        let mut pending = try!(lock.lock().map_err(|_| poisoned()));
+
+        pending.push_back(server);
+        cvar.notify_one();
+
+        return Ok(client);
+    }
+
+    /// Blocks until new connection is made and returns its server side.
+    pub fn accept(&self) -> io::Result<TransportLoopback> {
+        let &(ref lock, ref cvar) = &*self.pending;
+        let mut pending = try!(lock.lock().map_err(|_| poisoned()));
+
+        loop {
+            if let Some(server) = pending.pop_front() {
+                return Ok(server);
+            }
+            pending = try!(cvar.wait(pending).map_err(|_| poisoned()));
+        }
+    }
+}
+
+impl Default for LoopbackListener {
+    fn default() -> LoopbackListener {
+        return LoopbackListener::new();
+    }
+}
+
+struct PipeState {
+    buffer: VecDeque<u8>,
+    closed: bool
+}
+
+/// One direction of a duplex connection.
+struct Pipe {
+    state: Mutex<PipeState>,
+    cvar: Condvar
+}
+
+impl Pipe {
+    fn new() -> Arc<Pipe> {
+        return Arc::new(Pipe {
+            state: Mutex::new(PipeState { buffer: VecDeque::new(), closed: false }),
+            cvar: Condvar::new()
+        });
+    }
+
+    fn close(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.closed = true;
+        }
+        self.cvar.notify_all();
+    }
+}
+
+/// In-memory duplex transport. Bytes written into one side of a connection
+/// could be read from the other one. Reads respect read timeout in the same
+/// way TCP sockets do, i.e. they fail with `io::ErrorKind::WouldBlock`.
+pub struct TransportLoopback {
+    incoming: Arc<Pipe>,
+    outgoing: Arc<Pipe>,
+    read_timeout: Mutex<Option<Duration>>,
+    listener: Option<LoopbackListener>
+}
+
+impl TransportLoopback {
+    /// Creates two connected transports. Transports created this way
+    /// cannot be cloned as there is nobody to accept new connections,
+    /// use `LoopbackListener` if it's needed, e.g. for connection pools.
+    pub fn pair() -> (TransportLoopback, TransportLoopback) {
+        let a_to_b = Pipe::new();
+        let b_to_a = Pipe::new();
+
+        let a = TransportLoopback {
+            incoming: b_to_a.clone(),
+            outgoing: a_to_b.clone(),
+            read_timeout: Mutex::new(None),
+            listener: None
+        };
+        let b = TransportLoopback {
+            incoming: a_to_b,
+            outgoing: b_to_a,
+            read_timeout: Mutex::new(None),
+            listener: None
+        };
+
+        return (a, b);
+    }
+}
+
+impl CDRSTransport for TransportLoopback {
+    /// Creates new connection via the listener which this transport
+    /// was created by.
+    fn try_clone(&self) -> io::Result<Self> {
+        return match self.listener {
+            Some(ref listener) => listener.connect(),
+            None => Err(io::Error::new(io::ErrorKind::Other,
+                "Loopback transport created without listener cannot be cloned"))
+        };
+    }
+
+    /// In-memory connections don't have IP addresses, so this method always
+    /// returns an error of kind `io::ErrorKind::InvalidInput`.
+    fn peer_addr(&self) -> io::Result<net::SocketAddr> {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+            "Loopback transport has no IP address"));
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        let mut read_timeout = try!(self.read_timeout.lock().map_err(|_| poisoned()));
+        *read_timeout = timeout;
+        return Ok(());
+    }
+
+    fn close(&mut self, close: net::Shutdown) -> io::Result<()> {
+        match close {
+            net::Shutdown::Read => self.incoming.close(),
+            net::Shutdown::Write => self.outgoing.close(),
+            net::Shutdown::Both => {
+                self.incoming.close();
+                self.outgoing.close();
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl Drop for TransportLoopback {
+    fn drop(&mut self) {
+        self.incoming.close();
+        self.outgoing.close();
+    }
+}
+
+impl Read for TransportLoopback {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let timeout = *try!(self.read_timeout.lock().map_err(|_| poisoned()));
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = try!(self.incoming.state.lock().map_err(|_| poisoned()));
+
+        loop {
+            if !state.buffer.is_empty() {
+                let n = buf.len().min(state.buffer.len());
+                for (dst, src) in buf.iter_mut().zip(state.buffer.drain(..n)) {
+                    *dst = src;
+                }
+                return Ok(n);
+            }
+            if state.closed || buf.is_empty() {
+                return Ok(0);
+            }
+
+            state = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(io::Error::new(io::ErrorKind::WouldBlock,
+                            "Loopback read timed out"));
+                    }
+                    try!(self.incoming.cvar.wait_timeout(state, deadline - now)
+                        .map_err(|_| poisoned())).0
+                },
+                None => try!(self.incoming.cvar.wait(state).map_err(|_| poisoned()))
+            };
+        }
+    }
+}
+
+impl Write for TransportLoopback {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = try!(self.outgoing.state.lock().map_err(|_| poisoned()));
+        if state.closed {
+            return Err(io::Error::new(io::ErrorKind::BrokenPipe, "Loopback connection is closed"));
+        }
+
+        state.buffer.extend(buf.iter());
+        self.outgoing.cvar.notify_all();
+
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return Ok(());
+    }
+}
+
+fn poisoned() -> io::Error {
+    return io::Error::new(io::ErrorKind::Other, "Loopback transport lock is poisoned");
+}
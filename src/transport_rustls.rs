@@ -0,0 +1,179 @@
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, Read, Write};
+use std::net;
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustls::{ClientConfig, ClientSession, Session, StreamOwned};
+use rustls::internal::pemfile;
+use webpki::DNSNameRef;
+use webpki_roots::TLS_SERVER_ROOTS;
+
+use tls_config::{TlsConfig, host_from_addr};
+use transport::CDRSTransport;
+use transport_config::TransportConfig;
+
+/// TLS encrypted transport which uses rustls and therefore doesn't
+/// depend on system OpenSSL.
+pub struct TransportRustls {
+    stream: StreamOwned<ClientSession, TcpStream>,
+    client_config: Arc<ClientConfig>,
+    domain: String,
+    config: TransportConfig
+}
+
+impl TransportRustls {
+    /// Creates new TLS transport. Host part of `addr` is used for SNI
+    /// and hostname verification so it must be a DNS name.
+    pub fn new(addr: &str, client_config: Arc<ClientConfig>) -> io::Result<TransportRustls> {
+        return TransportRustls::with_config(addr, client_config, TransportConfig::default());
+    }
+
+    /// Creates new transport which socket is configured in accordance to provided
+    /// `config`. The config is also applied to connections created by `try_clone`.
+    pub fn with_config(addr: &str, client_config: Arc<ClientConfig>, config: TransportConfig)
+        -> io::Result<TransportRustls> {
+        let domain = host_from_addr(addr).to_string();
+        let socket = try!(config.connect(addr));
+        let stream = try!(handshake(&client_config, domain.as_str(), socket));
+
+        return Ok(TransportRustls {
+            stream: stream,
+            client_config: client_config,
+            domain: domain,
+            config: config
+        });
+    }
+
+    /// Creates new transport using TLS options from `tls_config`, i.e. CA bundle
+    /// and client's certificate for mutual TLS.
+    pub fn with_tls_config(addr: &str, tls_config: &TlsConfig, config: TransportConfig)
+        -> io::Result<TransportRustls> {
+        let client_config = try!(build_client_config(tls_config));
+        return TransportRustls::with_config(addr, Arc::new(client_config), config);
+    }
+
+    /// Returns a domain name which was used for SNI and hostname verification.
+    pub fn domain(&self) -> &str {
+        return self.domain.as_str();
+    }
+}
+
+/// Builds rustls client config basing on provided TLS options. If CA bundle
+/// is not set Mozilla's root certificates are trusted. rustls always verifies
+/// server's hostname so disabled hostname verification is reported as an error.
+pub fn build_client_config(tls_config: &TlsConfig) -> io::Result<ClientConfig> {
+    if !tls_config.get_verify_hostname() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+            "rustls transport does not support disabling hostname verification"));
+    }
+
+    let mut client_config = ClientConfig::new();
+
+    match tls_config.get_ca_file() {
+        Some(ca_file) => {
+            let mut reader = BufReader::new(try!(File::open(ca_file)));
+            let (added, _) = try!(client_config.root_store.add_pem_file(&mut reader)
+                .map_err(|_| invalid_pem(ca_file)));
+            if added == 0 {
+                return Err(invalid_pem(ca_file));
+            }
+        },
+        None => client_config.root_store.add_server_trust_anchors(&TLS_SERVER_ROOTS)
+    }
+
+    if let Some((cert_file, key_file)) = tls_config.get_client_cert() {
+        let mut cert_reader = BufReader::new(try!(File::open(cert_file)));
+        let certs = try!(pemfile::certs(&mut cert_reader).map_err(|_| invalid_pem(cert_file)));
+        let key = try!(read_private_key(key_file));
+        try!(client_config.set_single_client_cert(certs, key)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err)));
+    }
+
+    return Ok(client_config);
+}
+
+fn read_private_key(path: &Path) -> io::Result<::rustls::PrivateKey> {
+    let mut reader = BufReader::new(try!(File::open(path)));
+    let mut keys = try!(pemfile::pkcs8_private_keys(&mut reader).map_err(|_| invalid_pem(path)));
+
+    if keys.is_empty() {
+        let mut reader = BufReader::new(try!(File::open(path)));
+        keys = try!(pemfile::rsa_private_keys(&mut reader).map_err(|_| invalid_pem(path)));
+    }
+
+    return keys.into_iter().next().ok_or(invalid_pem(path));
+}
+
+fn invalid_pem(path: &Path) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData,
+        format!("{} does not contain valid PEM data", path.display()));
+}
+
+fn handshake(client_config: &Arc<ClientConfig>, domain: &str, socket: TcpStream)
+    -> io::Result<StreamOwned<ClientSession, TcpStream>> {
+    let dns_name = try!(DNSNameRef::try_from_ascii_str(domain)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput,
+            format!("{} is not a valid DNS name", domain))));
+    let mut session = ClientSession::new(client_config, dns_name);
+    let mut socket = socket;
+
+    // complete the handshake eagerly so its failures are reported on connect
+    // rather than on the first request
+    while session.is_handshaking() {
+        try!(session.complete_io(&mut socket));
+    }
+
+    return Ok(StreamOwned::new(session, socket));
+}
+
+impl CDRSTransport for TransportRustls {
+    /// Creates absolutely new connection to the same peer. The original
+    /// domain name is used for SNI and hostname verification.
+    fn try_clone(&self) -> io::Result<Self> {
+        let addr = try!(self.stream.sock.peer_addr());
+        let socket = try!(self.config.connect_addr(&addr));
+        let stream = try!(handshake(&self.client_config, self.domain.as_str(), socket));
+
+        return Ok(TransportRustls {
+            stream: stream,
+            client_config: self.client_config.clone(),
+            domain: self.domain.clone(),
+            config: self.config.clone()
+        });
+    }
+
+    fn peer_addr(&self) -> io::Result<net::SocketAddr> {
+        return self.stream.sock.peer_addr();
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        return self.stream.sock.set_read_timeout(timeout);
+    }
+
+    fn close(&mut self, close: net::Shutdown) -> io::Result<()> {
+        self.stream.sess.send_close_notify();
+        let flushed = self.stream.flush();
+        try!(self.stream.sock.shutdown(close));
+        return flushed;
+    }
+}
+
+impl Read for TransportRustls {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        return self.stream.read(buf);
+    }
+}
+
+impl Write for TransportRustls {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        return self.stream.write(buf);
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.stream.flush();
+    }
+}
@@ -2,52 +2,162 @@ use std::io;
 use std::io::{Read, Write};
 use std::net;
 use std::net::TcpStream;
+use std::time::Duration;
 
-use openssl::ssl::{SslStream, SslConnector};
+use openssl::error::ErrorStack;
+use openssl::ssl::{SslStream, SslConnector, SslMethod, SslFiletype, HandshakeError, ShutdownResult};
 
-pub struct Transport {
+use tls_config::{TlsConfig, host_from_addr};
+use transport::CDRSTransport;
+use transport_config::TransportConfig;
+
+/// TLS encrypted transport which uses OpenSSL.
+pub struct TransportTls {
     ssl: SslStream<TcpStream>,
-    connector: SslConnector
+    connector: SslConnector,
+    domain: String,
+    verify_hostname: bool,
+    config: TransportConfig
+}
+
+/// Former name of `TransportTls`.
+#[deprecated(note = "Use `TransportTls` instead")]
+pub type Transport = TransportTls;
+
+impl TransportTls {
+    /// Creates new TLS transport. Host part of `addr` is used for SNI
+    /// and hostname verification.
+    pub fn new(addr: &str, connector: &SslConnector) -> io::Result<TransportTls> {
+        return TransportTls::with_config(addr, connector, TransportConfig::default());
+    }
+
+    /// Creates new transport which socket is configured in accordance to provided
+    /// `config`. The config is also applied to connections created by `try_clone`.
+    pub fn with_config(addr: &str, connector: &SslConnector, config: TransportConfig)
+        -> io::Result<TransportTls> {
+        return TransportTls::connect(addr, connector.clone(), true, config);
+    }
+
+    /// Creates new transport using TLS options from `tls_config`, i.e. CA bundle,
+    /// client's certificate for mutual TLS and hostname verification flag.
+    pub fn with_tls_config(addr: &str, tls_config: &TlsConfig, config: TransportConfig)
+        -> io::Result<TransportTls> {
+        let connector = try!(build_connector(tls_config));
+        return TransportTls::connect(addr, connector, tls_config.get_verify_hostname(), config);
+    }
+
+    /// Returns a domain name which was used for SNI and hostname verification.
+    pub fn domain(&self) -> &str {
+        return self.domain.as_str();
+    }
+
+    fn connect(addr: &str,
+        connector: SslConnector,
+        verify_hostname: bool,
+        config: TransportConfig) -> io::Result<TransportTls> {
+        let domain = host_from_addr(addr).to_string();
+        let socket = try!(config.connect(addr));
+        let ssl = try!(handshake(&connector, domain.as_str(), verify_hostname, socket));
+
+        return Ok(TransportTls {
+            ssl: ssl,
+            connector: connector,
+            domain: domain,
+            verify_hostname: verify_hostname,
+            config: config
+        });
+    }
 }
 
-impl Transport {
-    pub fn new(addr: &str, connector: & SslConnector) -> io::Result<Transport> {
-        let a: Vec<&str> = addr.split(':').collect();
-        return net::TcpStream::connect(addr)
-            .map(|socket| Transport {
-                ssl: connector.connect(a[0], socket).unwrap(),
-                connector: connector.clone()
-            });
+/// Builds OpenSSL connector basing on provided TLS options.
+pub fn build_connector(tls_config: &TlsConfig) -> io::Result<SslConnector> {
+    let mut builder = try!(SslConnector::builder(SslMethod::tls()).map_err(stack_to_io));
+
+    if let Some(ca_file) = tls_config.get_ca_file() {
+        try!(builder.set_ca_file(ca_file).map_err(stack_to_io));
+    }
+    if let Some((cert_file, key_file)) = tls_config.get_client_cert() {
+        try!(builder.set_certificate_chain_file(cert_file).map_err(stack_to_io));
+        try!(builder.set_private_key_file(key_file, SslFiletype::PEM).map_err(stack_to_io));
+        try!(builder.check_private_key().map_err(stack_to_io));
     }
 
+    return Ok(builder.build());
+}
+
+fn handshake(connector: &SslConnector,
+    domain: &str,
+    verify_hostname: bool,
+    socket: TcpStream) -> io::Result<SslStream<TcpStream>> {
+    let mut configuration = try!(connector.configure().map_err(stack_to_io));
+    configuration.set_verify_hostname(verify_hostname);
+
+    return configuration.connect(domain, socket)
+        .map_err(|err| match err {
+            HandshakeError::SetupFailure(stack) => stack_to_io(stack),
+            HandshakeError::Failure(mid) => {
+                io::Error::new(io::ErrorKind::ConnectionAborted,
+                    format!("TLS handshake failed: {}", mid.error()))
+            },
+            HandshakeError::WouldBlock(mid) => {
+                io::Error::new(io::ErrorKind::WouldBlock,
+                    format!("TLS handshake interrupted: {}", mid.error()))
+            }
+        });
+}
+
+fn stack_to_io(stack: ErrorStack) -> io::Error {
+    return io::Error::new(io::ErrorKind::Other, stack);
+}
+
+impl CDRSTransport for TransportTls {
     /// In opposite to `TcpStream`'s `try_clone` this method
     /// creates absolutely new connection - it gets an address
-    /// of a peer from `Transport` and creates a new encrypted
-    /// connection with a new TCP stream under hood.
-    pub fn try_clone(&self) -> io::Result<Transport> {
+    /// of a peer from `TransportTls` and creates a new encrypted
+    /// connection with a new TCP stream under hood. The original
+    /// domain name is used for SNI and hostname verification.
+    fn try_clone(&self) -> io::Result<Self> {
         let addr = try!(self.ssl.get_ref().peer_addr());
-        let ip_string = format!("{}", addr.ip());
+        let socket = try!(self.config.connect_addr(&addr));
+        let ssl = try!(handshake(&self.connector, self.domain.as_str(), self.verify_hostname, socket));
+
+        return Ok(TransportTls {
+            ssl: ssl,
+            connector: self.connector.clone(),
+            domain: self.domain.clone(),
+            verify_hostname: self.verify_hostname,
+            config: self.config.clone()
+        });
+    }
 
-        net::TcpStream::connect(addr)
-            .map(|socket| Transport {
-                ssl: self.connector.connect(ip_string.as_str(), socket).unwrap(),
-                connector: self.connector.clone()
-            })
+    fn peer_addr(&self) -> io::Result<net::SocketAddr> {
+        return self.ssl.get_ref().peer_addr();
     }
 
-    pub fn close(&mut self, _close: net::Shutdown) -> io::Result<()> {
-        self.ssl.shutdown().unwrap();
-        return Ok(());
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        return self.ssl.get_ref().set_read_timeout(timeout);
+    }
+
+    fn close(&mut self, close: net::Shutdown) -> io::Result<()> {
+        // close_notify is sent before the socket is shut down, but the socket
+        // is shut down even if TLS layer reported an error
+        let tls_shutdown = self.ssl.shutdown();
+        try!(self.ssl.get_ref().shutdown(close));
+
+        return match tls_shutdown {
+            Ok(ShutdownResult::Sent) | Ok(ShutdownResult::Received) => Ok(()),
+            Err(err) => Err(io::Error::new(io::ErrorKind::Other, err))
+        };
     }
 }
 
-impl Read for Transport {
+impl Read for TransportTls {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         return self.ssl.read(buf);
     }
 }
 
-impl Write for Transport {
+impl Write for TransportTls {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         return self.ssl.write(buf);
     }
@@ -0,0 +1,69 @@
+use std::io;
+use std::io::{Read, Write};
+use std::net;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use transport::CDRSTransport;
+
+/// Transport over Unix domain socket. It's useful when Cassandra's native protocol
+/// is exposed on a socket file, e.g. by a sidecar.
+pub struct TransportUnix {
+    stream: UnixStream,
+    path: PathBuf
+}
+
+impl TransportUnix {
+    /// Connects to a Unix domain socket located at `path`.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<TransportUnix> {
+        return UnixStream::connect(path.as_ref())
+            .map(|stream| TransportUnix {
+                stream: stream,
+                path: path.as_ref().to_path_buf()
+            });
+    }
+
+    /// Returns a path of the socket.
+    pub fn path(&self) -> &Path {
+        return self.path.as_path();
+    }
+}
+
+impl CDRSTransport for TransportUnix {
+    /// Creates new connection to the same socket path.
+    fn try_clone(&self) -> io::Result<Self> {
+        return TransportUnix::new(self.path.as_path());
+    }
+
+    /// Unix domain sockets don't have IP addresses, so this method always returns
+    /// an error of kind `io::ErrorKind::InvalidInput`.
+    fn peer_addr(&self) -> io::Result<net::SocketAddr> {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+            format!("Unix domain socket {:?} has no IP address", self.path)));
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        return self.stream.set_read_timeout(timeout);
+    }
+
+    fn close(&mut self, close: net::Shutdown) -> io::Result<()> {
+        return self.stream.shutdown(close);
+    }
+}
+
+impl Read for TransportUnix {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        return self.stream.read(buf);
+    }
+}
+
+impl Write for TransportUnix {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        return self.stream.write(buf);
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        return self.stream.flush();
+    }
+}
@@ -0,0 +1,157 @@
+use std::io;
+use std::net;
+use uuid::Uuid;
+
+use frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+use types::{CBytes, try_i32_from_bytes};
+use types::data_serialization_types::*;
+use types::duration::Duration;
+use types::from_cbytes::FromCBytes;
+use error::{Error, Result};
+
+/// Cassandra value of any type. It's decoded basing on a type known at runtime,
+/// so values could be read without knowing a schema, e.g. to export arbitrary tables.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CqlValue {
+    /// A value of a custom type, it's kept as it was sent.
+    Custom { class: String, bytes: Vec<u8> },
+    Ascii(String),
+    Bigint(i64),
+    Blob(Vec<u8>),
+    Boolean(bool),
+    Counter(i64),
+    /// Decimal which value is `unscaled * 10 ^ -scale`. Unscaled value is a big-endian
+    /// two's complement integer of arbitrary length.
+    Decimal { unscaled: Vec<u8>, scale: i32 },
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Timestamp(i64),
+    Uuid(Uuid),
+    Text(String),
+    /// Big-endian two's complement integer of arbitrary length.
+    Varint(Vec<u8>),
+    Timeuuid(Uuid),
+    Inet(net::IpAddr),
+    Date(i32),
+    Time(i64),
+    Smallint(i16),
+    Tinyint(i8),
+    Duration(Duration),
+    List(Vec<CqlValue>),
+    Set(Vec<CqlValue>),
+    Map(Vec<(CqlValue, CqlValue)>),
+    /// User defined type, fields go in the order they are declared in the type.
+    Udt { keyspace: String, name: String, fields: Vec<(String, CqlValue)> },
+    Tuple(Vec<CqlValue>),
+    Null
+}
+
+impl CqlValue {
+    /// Decodes a value of provided type. Items of collections, fields of UDTs
+    /// and elements of tuples are decoded recursively.
+    pub fn decode(bytes: &CBytes, col_type: &ColTypeOption) -> Result<CqlValue> {
+        if bytes.is_null() {
+            return Ok(CqlValue::Null);
+        }
+        if Duration::is_duration_type(col_type) {
+            return Ok(CqlValue::Duration(try!(decode_duration(bytes.as_plain()))));
+        }
+
+        let plain = bytes.as_plain();
+        let value = match (&col_type.id, col_type.value.as_ref()) {
+            (&ColType::Custom, Some(&ColTypeOptionValue::CString(ref class))) => CqlValue::Custom {
+                class: class.as_str().to_string(),
+                bytes: plain
+            },
+            (&ColType::Custom, _) => CqlValue::Custom { class: String::new(), bytes: plain },
+            (&ColType::Ascii, _) => CqlValue::Ascii(try!(decode_ascii(plain))),
+            (&ColType::Bigint, _) => CqlValue::Bigint(try!(decode_bigint(plain))),
+            (&ColType::Blob, _) => CqlValue::Blob(try!(decode_blob(plain))),
+            (&ColType::Boolean, _) => CqlValue::Boolean(try!(decode_boolean(plain))),
+            (&ColType::Counter, _) => CqlValue::Counter(try!(decode_counter(plain))),
+            (&ColType::Decimal, _) => {
+                if plain.len() < 4 {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "decimal is too short").into());
+                }
+                CqlValue::Decimal {
+                    scale: try!(try_i32_from_bytes(plain[..4].to_vec())),
+                    unscaled: plain[4..].to_vec()
+                }
+            },
+            (&ColType::Double, _) => CqlValue::Double(try!(decode_double(plain))),
+            (&ColType::Float, _) => CqlValue::Float(try!(decode_float(plain))),
+            (&ColType::Int, _) => CqlValue::Int(try!(decode_int(plain))),
+            (&ColType::Timestamp, _) => CqlValue::Timestamp(try!(decode_timestamp(plain))),
+            (&ColType::Uuid, _) => CqlValue::Uuid(try!(decode_timeuuid(plain))),
+            (&ColType::Varchar, _) => CqlValue::Text(try!(decode_varchar(plain))),
+            (&ColType::Varint, _) => CqlValue::Varint(plain),
+            (&ColType::Timeuuid, _) => CqlValue::Timeuuid(try!(decode_timeuuid(plain))),
+            (&ColType::Inet, _) => CqlValue::Inet(try!(decode_inet(plain))),
+            (&ColType::Date, _) => CqlValue::Date(try!(decode_date(plain))),
+            (&ColType::Time, _) => CqlValue::Time(try!(decode_time(plain))),
+            (&ColType::Smallint, _) => CqlValue::Smallint(try!(decode_smallint(plain))),
+            (&ColType::Tinyint, _) => CqlValue::Tinyint(try!(decode_tinyint(plain))),
+            (&ColType::List, Some(&ColTypeOptionValue::CList(ref item_type))) => {
+                CqlValue::List(try!(decode_all(try!(decode_list(plain)), item_type)))
+            },
+            (&ColType::Set, Some(&ColTypeOptionValue::CSet(ref item_type))) => {
+                CqlValue::Set(try!(decode_all(try!(decode_set(plain)), item_type)))
+            },
+            (&ColType::Map, Some(&ColTypeOptionValue::CMap((ref key_type, ref value_type)))) => {
+                let mut entries = vec![];
+                for (key, value) in try!(decode_map(plain)) {
+                    entries.push((try!(CqlValue::decode(&key, key_type)), try!(CqlValue::decode(&value, value_type))));
+                }
+                CqlValue::Map(entries)
+            },
+            (&ColType::Udt, Some(&ColTypeOptionValue::UdtType(ref udt))) => {
+                let mut values = try!(decode_udt(plain)).into_iter();
+                let mut fields = vec![];
+                // values written before trailing fields were added to the type don't contain them
+                for &(ref name, ref field_type) in udt.descriptions.iter() {
+                    let value = match values.next() {
+                        Some(value) => try!(CqlValue::decode(&value, field_type)),
+                        None => CqlValue::Null
+                    };
+                    fields.push((name.as_str().to_string(), value));
+                }
+                CqlValue::Udt {
+                    keyspace: udt.ks.as_str().to_string(),
+                    name: udt.udt_name.as_str().to_string(),
+                    fields: fields
+                }
+            },
+            (&ColType::Tuple, Some(&ColTypeOptionValue::TupleType(ref tuple))) => {
+                let mut values = try!(decode_tuple(plain)).into_iter();
+                let mut elements = vec![];
+                for element_type in tuple.types.iter() {
+                    elements.push(match values.next() {
+                        Some(value) => try!(CqlValue::decode(&value, element_type)),
+                        None => CqlValue::Null
+                    });
+                }
+                CqlValue::Tuple(elements)
+            },
+            (&ColType::Null, _) => CqlValue::Null,
+            _ => return Err(Error::General(format!("Type {:?} misses its description", col_type.id)))
+        };
+
+        return Ok(value);
+    }
+
+    /// Indicates if the value is null.
+    pub fn is_null(&self) -> bool {
+        return *self == CqlValue::Null;
+    }
+}
+
+fn decode_all(items: Vec<CBytes>, item_type: &ColTypeOption) -> Result<Vec<CqlValue>> {
+    return items.iter().map(|item| CqlValue::decode(item, item_type)).collect();
+}
+
+impl FromCBytes for CqlValue {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<CqlValue> {
+        return CqlValue::decode(bytes, col_type);
+    }
+}
@@ -4,6 +4,7 @@ use std::net;
 use std::string::FromUtf8Error;
 use uuid;
 use super::*;
+use types::duration::Duration;
 use FromCursor;
 
 
@@ -148,6 +149,26 @@ pub fn decode_smallint(bytes: Vec<u8>) -> Result<i16, io::Error> {
     return try_from_bytes(bytes).map(|i| i as i16);
 }
 
+// Decodes Cassandra `tinyint` data (bytes) into Rust's `Result<i8, io::Error>`
+pub fn decode_tinyint(bytes: Vec<u8>) -> Result<i8, io::Error> {
+    return try_from_bytes(bytes).map(|i| i as i8);
+}
+
+// Decodes Cassandra `counter` data (bytes) into Rust's `Result<i64, io::Error>`
+pub fn decode_counter(bytes: Vec<u8>) -> Result<i64, io::Error> {
+    return try_i_from_bytes(bytes);
+}
+
+// Decodes Cassandra `duration` data (bytes) into Rust's `Result<Duration, io::Error>`.
+// Months, days and nanoseconds are encoded as signed vints one after another.
+pub fn decode_duration(bytes: Vec<u8>) -> Result<Duration, io::Error> {
+    let mut cursor: io::Cursor<Vec<u8>> = io::Cursor::new(bytes);
+    let months = try!(try_vint_from_cursor(&mut cursor));
+    let days = try!(try_vint_from_cursor(&mut cursor));
+    let nanoseconds = try!(try_vint_from_cursor(&mut cursor));
+    return Ok(Duration::new(months as i32, days as i32, nanoseconds));
+}
+
 // Decodes Cassandra `text` data (bytes) into Rust's `Result<String, FromUtf8Error>`.
 pub fn decode_text(bytes: Vec<u8>) -> Result<String, FromUtf8Error> {
     return String::from_utf8(bytes);
@@ -169,10 +190,20 @@ pub fn decode_varint(bytes: Vec<u8>) -> Result<i64, io::Error> {
 }
 
 // Decodes Cassandra `Udt` data (bytes) into Rust's `Result<Vec<CBytes>, io::Error>`
-// each `CBytes` is encoded type of field of user defined type
+// each `CBytes` is encoded type of field of user defined type. UDT value isn't prefixed
+// with a number of fields, fields follow one another till the end of the value.
 pub fn decode_udt(bytes: Vec<u8>) -> Result<Vec<CBytes>, io::Error> {
+    let l = bytes.len() as u64;
     let mut cursor: io::Cursor<Vec<u8>> = io::Cursor::new(bytes);
-    let l = CInt::from_cursor(&mut cursor);
-    let list = (0..l).map(|_| CBytes::from_cursor(&mut cursor)).collect();
+    let mut list = vec![];
+    while cursor.position() < l {
+        list.push(CBytes::from_cursor(&mut cursor));
+    }
     return Ok(list);
 }
+
+// Decodes Cassandra `tuple` data (bytes) into Rust's `Result<Vec<CBytes>, io::Error>`,
+// tuples are serialized in the same way as UDTs
+pub fn decode_tuple(bytes: Vec<u8>) -> Result<Vec<CBytes>, io::Error> {
+    return decode_udt(bytes);
+}
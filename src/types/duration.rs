@@ -0,0 +1,33 @@
+use frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+
+/// Class name of `duration` type which is sent over the wire as a custom type
+/// by protocol v4. Protocol v5 has a dedicated `ColType::Duration` for it.
+pub const DURATION_CLASS: &'static str = "org.apache.cassandra.db.marshal.DurationType";
+
+/// Cassandra `duration`. Months and days are kept apart from nanoseconds because
+/// their lengths vary, e.g. a month could have from 28 to 31 days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Duration {
+    pub months: i32,
+    pub days: i32,
+    pub nanoseconds: i64
+}
+
+impl Duration {
+    pub fn new(months: i32, days: i32, nanoseconds: i64) -> Duration {
+        return Duration {
+            months: months,
+            days: days,
+            nanoseconds: nanoseconds
+        };
+    }
+
+    /// Indicates if provided type is a duration either as a custom or a native type.
+    pub fn is_duration_type(col_type: &ColTypeOption) -> bool {
+        return match (&col_type.id, col_type.value.as_ref()) {
+            (&ColType::Duration, _) => true,
+            (&ColType::Custom, Some(&ColTypeOptionValue::CString(ref class))) => class.as_str() == DURATION_CLASS,
+            _ => false
+        };
+    }
+}
@@ -0,0 +1,181 @@
+use std::net;
+use uuid::Uuid;
+
+use frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+use types::CBytes;
+use types::data_serialization_types::*;
+use types::duration::Duration;
+use types::list::List;
+use types::map::Map;
+use types::set::Set;
+use types::udt::UDT;
+use error::{Error, Result};
+
+/// Should be used to convert a single serialized value of provided type into Rust value.
+/// It's implemented for types which could be items of collections, keys and values of maps,
+/// so collections could be converted into Rust ones regardless of item types.
+pub trait FromCBytes: Sized {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<Self>;
+}
+
+fn mismatch<T>(col_type: &ColTypeOption, rust_type: &str) -> Result<T> {
+    return Err(Error::General(format!("{:?} cannot be converted into {}", col_type.id, rust_type)));
+}
+
+impl FromCBytes for Vec<u8> {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<Vec<u8>> {
+        return match col_type.id {
+            ColType::Blob => decode_blob(bytes.as_plain()).map_err(|err| err.into()),
+            _ => mismatch(col_type, "Vec<u8>")
+        };
+    }
+}
+
+impl FromCBytes for String {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<String> {
+        let converted = match col_type.id {
+            ColType::Custom => decode_custom(bytes.as_plain()),
+            ColType::Ascii => decode_ascii(bytes.as_plain()),
+            ColType::Varchar => decode_varchar(bytes.as_plain()),
+            _ => return mismatch(col_type, "String")
+        };
+        return converted.map_err(|err| err.into());
+    }
+}
+
+impl FromCBytes for bool {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<bool> {
+        return match col_type.id {
+            ColType::Boolean => decode_boolean(bytes.as_plain()).map_err(|err| err.into()),
+            _ => mismatch(col_type, "bool")
+        };
+    }
+}
+
+impl FromCBytes for i64 {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<i64> {
+        let converted = match col_type.id {
+            ColType::Bigint => decode_bigint(bytes.as_plain()),
+            ColType::Counter => decode_counter(bytes.as_plain()),
+            ColType::Timestamp => decode_timestamp(bytes.as_plain()),
+            ColType::Time => decode_time(bytes.as_plain()),
+            ColType::Varint => decode_varint(bytes.as_plain()),
+            _ => return mismatch(col_type, "i64")
+        };
+        return converted.map_err(|err| err.into());
+    }
+}
+
+impl FromCBytes for i32 {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<i32> {
+        let converted = match col_type.id {
+            ColType::Int => decode_int(bytes.as_plain()),
+            ColType::Date => decode_date(bytes.as_plain()),
+            _ => return mismatch(col_type, "i32")
+        };
+        return converted.map_err(|err| err.into());
+    }
+}
+
+impl FromCBytes for i16 {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<i16> {
+        return match col_type.id {
+            ColType::Smallint => decode_smallint(bytes.as_plain()).map_err(|err| err.into()),
+            _ => mismatch(col_type, "i16")
+        };
+    }
+}
+
+impl FromCBytes for i8 {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<i8> {
+        return match col_type.id {
+            ColType::Tinyint => decode_tinyint(bytes.as_plain()).map_err(|err| err.into()),
+            _ => mismatch(col_type, "i8")
+        };
+    }
+}
+
+impl FromCBytes for Duration {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<Duration> {
+        if !Duration::is_duration_type(col_type) {
+            return mismatch(col_type, "Duration");
+        }
+        return decode_duration(bytes.as_plain()).map_err(|err| err.into());
+    }
+}
+
+impl FromCBytes for f64 {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<f64> {
+        return match col_type.id {
+            ColType::Double => decode_double(bytes.as_plain()).map_err(|err| err.into()),
+            _ => mismatch(col_type, "f64")
+        };
+    }
+}
+
+impl FromCBytes for f32 {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<f32> {
+        let converted = match col_type.id {
+            ColType::Decimal => decode_decimal(bytes.as_plain()),
+            ColType::Float => decode_float(bytes.as_plain()),
+            _ => return mismatch(col_type, "f32")
+        };
+        return converted.map_err(|err| err.into());
+    }
+}
+
+impl FromCBytes for net::IpAddr {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<net::IpAddr> {
+        return match col_type.id {
+            ColType::Inet => decode_inet(bytes.as_plain()).map_err(|err| err.into()),
+            _ => mismatch(col_type, "IpAddr")
+        };
+    }
+}
+
+impl FromCBytes for Uuid {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<Uuid> {
+        return match col_type.id {
+            ColType::Uuid | ColType::Timeuuid => decode_timeuuid(bytes.as_plain()).map_err(|err| err.into()),
+            _ => mismatch(col_type, "Uuid")
+        };
+    }
+}
+
+impl FromCBytes for List {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<List> {
+        return match col_type.id {
+            ColType::List | ColType::Set => Ok(List::new(try!(decode_list(bytes.as_plain())), col_type.clone())),
+            _ => mismatch(col_type, "List")
+        };
+    }
+}
+
+impl FromCBytes for Set {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<Set> {
+        return match col_type.id {
+            ColType::Set => Ok(Set::new(try!(decode_set(bytes.as_plain())), col_type.clone())),
+            _ => mismatch(col_type, "Set")
+        };
+    }
+}
+
+impl FromCBytes for Map {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<Map> {
+        return match col_type.id {
+            ColType::Map => Ok(Map::new(try!(decode_map(bytes.as_plain())), col_type.clone())),
+            _ => mismatch(col_type, "Map")
+        };
+    }
+}
+
+impl FromCBytes for UDT {
+    fn from_cbytes(bytes: &CBytes, col_type: &ColTypeOption) -> Result<UDT> {
+        return match (&col_type.id, col_type.value.as_ref()) {
+            (&ColType::Udt, Some(&ColTypeOptionValue::UdtType(ref metadata))) => {
+                Ok(UDT::new(try!(decode_udt(bytes.as_plain())), metadata.clone()))
+            },
+            _ => mismatch(col_type, "UDT")
+        };
+    }
+}
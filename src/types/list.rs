@@ -2,10 +2,13 @@ use std::net;
 use uuid::Uuid;
 use frame::frame_result::{ColType, ColTypeOptionValue, ColTypeOption};
 use types::{CBytes, AsRust};
+use types::cql_value::CqlValue;
 use types::data_serialization_types::*;
+use types::duration::Duration;
+use types::from_cbytes::FromCBytes;
 use types::map::Map;
 use types::udt::UDT;
-use error::Result;
+use error::{Error, Result};
 
 // TODO: consider using pointers to ColTypeOption and Vec<CBytes> instead of owning them.
 pub struct List {
@@ -29,6 +32,15 @@ impl List {
             .map(f)
             .collect();
     }
+
+    fn items<T: FromCBytes>(&self) -> Result<Vec<T>> {
+        let item_type = match self.metadata.value {
+            Some(ColTypeOptionValue::CList(ref item_type)) => item_type,
+            Some(ColTypeOptionValue::CSet(ref item_type)) => item_type,
+            _ => return Err(Error::General(format!("{:?} is not a list", self.metadata.id)))
+        };
+        return self.data.iter().map(|bytes| T::from_cbytes(bytes, item_type)).collect();
+    }
 }
 
 impl AsRust<Vec<Vec<u8>>> for List {
@@ -127,6 +139,9 @@ impl AsRust<Vec<i64>> for List {
                     ColType::Bigint => Ok(
                         self.map(|bytes| decode_bigint(bytes.as_plain()).unwrap())
                     ),
+                    ColType::Counter => Ok(
+                        self.map(|bytes| decode_counter(bytes.as_plain()).unwrap())
+                    ),
                     ColType::Timestamp => Ok(
                         self.map(|bytes| decode_timestamp(bytes.as_plain()).unwrap())
                     ),
@@ -144,6 +159,9 @@ impl AsRust<Vec<i64>> for List {
                     ColType::Bigint => Ok(
                         self.map(|bytes| decode_bigint(bytes.as_plain()).unwrap())
                     ),
+                    ColType::Counter => Ok(
+                        self.map(|bytes| decode_counter(bytes.as_plain()).unwrap())
+                    ),
                     ColType::Timestamp => Ok(
                         self.map(|bytes| decode_timestamp(bytes.as_plain()).unwrap())
                     ),
@@ -217,6 +235,27 @@ impl AsRust<Vec<i16>> for List {
     }
 }
 
+impl AsRust<Vec<i8>> for List {
+    /// Converts cassandra list of tinyint values into Rust `Vec<i8>`
+    fn as_rust(&self) -> Result<Vec<i8>> {
+        return self.items();
+    }
+}
+
+impl AsRust<Vec<Duration>> for List {
+    /// Converts cassandra list of durations into Rust `Vec<Duration>`
+    fn as_rust(&self) -> Result<Vec<Duration>> {
+        return self.items();
+    }
+}
+
+impl AsRust<Vec<CqlValue>> for List {
+    /// Converts cassandra list of values of any type into Rust `Vec<CqlValue>`
+    fn as_rust(&self) -> Result<Vec<CqlValue>> {
+        return self.items();
+    }
+}
+
 impl AsRust<Vec<f64>> for List {
     /// Converts cassandra list of f64-like values into Rust `Vec<f64>`
     fn as_rust(&self) -> Result<Vec<f64>> {
@@ -7,14 +7,20 @@ pub const UUID_LEN: usize = 16;
 
 use std::io;
 use std::io::{Cursor, Read};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt, ByteOrder};
 use {FromBytes, IntoBytes, FromCursor};
 use error::{Result as CDRSResult};
 
+pub mod cql_value;
 pub mod data_serialization_types;
+pub mod duration;
+pub mod from_cbytes;
 pub mod list;
 pub mod map;
 pub mod rows;
+pub mod set;
 pub mod udt;
 pub mod value;
 
@@ -117,7 +123,33 @@ pub fn to_int(int: i64) -> Vec<u8> {
     return i_to_n_bytes(int, INT_LEN);
 }
 
-#[derive(Debug, Clone)]
+/// Converts integer into Cassandra's signed [vint], i.e. zig-zag encoded integer
+/// which takes from 1 to 9 bytes. The number of leading 1 bits of the first byte
+/// is the number of bytes which follow it.
+pub fn to_vint(int: i64) -> Vec<u8> {
+    let mut value = ((int << 1) ^ (int >> 63)) as u64;
+    let extra = (0..8).find(|n| value >> (7 * (n + 1)) == 0).unwrap_or(8);
+    let mut bytes = vec![0; extra + 1];
+    for i in (1..extra + 1).rev() {
+        bytes[i] = value as u8;
+        value >>= 8;
+    }
+    bytes[0] = if extra == 8 { 0xFF } else { (0xFF00u16 >> extra) as u8 | value as u8 };
+    return bytes;
+}
+
+/// Reads Cassandra's signed [vint] from the cursor.
+pub fn try_vint_from_cursor(cursor: &mut Cursor<Vec<u8>>) -> io::Result<i64> {
+    let first = try!(cursor.read_u8());
+    let extra = (!first).leading_zeros() as usize;
+    let mut value = if extra == 8 { 0 } else { (first & (0xFF >> extra)) as u64 };
+    for _ in 0..extra {
+        value = (value << 8) | try!(cursor.read_u8()) as u64;
+    }
+    return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct CString {
     string: String
 }
@@ -220,6 +252,10 @@ pub struct CStringList {
 }
 
 impl CStringList {
+    pub fn new(list: Vec<String>) -> CStringList {
+        return CStringList { list: list.into_iter().map(CString::new).collect() };
+    }
+
     pub fn into_plain(self) -> Vec<String> {
         return self.list
             .iter()
@@ -241,24 +277,45 @@ impl FromCursor for CStringList {
     }
 }
 
+impl IntoBytes for CStringList {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+        v.extend_from_slice(to_short(self.list.len() as u64).as_slice());
+        for string in self.list.iter() {
+            v.extend_from_slice(string.into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
+
 /**/
 
 #[derive(Debug, Clone)]
 /// The structure that represents Cassandra byte type
 pub struct CBytes {
-    bytes: Vec<u8>
+    // `None` represents Cassandra's null value, i.e. [bytes] of negative length.
+    bytes: Option<Vec<u8>>
 }
 
 impl CBytes {
     pub fn new(bytes: Vec<u8>) -> CBytes {
-        return CBytes { bytes: bytes };
+        return CBytes { bytes: Some(bytes) };
+    }
+    /// Creates Cassandra's null bytes.
+    pub fn new_null() -> CBytes {
+        return CBytes { bytes: None };
     }
-    /// Converts `CBytes` into a plain array of bytes
+    /// Converts `CBytes` into a plain array of bytes. Null value is converted into
+    /// an empty array.
     pub fn into_plain(self) -> Vec<u8> {
-        return self.bytes;
+        return self.bytes.unwrap_or(vec![]);
     }
     pub fn as_plain(&self) -> Vec<u8> {
-        return self.bytes.clone();
+        return self.bytes.clone().unwrap_or(vec![]);
+    }
+    /// Indicates if `CBytes` represents Cassandra's null value.
+    pub fn is_null(&self) -> bool {
+        return self.bytes.is_none();
     }
 }
 
@@ -266,8 +323,12 @@ impl FromCursor for CBytes {
     /// from_cursor gets Cursor who's position is set such that it should be a start of a [bytes].
     /// It reads required number of bytes and returns a CBytes
     fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CBytes {
-        let len: u64 = CInt::from_cursor(&mut cursor) as u64;
-        return CBytes { bytes: cursor_next_value(&mut cursor, len) };
+        let len = CInt::from_cursor(&mut cursor);
+        // null value
+        if len < 0 {
+            return CBytes::new_null();
+        }
+        return CBytes::new(cursor_next_value(&mut cursor, len as u64));
     }
 }
 
@@ -275,9 +336,13 @@ impl FromCursor for CBytes {
 impl IntoBytes for CBytes {
     fn into_cbytes(&self) -> Vec<u8> {
         let mut v: Vec<u8> = vec![];
-        let l = self.bytes.len() as i64;
-        v.extend_from_slice(to_int(l).as_slice());
-        v.extend_from_slice(self.bytes.as_slice());
+        match self.bytes {
+            Some(ref bytes) => {
+                v.extend_from_slice(to_int(bytes.len() as i64).as_slice());
+                v.extend_from_slice(bytes.as_slice());
+            },
+            None => v.extend_from_slice(to_int(-1).as_slice())
+        }
         return v;
     }
 }
@@ -296,6 +361,10 @@ impl CBytesShort {
     pub fn into_plain(self) -> Vec<u8> {
         return self.bytes;
     }
+    /// Represents internal value as a slice of bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        return self.bytes.as_slice();
+    }
 }
 
 impl FromCursor for CBytesShort {
@@ -319,6 +388,86 @@ impl IntoBytes for CBytesShort {
 }
 
 
+/// Cassandra [bytes map]: a [short] n, followed by n pair <k><v> where <k> is a
+/// [string] and <v> is a [bytes]. It's used to transfer custom payloads.
+pub type CBytesMap = HashMap<String, Vec<u8>>;
+
+impl FromCursor for CBytesMap {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CBytesMap {
+        let len = CIntShort::from_cursor(&mut cursor);
+        let mut map = HashMap::with_capacity(len as usize);
+        for _ in 0..len {
+            let key = CString::from_cursor(&mut cursor).into_plain();
+            let value = CBytes::from_cursor(&mut cursor).into_plain();
+            map.insert(key, value);
+        }
+        return map;
+    }
+}
+
+impl IntoBytes for CBytesMap {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+        v.extend_from_slice(to_short(self.len() as u64).as_slice());
+        for (key, value) in self.iter() {
+            v.extend_from_slice(CString::new(key.clone()).into_cbytes().as_slice());
+            v.extend_from_slice(CBytes::new(value.clone()).into_cbytes().as_slice());
+        }
+        return v;
+    }
+}
+
+/// Cassandra [inet]: an address (ip and port) of a node. It consists of a [byte] n
+/// which is a size of an ip address (4 for IPv4 and 16 for IPv6), n bytes of the address
+/// and an [int] port.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CInet {
+    pub addr: SocketAddr
+}
+
+impl FromCursor for CInet {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> CInet {
+        let n = cursor_next_value(&mut cursor, 1)[0];
+        let bytes = cursor_next_value(&mut cursor, n as u64);
+        let ip = match n {
+            4 => IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])),
+            16 => {
+                let mut segments = [0u16; 8];
+                for (i, segment) in segments.iter_mut().enumerate() {
+                    *segment = ((bytes[i * 2] as u16) << 8) | (bytes[i * 2 + 1] as u16);
+                }
+                IpAddr::V6(Ipv6Addr::new(segments[0], segments[1], segments[2], segments[3],
+                    segments[4], segments[5], segments[6], segments[7]))
+            },
+            _ => {
+                error!("Unexpected Cassandra inet address length: {}", n);
+                panic!("Unexpected Cassandra inet address length: {}", n);
+            }
+        };
+        let port = CInt::from_cursor(&mut cursor);
+
+        return CInet { addr: SocketAddr::new(ip, port as u16) };
+    }
+}
+
+impl IntoBytes for CInet {
+    fn into_cbytes(&self) -> Vec<u8> {
+        let mut v: Vec<u8> = vec![];
+        match self.addr.ip() {
+            IpAddr::V4(ip) => {
+                v.push(4);
+                v.extend_from_slice(&ip.octets());
+            },
+            IpAddr::V6(ip) => {
+                v.push(16);
+                v.extend_from_slice(&ip.octets());
+            }
+        }
+        v.extend_from_slice(to_int(self.addr.port() as i64).as_slice());
+        return v;
+    }
+}
+
 /// Cassandra int type.
 pub type CInt = i32;
 
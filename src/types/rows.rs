@@ -3,12 +3,17 @@ use uuid::Uuid;
 
 use frame::frame_result::{RowsMetadata, ColType, ColSpec, BodyResResultRows, ColTypeOptionValue};
 use types::{CBytes, IntoRustByName};
+use types::cql_value::CqlValue;
 use types::data_serialization_types::*;
+use types::duration::Duration;
+use types::from_cbytes::FromCBytes;
 use types::list::List;
 use types::map::Map;
+use types::set::Set;
 use types::udt::UDT;
 use error::Result;
 
+#[derive(Debug)]
 pub struct Row {
     metadata: RowsMetadata,
     row_content: Vec<CBytes>
@@ -25,6 +30,25 @@ impl Row {
             .collect();
     }
 
+    /// Indicates if a column with provided name contains null value.
+    /// It returns `None` if there is no such column.
+    pub fn is_null(&self, name: &str) -> Option<bool> {
+        return self.get_col_by_name(name).map(|(_, cbytes)| cbytes.is_null());
+    }
+
+    /// Returns all columns as pairs `(name, value)` in the order they are returned by the server.
+    /// Values are decoded basing on column types, so the schema needn't be known in advance.
+    pub fn values(&self) -> Result<Vec<(String, CqlValue)>> {
+        return self.metadata.col_specs
+            .iter()
+            .zip(self.row_content.iter())
+            .map(|(spec, cbytes)| {
+                let value = try!(CqlValue::decode(cbytes, &spec.col_type));
+                return Ok((spec.name.as_str().to_string(), value));
+            })
+            .collect();
+    }
+
     fn get_col_by_name(&self, name: &str) -> Option<(&ColType, &CBytes)> {
         let i_opt = self.metadata.col_specs.iter().position(|spec| spec.name.as_str() == name);
         if !i_opt.is_some() {
@@ -104,6 +128,7 @@ impl IntoRustByName<i64> for Row {
             let converted = match cassandra_type {
                 &ColType::Int => decode_bigint(bytes),
                 &ColType::Bigint => decode_bigint(bytes),
+                &ColType::Counter => decode_counter(bytes),
                 &ColType::Timestamp => decode_timestamp(bytes),
                 &ColType::Time => decode_time(bytes),
                 &ColType::Varint => decode_varint(bytes),
@@ -145,6 +170,22 @@ impl IntoRustByName<i16> for Row {
     }
 }
 
+impl IntoRustByName<i8> for Row {
+    fn get_by_name(&self, name: &str) -> Option<Result<i8>> {
+        return self.get_col_spec_by_name(name).map(|(cassandra_type, cbytes)| {
+            return i8::from_cbytes(cbytes, &cassandra_type.col_type);
+        });
+    }
+}
+
+impl IntoRustByName<Duration> for Row {
+    fn get_by_name(&self, name: &str) -> Option<Result<Duration>> {
+        return self.get_col_spec_by_name(name).map(|(cassandra_type, cbytes)| {
+            return Duration::from_cbytes(cbytes, &cassandra_type.col_type);
+        });
+    }
+}
+
 impl IntoRustByName<f64> for Row {
     fn get_by_name(&self, name: &str) -> Option<Result<f64>> {
         return self.get_col_by_name(name).map(|(cassandra_type, cbytes)| {
@@ -222,6 +263,22 @@ impl IntoRustByName<List> for Row {
     }
 }
 
+impl IntoRustByName<Set> for Row {
+    fn get_by_name(&self, name: &str) -> Option<Result<Set>> {
+        return self.get_col_spec_by_name(name).map(|(cassandra_type, cbytes)| {
+            return Set::from_cbytes(cbytes, &cassandra_type.col_type);
+        });
+    }
+}
+
+impl IntoRustByName<CqlValue> for Row {
+    fn get_by_name(&self, name: &str) -> Option<Result<CqlValue>> {
+        return self.get_col_spec_by_name(name).map(|(cassandra_type, cbytes)| {
+            return CqlValue::decode(cbytes, &cassandra_type.col_type);
+        });
+    }
+}
+
 impl IntoRustByName<Map> for Row {
     fn get_by_name(&self, name: &str) -> Option<Result<Map>> {
         return self.get_col_spec_by_name(name).map(|(cassandra_type, cbytes)| {
@@ -246,7 +303,7 @@ impl IntoRustByName<UDT> for Row {
             };
 
             return match cassandra_type.col_type.id {
-                ColType::Map => Ok(UDT::new(decode_udt(bytes).unwrap(), cudt)),
+                ColType::Udt => Ok(UDT::new(decode_udt(bytes).unwrap(), cudt)),
                 _ => unreachable!()
             }
         });
@@ -0,0 +1,53 @@
+use std::collections::{BTreeSet, HashSet};
+use std::hash::Hash;
+
+use frame::frame_result::{ColTypeOption, ColTypeOptionValue};
+use types::{AsRust, CBytes};
+use types::from_cbytes::FromCBytes;
+use error::{Error, Result};
+
+/// Cassandra `set`. Unlike `List` it's converted into Rust sets.
+pub struct Set {
+    /// column spec of the set, i.e. id should be Set and value should contain
+    /// a type of set items.
+    metadata: ColTypeOption,
+    data: Vec<CBytes>
+}
+
+impl Set {
+    pub fn new(data: Vec<CBytes>, metadata: ColTypeOption) -> Set {
+        return Set {
+            metadata: metadata,
+            data: data
+        };
+    }
+
+    fn items<T: FromCBytes>(&self) -> Result<Vec<T>> {
+        let item_type = match self.metadata.value {
+            Some(ColTypeOptionValue::CSet(ref item_type)) => item_type,
+            _ => return Err(Error::General(format!("{:?} is not a set", self.metadata.id)))
+        };
+        return self.data.iter().map(|bytes| T::from_cbytes(bytes, item_type)).collect();
+    }
+}
+
+impl<T: FromCBytes + Eq + Hash> AsRust<HashSet<T>> for Set {
+    /// Converts cassandra set into Rust `HashSet<T>`
+    fn as_rust(&self) -> Result<HashSet<T>> {
+        return self.items().map(|items| items.into_iter().collect());
+    }
+}
+
+impl<T: FromCBytes + Ord> AsRust<BTreeSet<T>> for Set {
+    /// Converts cassandra set into Rust `BTreeSet<T>`
+    fn as_rust(&self) -> Result<BTreeSet<T>> {
+        return self.items().map(|items| items.into_iter().collect());
+    }
+}
+
+impl<T: FromCBytes> AsRust<Vec<T>> for Set {
+    /// Converts cassandra set into Rust `Vec<T>` keeping the order of items
+    fn as_rust(&self) -> Result<Vec<T>> {
+        return self.items();
+    }
+}
@@ -4,10 +4,16 @@ use uuid::Uuid;
 
 use frame::frame_result::{ColTypeOption, CUdt, ColType, ColTypeOptionValue};
 use types::{CBytes, IntoRustByName};
+use types::cql_value::CqlValue;
 use types::data_serialization_types::*;
+use types::duration::Duration;
+use types::from_cbytes::FromCBytes;
 use types::list::List;
 use types::map::Map;
-use error::Result;
+use types::set::Set;
+use types::value::{Bytes, Value, ValueType};
+use error::{Error, Result};
+use IntoBytes;
 
 pub struct UDT {
     data: HashMap<String, (ColTypeOption, CBytes)>
@@ -16,15 +22,19 @@ pub struct UDT {
 impl UDT {
     pub fn new(data: Vec<CBytes>, metadata: CUdt) -> UDT {
         let meta_iter = metadata.descriptions.iter();
+        // values serialized before fields were added to the type don't contain
+        // trailing fields, such fields are nulls
+        let missing = metadata.descriptions.len().saturating_sub(data.len());
+        let data_iter = data.iter().cloned().chain((0..missing).map(|_| CBytes::new_null()));
 
         let acc: HashMap<String, (ColTypeOption, CBytes)> = HashMap::with_capacity(metadata.descriptions.len());
         let d = meta_iter
-            .zip(data.iter())
+            .zip(data_iter)
             .fold(acc, |mut a, v| {
                 let (m, val_b) = v;
                 let &(ref name_b, ref val_type) = m;
                 let name = name_b.as_plain();
-                a.insert(name, (val_type.clone(), val_b.clone()));
+                a.insert(name, (val_type.clone(), val_b));
                 return a;
             });
 
@@ -32,6 +42,72 @@ impl UDT {
             data: d
         };
     }
+
+    /// Indicates if a field with provided name contains null value.
+    /// It returns `None` if there is no such field.
+    pub fn is_null(&self, name: &str) -> Option<bool> {
+        return self.data.get(name).map(|&(_, ref bytes)| bytes.is_null());
+    }
+}
+
+/// Builder of an UDT value which could be bound to a query or used as an item of
+/// a collection or a field of another UDT. Fields are serialized in the order
+/// they are declared in UDT metadata, fields which weren't set are sent as nulls.
+#[derive(Debug, Clone)]
+pub struct UdtBuilder {
+    metadata: CUdt,
+    fields: HashMap<String, Value>
+}
+
+impl UdtBuilder {
+    /// Creates new builder of a value of provided UDT.
+    pub fn new(metadata: CUdt) -> UdtBuilder {
+        return UdtBuilder {
+            metadata: metadata,
+            fields: HashMap::new()
+        };
+    }
+
+    /// Creates new builder from a type of a bind marker (see prepared metadata),
+    /// a column or an UDT field. Frozen UDTs have the same type as non-frozen ones.
+    pub fn from_col_type(col_type: &ColTypeOption) -> Result<UdtBuilder> {
+        return match (&col_type.id, col_type.value.as_ref()) {
+            (&ColType::Udt, Some(&ColTypeOptionValue::UdtType(ref metadata))) => Ok(UdtBuilder::new(metadata.clone())),
+            _ => Err(Error::General(format!("{:?} is not an UDT", col_type.id)))
+        };
+    }
+
+    /// Returns a type of a field by its name. It could be used to build
+    /// a value of a nested UDT or a collection of UDTs.
+    pub fn field_type(&self, name: &str) -> Option<&ColTypeOption> {
+        return self.metadata.descriptions.iter()
+            .find(|&&(ref field_name, _)| field_name.as_str() == name)
+            .map(|&(_, ref col_type)| col_type);
+    }
+
+    /// Sets a value of a field. Not-set values are sent as nulls.
+    pub fn field<V: Into<Value>>(mut self, name: &str, value: V) -> UdtBuilder {
+        self.fields.insert(name.to_string(), value.into());
+        return self;
+    }
+
+    /// Serializes the value. It fails if some of set fields are not declared in the UDT.
+    pub fn finalize(mut self) -> Result<Bytes> {
+        let mut bytes = vec![];
+        for &(ref name, _) in self.metadata.descriptions.iter() {
+            let value = match self.fields.remove(name.as_str()) {
+                Some(Value { value_type: ValueType::NotSet, .. }) | None => Value::new_null(),
+                Some(value) => value
+            };
+            bytes.extend_from_slice(value.into_cbytes().as_slice());
+        }
+
+        return match self.fields.keys().next() {
+            Some(name) => Err(Error::General(format!("UDT {}.{} doesn't have field {}",
+                self.metadata.ks.as_str(), self.metadata.udt_name.as_str(), name))),
+            None => Ok(Bytes::new(bytes))
+        };
+    }
 }
 
 impl IntoRustByName<Vec<u8>> for UDT {
@@ -80,6 +156,7 @@ impl IntoRustByName<i64> for UDT {
             let &(ref col_type, ref bytes) = v;
             let converted = match col_type.id {
                 ColType::Bigint => decode_bigint(bytes.as_plain()),
+                ColType::Counter => decode_counter(bytes.as_plain()),
                 ColType::Timestamp => decode_timestamp(bytes.as_plain()),
                 ColType::Time => decode_time(bytes.as_plain()),
                 ColType::Varint => decode_varint(bytes.as_plain()),
@@ -117,6 +194,18 @@ impl IntoRustByName<i16> for UDT {
     }
 }
 
+impl IntoRustByName<i8> for UDT {
+    fn get_by_name(&self, name: &str) -> Option<Result<i8>> {
+        return self.data.get(name).map(|&(ref col_type, ref bytes)| i8::from_cbytes(bytes, col_type));
+    }
+}
+
+impl IntoRustByName<Duration> for UDT {
+    fn get_by_name(&self, name: &str) -> Option<Result<Duration>> {
+        return self.data.get(name).map(|&(ref col_type, ref bytes)| Duration::from_cbytes(bytes, col_type));
+    }
+}
+
 impl IntoRustByName<f64> for UDT {
     fn get_by_name(&self, name: &str) -> Option<Result<f64>> {
         return self.data.get(name).map(|v| {
@@ -190,6 +279,18 @@ impl IntoRustByName<List> for UDT {
     }
 }
 
+impl IntoRustByName<Set> for UDT {
+    fn get_by_name(&self, name: &str) -> Option<Result<Set>> {
+        return self.data.get(name).map(|&(ref col_type, ref bytes)| Set::from_cbytes(bytes, col_type));
+    }
+}
+
+impl IntoRustByName<CqlValue> for UDT {
+    fn get_by_name(&self, name: &str) -> Option<Result<CqlValue>> {
+        return self.data.get(name).map(|&(ref col_type, ref bytes)| CqlValue::decode(bytes, col_type));
+    }
+}
+
 impl IntoRustByName<Map> for UDT {
     fn get_by_name(&self, name: &str) -> Option<Result<Map>> {
         return self.data.get(name).map(|v| {
@@ -1,5 +1,13 @@
-use super::super::IntoBytes;
+use std::io::Cursor;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+use std::net::IpAddr;
+use byteorder::{BigEndian, WriteBytesExt};
+use uuid::Uuid;
+
+use super::super::{IntoBytes, FromCursor};
 use super::*;
+use super::duration::Duration;
 
 /// Types of Cassandra value: normal value (bits), null value and not-set value
 #[derive(Debug, Clone)]
@@ -61,3 +69,181 @@ impl IntoBytes for Value {
         return v;
     }
 }
+
+impl FromCursor for Value {
+    fn from_cursor(mut cursor: &mut Cursor<Vec<u8>>) -> Value {
+        let n = CInt::from_cursor(&mut cursor);
+        return match n {
+            -1 => Value::new_null(),
+            -2 => Value::new_not_set(),
+            _ => Value::new_normal(cursor_next_value(&mut cursor, n as u64))
+        };
+    }
+}
+
+impl<T: Into<Bytes>> From<T> for Value {
+    fn from(value: T) -> Value {
+        return Value::new_normal(value.into().into_plain());
+    }
+}
+
+impl<T: Into<Bytes>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Value {
+        return match value {
+            Some(value) => Value::from(value),
+            None => Value::new_null()
+        };
+    }
+}
+
+/// Serialized Cassandra value (without length) which could be used as a bind parameter
+/// via `Value::from` or as an item of a collection or a field of an UDT.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bytes(Vec<u8>);
+
+impl Bytes {
+    /// The factory method which creates a value from already serialized bytes.
+    pub fn new(bytes: Vec<u8>) -> Bytes {
+        return Bytes(bytes);
+    }
+
+    /// Returns serialized bytes.
+    pub fn into_plain(self) -> Vec<u8> {
+        return self.0;
+    }
+}
+
+impl From<String> for Bytes {
+    fn from(value: String) -> Bytes {
+        return Bytes(value.into_bytes());
+    }
+}
+
+impl<'a> From<&'a str> for Bytes {
+    fn from(value: &'a str) -> Bytes {
+        return Bytes(value.as_bytes().to_vec());
+    }
+}
+
+impl From<bool> for Bytes {
+    fn from(value: bool) -> Bytes {
+        return Bytes(vec![if value { 1 } else { 0 }]);
+    }
+}
+
+impl From<i8> for Bytes {
+    fn from(value: i8) -> Bytes {
+        return Bytes(vec![value as u8]);
+    }
+}
+
+impl From<i16> for Bytes {
+    fn from(value: i16) -> Bytes {
+        return Bytes(i_to_n_bytes(value as i64, SHORT_LEN));
+    }
+}
+
+impl From<i32> for Bytes {
+    fn from(value: i32) -> Bytes {
+        return Bytes(i_to_n_bytes(value as i64, INT_LEN));
+    }
+}
+
+impl From<i64> for Bytes {
+    fn from(value: i64) -> Bytes {
+        return Bytes(i_to_n_bytes(value, 8));
+    }
+}
+
+impl From<f32> for Bytes {
+    fn from(value: f32) -> Bytes {
+        let mut bytes = vec![];
+        bytes.write_f32::<BigEndian>(value).unwrap();
+        return Bytes(bytes);
+    }
+}
+
+impl From<f64> for Bytes {
+    fn from(value: f64) -> Bytes {
+        let mut bytes = vec![];
+        bytes.write_f64::<BigEndian>(value).unwrap();
+        return Bytes(bytes);
+    }
+}
+
+impl From<Duration> for Bytes {
+    fn from(value: Duration) -> Bytes {
+        let mut bytes = to_vint(value.months as i64);
+        bytes.extend_from_slice(to_vint(value.days as i64).as_slice());
+        bytes.extend_from_slice(to_vint(value.nanoseconds).as_slice());
+        return Bytes(bytes);
+    }
+}
+
+impl From<Uuid> for Bytes {
+    fn from(value: Uuid) -> Bytes {
+        return Bytes(value.as_bytes().to_vec());
+    }
+}
+
+impl From<IpAddr> for Bytes {
+    fn from(value: IpAddr) -> Bytes {
+        return match value {
+            IpAddr::V4(ip) => Bytes(ip.octets().to_vec()),
+            IpAddr::V6(ip) => Bytes(ip.octets().to_vec())
+        };
+    }
+}
+
+/// Serializes items as a Cassandra `list`.
+impl<T: Into<Bytes>> From<Vec<T>> for Bytes {
+    fn from(items: Vec<T>) -> Bytes {
+        return serialize_items(items.len(), items.into_iter());
+    }
+}
+
+/// Serializes items as a Cassandra `set`.
+impl<T: Into<Bytes> + Hash + Eq> From<HashSet<T>> for Bytes {
+    fn from(items: HashSet<T>) -> Bytes {
+        return serialize_items(items.len(), items.into_iter());
+    }
+}
+
+/// Serializes items as a Cassandra `set`.
+impl<T: Into<Bytes> + Ord> From<BTreeSet<T>> for Bytes {
+    fn from(items: BTreeSet<T>) -> Bytes {
+        return serialize_items(items.len(), items.into_iter());
+    }
+}
+
+/// Serializes entries as a Cassandra `map`.
+impl<K: Into<Bytes> + Hash + Eq, V: Into<Bytes>> From<HashMap<K, V>> for Bytes {
+    fn from(entries: HashMap<K, V>) -> Bytes {
+        return serialize_entries(entries.len(), entries.into_iter());
+    }
+}
+
+/// Serializes entries as a Cassandra `map`.
+impl<K: Into<Bytes> + Ord, V: Into<Bytes>> From<BTreeMap<K, V>> for Bytes {
+    fn from(entries: BTreeMap<K, V>) -> Bytes {
+        return serialize_entries(entries.len(), entries.into_iter());
+    }
+}
+
+// lists and sets are serialized in the same way
+fn serialize_items<T: Into<Bytes>, I: Iterator<Item = T>>(len: usize, items: I) -> Bytes {
+    let mut bytes = to_int(len as i64);
+    for item in items {
+        bytes.extend_from_slice(Value::from(item).into_cbytes().as_slice());
+    }
+    return Bytes(bytes);
+}
+
+fn serialize_entries<K: Into<Bytes>, V: Into<Bytes>, I: Iterator<Item = (K, V)>>(len: usize, entries: I) -> Bytes {
+    let mut bytes = to_int(len as i64);
+    for (key, value) in entries {
+        bytes.extend_from_slice(Value::from(key).into_cbytes().as_slice());
+        bytes.extend_from_slice(Value::from(value).into_cbytes().as_slice());
+    }
+    return Bytes(bytes);
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "async")]
+extern crate cdrs;
+extern crate futures;
+extern crate tokio_core;
+
+use std::time::Duration;
+
+use futures::{Future, Stream};
+use futures::future::join_all;
+use tokio_core::reactor::Core;
+
+use cdrs::async_session::AsyncSession;
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{BatchQueryBuilder, QueryBuilder};
+use cdrs::compression::Compression;
+use cdrs::consistency::Consistency;
+use cdrs::error::Error;
+use cdrs::frame::frame_error::AdditionalErrorInfo;
+use cdrs::frame::frame_query::ParamsReqQuery;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::frame::frame_response::ResponseBody;
+use cdrs::frame::frame_result::{ColType, ResResultBody};
+use cdrs::testing::{MockAction, MockResponse, MockRows, MockServer, RequestMatcher, prepared_id,
+                    MOCK_KEYSPACE, MOCK_TABLE};
+use cdrs::types::{CBytes, IntoRustByName, to_int};
+
+fn connect(server: &MockServer, core: &mut Core) -> AsyncSession {
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    let session = AsyncSession::connect(&server.addr(), &authenticator, Compression::None, &core.handle());
+    return core.run(session).unwrap();
+}
+
+fn ids_page(ids: Vec<i32>) -> MockRows {
+    return ids.into_iter().fold(MockRows::new(MOCK_KEYSPACE, MOCK_TABLE).column("id", ColType::Int),
+        |rows, id| rows.row(vec![CBytes::new(to_int(id as i64))]));
+}
+
+#[test]
+fn test_async_session_concurrent_queries() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT slow"))
+        .then(MockAction::Delay(Duration::from_millis(100), MockResponse::rows(ids_page(vec![1]))));
+    server.when(RequestMatcher::query("SELECT fast"))
+        .then(MockResponse::rows(ids_page(vec![2])));
+
+    let mut core = Core::new().unwrap();
+    let session = connect(&server, &mut core);
+
+    let queries = vec!["SELECT slow", "SELECT fast"].into_iter()
+        .map(|query| session.query(QueryBuilder::new(query).finalize(), false, false)
+            .map(|frame| frame.get_body().into_rows().unwrap()));
+    let results = core.run(join_all(queries)).unwrap();
+
+    let slow: i32 = results[0][0].get_by_name("id").unwrap().unwrap();
+    let fast: i32 = results[1][0].get_by_name("id").unwrap().unwrap();
+    assert_eq!(slow, 1);
+    assert_eq!(fast, 2);
+
+    // both requests were sent over the same connection using different stream ids
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[1].connection_id, requests[2].connection_id);
+    assert!(requests[1].stream != requests[2].stream);
+}
+
+#[test]
+fn test_async_session_prepare_execute_batch() {
+    let server = MockServer::start().unwrap();
+    let mut core = Core::new().unwrap();
+    let session = connect(&server, &mut core);
+
+    let query = "INSERT INTO mock.mock (id) VALUES (?)";
+    let prepared = core.run(session.prepare(query.to_string(), false, false)).unwrap();
+    let id = match prepared.get_body() {
+        ResponseBody::Result(ResResultBody::Prepared(ref prepared)) => prepared.id.clone(),
+        ref body => panic!("Unexpected response body {:?}", body)
+    };
+    assert_eq!(id.as_slice(), prepared_id(query).as_slice());
+
+    let params = ParamsReqQuery {
+        consistency: Consistency::One,
+        flags: vec![],
+        values: vec![],
+        page_size: 0,
+        paging_state: CBytes::new(vec![]),
+        serial_consistency: Consistency::Serial,
+        timestamp: 0
+    };
+    core.run(session.execute(id.clone(), params, false, false)).unwrap();
+
+    let batch = BatchQueryBuilder::new()
+        .add_query_prepared(id, vec![])
+        .add_query("DELETE FROM mock.mock WHERE id = 1".to_string(), vec![])
+        .finalize();
+    core.run(session.batch(batch, false, false)).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 4);
+    match requests[3].body {
+        RequestBody::Batch(ref batch) => assert_eq!(batch.queries.len(), 2),
+        ref body => panic!("Unexpected request body {:?}", body)
+    }
+}
+
+#[test]
+fn test_async_session_query_pages() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::custom(|request| match request.body {
+            RequestBody::Query(ref query) => query.query_params.paging_state.as_plain() == vec![1],
+            _ => false
+        }))
+        .then(MockResponse::rows(ids_page(vec![3])));
+    server.when(RequestMatcher::query("SELECT id FROM mock.mock"))
+        .then(MockResponse::rows(ids_page(vec![1, 2]).paging_state(vec![1])));
+
+    let mut core = Core::new().unwrap();
+    let session = connect(&server, &mut core);
+
+    let query = QueryBuilder::new("SELECT id FROM mock.mock").page_size(2).finalize();
+    let pages = core.run(session.query_pages(query).collect()).unwrap();
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].len(), 2);
+    assert_eq!(pages[1].len(), 1);
+    let last: i32 = pages[1][0].get_by_name("id").unwrap().unwrap();
+    assert_eq!(last, 3);
+}
+
+#[test]
+fn test_async_session_errors() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT invalid"))
+        .then(MockResponse::simple_error(AdditionalErrorInfo::Invalid, "Invalid query"));
+    server.when(RequestMatcher::query("SELECT slow"))
+        .then(MockAction::Delay(Duration::from_millis(300), MockResponse::void()));
+
+    let mut core = Core::new().unwrap();
+    let mut session = connect(&server, &mut core);
+    session.request_timeout(Some(Duration::from_millis(50)));
+
+    match core.run(session.query(QueryBuilder::new("SELECT invalid").finalize(), false, false)) {
+        Err(Error::Server(err)) => assert_eq!(err.message.as_str(), "Invalid query"),
+        other => panic!("Unexpected result {:?}", other.map(|frame| frame.opcode))
+    }
+    match core.run(session.query(QueryBuilder::new("SELECT slow").finalize(), false, false)) {
+        Err(Error::Timeout(timeout)) => assert_eq!(timeout, Duration::from_millis(50)),
+        other => panic!("Unexpected result {:?}", other.map(|frame| frame.opcode))
+    }
+
+    // a response which came after the timeout doesn't break the connection,
+    // the mock server answers requests of a connection one by one, so the next
+    // response is sent only after the delayed one
+    session.request_timeout(None);
+    assert!(core.run(session.query(QueryBuilder::new("SELECT 1").finalize(), false, false)).is_ok());
+
+    server.close_connections();
+    match core.run(session.query(QueryBuilder::new("SELECT 1").finalize(), false, false)) {
+        Err(Error::Io(_)) => {},
+        other => panic!("Unexpected result {:?}", other.map(|frame| frame.opcode))
+    }
+}
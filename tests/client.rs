@@ -0,0 +1,97 @@
+extern crate cdrs;
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use cdrs::client::{CDRS, QueryBuilder};
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::compression::Compression;
+use cdrs::error::Error;
+use cdrs::frame::Opcode;
+use cdrs::testing::MockServer;
+use cdrs::transport::TransportTcp;
+
+// reads a request frame and returns its stream id
+fn read_request(socket: &mut TcpStream) -> [u8; 2] {
+    let mut header = [0; 9];
+    socket.read_exact(&mut header).unwrap();
+    let len = ((header[5] as usize) << 24) | ((header[6] as usize) << 16) |
+        ((header[7] as usize) << 8) | (header[8] as usize);
+    let mut body = vec![0; len];
+    socket.read_exact(&mut body).unwrap();
+    return [header[2], header[3]];
+}
+
+fn write_response(socket: &mut TcpStream, stream: [u8; 2], opcode: u8, body: &[u8]) {
+    let mut frame = vec![0x84, 0, stream[0], stream[1], opcode, 0, 0, 0, body.len() as u8];
+    frame.extend_from_slice(body);
+    socket.write_all(frame.as_slice()).unwrap();
+}
+
+const READY: u8 = 0x02;
+const RESULT: u8 = 0x08;
+const VOID_RESULT: [u8; 4] = [0, 0, 0, 1];
+const SET_KEYSPACE_RESULT: [u8; 8] = [0, 0, 0, 3, 0, 2, 107, 115];
+
+#[test]
+fn test_query_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+        let stream = read_request(&mut socket);
+        write_response(&mut socket, stream, READY, &[]);
+
+        // the first query is answered too late
+        let late_stream = read_request(&mut socket);
+        thread::sleep(Duration::from_millis(200));
+        write_response(&mut socket, late_stream, RESULT, &SET_KEYSPACE_RESULT);
+
+        let stream = read_request(&mut socket);
+        write_response(&mut socket, stream, RESULT, &VOID_RESULT);
+    });
+
+    let transport = TransportTcp::with_connect_timeout(addr.to_string().as_str(),
+        Duration::from_secs(1)).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    let mut session = CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+
+    let query = QueryBuilder::new("SELECT * FROM slow;")
+        .timeout(Duration::from_millis(50))
+        .finalize();
+    match session.query(query, false, false) {
+        Err(Error::Timeout(timeout)) => assert_eq!(timeout, Duration::from_millis(50)),
+        other => panic!("Timeout error expected, got {:?}", other)
+    }
+
+    // the late response must be discarded
+    let query = QueryBuilder::new("SELECT * FROM fast;").finalize();
+    let frame = session.query(query, false, false).unwrap();
+    assert_eq!(frame.opcode, Opcode::Result);
+    assert_eq!(frame.body, VOID_RESULT.to_vec());
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_session_heartbeat() {
+    let server = MockServer::start().unwrap();
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    let mut session = CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+    session.heartbeat(Duration::from_millis(50));
+
+    thread::sleep(Duration::from_millis(200));
+    let heartbeats = || server.requests().iter().filter(|request| request.opcode == Opcode::Options).count();
+    assert!(heartbeats() >= 2);
+    assert!(!session.is_broken());
+
+    // the heartbeat thread stops together with the session
+    drop(session);
+    let sent = heartbeats();
+    thread::sleep(Duration::from_millis(150));
+    assert_eq!(heartbeats(), sent);
+}
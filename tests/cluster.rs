@@ -0,0 +1,283 @@
+extern crate cdrs;
+extern crate r2d2;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::QueryBuilder;
+use cdrs::cluster::Cluster;
+use cdrs::compression::Compression;
+use cdrs::connection_manager::ConnectionManager;
+use cdrs::frame::Opcode;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::node_state::NodeState;
+use cdrs::reconnection_policy::ConstantReconnectionPolicy;
+use cdrs::speculative_execution::ConstantSpeculativeExecutionPolicy;
+use cdrs::testing::{MockAction, MockResponse, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+
+type TestCluster = Cluster<PasswordAuthenticator<'static>, TransportTcp>;
+
+fn manager(server: &MockServer) -> ConnectionManager<PasswordAuthenticator<'static>, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let mut manager = ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"),
+                                             Compression::None);
+    manager.reconnection_policy(Some(Arc::new(ConstantReconnectionPolicy::new(Duration::from_millis(50)))));
+    return manager;
+}
+
+fn add_node(cluster: &mut TestCluster, server: &MockServer) {
+    add_node_with(cluster, manager(server));
+}
+
+fn add_node_with(cluster: &mut TestCluster,
+                 manager: ConnectionManager<PasswordAuthenticator<'static>, TransportTcp>) {
+    let config = r2d2::Config::builder()
+        .pool_size(1)
+        .connection_timeout(Duration::from_millis(500))
+        .initialization_fail_fast(false)
+        .build();
+    cluster.add_node(config, manager).unwrap();
+}
+
+fn queries_count(server: &MockServer) -> usize {
+    return server.requests().iter()
+        .filter(|request| match request.body {
+            RequestBody::Query(ref query) => query.query.as_str() == "SELECT 1",
+            _ => false
+        })
+        .count();
+}
+
+fn query(cluster: &TestCluster) {
+    let mut connection = cluster.get_connection().unwrap();
+    connection.query(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+}
+
+fn wait_until_up(state: &NodeState) {
+    let start = Instant::now();
+    while !state.is_up() {
+        assert!(start.elapsed() < Duration::from_secs(5), "Node wasn't reconnected");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_connection_manager_reconnection() {
+    let mut server = MockServer::start().unwrap();
+    let addr = server.addr();
+    let manager = manager(&server);
+    server.stop();
+
+    assert!(r2d2::ManageConnection::connect(&manager).is_err());
+    assert!(!manager.node_state().is_up());
+    assert!(manager.is_reconnecting());
+
+    // a downed node isn't connected on demand
+    let server = MockServer::bind(addr).unwrap();
+    assert!(r2d2::ManageConnection::connect(&manager).is_err());
+    assert_eq!(server.requests().len(), 0);
+
+    wait_until_up(&manager.node_state());
+    assert!(r2d2::ManageConnection::connect(&manager).is_ok());
+}
+
+#[test]
+fn test_cluster_round_robin() {
+    let servers = vec![MockServer::start().unwrap(), MockServer::start().unwrap()];
+    let mut cluster = Cluster::new();
+    for server in servers.iter() {
+        add_node(&mut cluster, server);
+    }
+    assert_eq!(cluster.len(), 2);
+    assert_eq!(cluster.nodes_up(), 2);
+
+    for _ in 0..4 {
+        query(&cluster);
+    }
+    assert_eq!(queries_count(&servers[0]), 2);
+    assert_eq!(queries_count(&servers[1]), 2);
+}
+
+#[test]
+fn test_cluster_excludes_downed_nodes() {
+    let first = MockServer::start().unwrap();
+    let mut second = MockServer::start().unwrap();
+    let second_addr = second.addr();
+    let mut cluster = Cluster::new();
+    add_node(&mut cluster, &first);
+    add_node(&mut cluster, &second);
+    let state = cluster.node_state(1).unwrap();
+
+    second.stop();
+    for _ in 0..4 {
+        query(&cluster);
+    }
+    assert!(!state.is_up());
+    assert_eq!(cluster.nodes_up(), 1);
+    assert_eq!(queries_count(&first), 4);
+
+    // the node is put back into load balancing once it's reconnected
+    let second = MockServer::bind(second_addr).unwrap();
+    wait_until_up(&state);
+    let start = Instant::now();
+    while queries_count(&second) == 0 {
+        assert!(start.elapsed() < Duration::from_secs(5), "Node didn't get queries");
+        query(&cluster);
+    }
+}
+
+#[test]
+fn test_cluster_connects_downed_nodes_on_demand() {
+    let servers = vec![MockServer::start().unwrap(), MockServer::start().unwrap()];
+    let mut cluster = Cluster::new();
+    for server in servers.iter() {
+        let mut manager = manager(server);
+        manager.reconnection_policy(None);
+        add_node_with(&mut cluster, manager);
+    }
+    let first_state = cluster.node_state(0).unwrap();
+    let second_state = cluster.node_state(1).unwrap();
+    // pools are connected
+    for _ in 0..2 {
+        query(&cluster);
+    }
+
+    // e.g. a heartbeat failed once
+    first_state.mark_down();
+    for _ in 0..2 {
+        query(&cluster);
+    }
+    assert_eq!(queries_count(&servers[0]), 1);
+    assert_eq!(queries_count(&servers[1]), 3);
+
+    // without reconnection policy nothing else marks the node up, so it's still tried
+    // when no other node is available
+    second_state.mark_down();
+    assert_eq!(cluster.nodes_up(), 0);
+    query(&cluster);
+    assert_eq!(queries_count(&servers[0]), 2);
+    assert!(first_state.is_up());
+}
+
+fn slow_server(delay: Duration) -> MockServer {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT 1"))
+        .then(MockAction::Delay(delay, MockResponse::void()));
+    return server;
+}
+
+fn speculative_cluster(servers: &[MockServer], max_executions: usize) -> TestCluster {
+    let mut cluster = Cluster::new();
+    for server in servers.iter() {
+        add_node(&mut cluster, server);
+    }
+    let policy = ConstantSpeculativeExecutionPolicy::new(Duration::from_millis(50), max_executions);
+    cluster.speculative_execution_policy(Some(Arc::new(policy)));
+    return cluster;
+}
+
+#[test]
+fn test_cluster_speculative_execution() {
+    let servers = vec![slow_server(Duration::from_millis(1000)), MockServer::start().unwrap()];
+    let cluster = speculative_cluster(&servers, 1);
+
+    let start = Instant::now();
+    let query = QueryBuilder::new("SELECT 1").idempotent(true).finalize();
+    let (_, info) = cluster.query_with_info(query, false, false).unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500));
+    assert_eq!(info.attempts, 2);
+    assert_eq!(queries_count(&servers[0]), 1);
+    assert_eq!(queries_count(&servers[1]), 1);
+}
+
+#[test]
+fn test_cluster_speculative_execution_only_for_idempotent_queries() {
+    let servers = vec![slow_server(Duration::from_millis(200)), MockServer::start().unwrap()];
+    let cluster = speculative_cluster(&servers, 1);
+
+    let start = Instant::now();
+    let (_, info) = cluster.query_with_info(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(info.attempts, 1);
+    assert_eq!(queries_count(&servers[1]), 0);
+}
+
+#[test]
+fn test_cluster_speculative_executions_limit() {
+    let servers = vec![slow_server(Duration::from_millis(300)),
+                       slow_server(Duration::from_millis(300)),
+                       slow_server(Duration::from_millis(300))];
+    let cluster = speculative_cluster(&servers, 1);
+
+    let query = QueryBuilder::new("SELECT 1").idempotent(true).finalize();
+    let (_, info) = cluster.query_with_info(query, false, false).unwrap();
+    assert_eq!(info.attempts, 2);
+    assert_eq!(queries_count(&servers[0]), 1);
+    assert_eq!(queries_count(&servers[1]), 1);
+    assert_eq!(queries_count(&servers[2]), 0);
+}
+
+#[test]
+fn test_cluster_speculative_executions_are_cancelled() {
+    let servers = vec![slow_server(Duration::from_millis(200)), MockServer::start().unwrap()];
+    let mut cluster = speculative_cluster(&servers, 1);
+    // the speculative execution waits for the thread which runs the first one
+    cluster.speculative_execution_threads(1);
+
+    let query = QueryBuilder::new("SELECT 1").idempotent(true).finalize();
+    let (_, info) = cluster.query_with_info(query, false, false).unwrap();
+    assert_eq!(info.node, Some(servers[0].addr()));
+    assert_eq!(info.attempts, 2);
+
+    // the speculative execution is cancelled once the first one is answered
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(queries_count(&servers[0]), 1);
+    assert_eq!(queries_count(&servers[1]), 0);
+}
+
+#[test]
+fn test_cluster_speculative_execution_panics() {
+    // a malformed error response makes the parser panic
+    let broken = MockServer::start().unwrap();
+    broken.when(RequestMatcher::query("SELECT 1")).then(MockResponse::new(Opcode::Error, vec![0, 0]));
+    let servers = vec![broken, MockServer::start().unwrap()];
+    // no speculative executions, so nothing but the failed execution could be awaited
+    let mut cluster = speculative_cluster(&servers, 0);
+    cluster.speculative_execution_threads(1);
+
+    let query = QueryBuilder::new("SELECT 1").idempotent(true).finalize();
+    let (_, info) = cluster.query_with_info(query.clone(), false, false).unwrap();
+    assert_eq!(info.node, Some(servers[1].addr()));
+    assert_eq!(info.attempts, 2);
+
+    let mut cluster = speculative_cluster(&servers[..1], 0);
+    cluster.speculative_execution_threads(1);
+    assert!(cluster.query_with_info(query, false, false).is_err());
+}
+
+#[test]
+fn test_cluster_first_execution_doesnt_wait_for_pool() {
+    let servers = vec![slow_server(Duration::from_millis(1000)), MockServer::start().unwrap(),
+                       MockServer::start().unwrap()];
+    let mut cluster = speculative_cluster(&servers, 1);
+    // the only thread is taken by the first execution of a slow query
+    cluster.speculative_execution_threads(1);
+    let cluster = Arc::new(cluster);
+
+    let slow_cluster = cluster.clone();
+    let slow = thread::spawn(move || {
+        let query = QueryBuilder::new("SELECT 1").idempotent(true).finalize();
+        slow_cluster.query_with_info(query, false, false).unwrap();
+    });
+    thread::sleep(Duration::from_millis(100));
+
+    let start = Instant::now();
+    let query = QueryBuilder::new("SELECT 1").idempotent(true).finalize();
+    let (_, info) = cluster.query_with_info(query, false, false).unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500), "{:?}", start.elapsed());
+    assert_eq!(info.node, Some(servers[1].addr()));
+    slow.join().unwrap();
+}
@@ -0,0 +1,151 @@
+extern crate cdrs;
+extern crate r2d2;
+
+use std::thread;
+use std::time::Duration;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::QueryBuilder;
+use cdrs::compression::Compression;
+use cdrs::connection_manager::ConnectionManager;
+use cdrs::error::Error;
+use cdrs::frame::Opcode;
+use cdrs::frame::frame_error::AdditionalErrorInfo;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::testing::{MockAction, MockResponse, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use r2d2::ManageConnection;
+
+fn manager(server: &MockServer) -> ConnectionManager<PasswordAuthenticator<'static>, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    return ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"), Compression::None);
+}
+
+#[test]
+fn test_connection_manager_validates_with_options() {
+    let server = MockServer::start().unwrap();
+    let manager = manager(&server);
+    let mut connection = manager.connect().unwrap();
+
+    assert!(manager.is_valid(&mut connection).is_ok());
+    assert!(!manager.has_broken(&mut connection));
+
+    let requests = server.requests();
+    assert_eq!(requests.last().unwrap().opcode, Opcode::Options);
+}
+
+#[test]
+fn test_connection_manager_validation_query() {
+    let server = MockServer::start().unwrap();
+    let mut manager = manager(&server);
+    manager.validation_query(Some("SELECT now() FROM system.local".to_string()));
+    let mut connection = manager.connect().unwrap();
+
+    assert!(manager.is_valid(&mut connection).is_ok());
+    match server.requests().last().unwrap().body {
+        RequestBody::Query(ref query) => assert_eq!(query.query.as_str(), "SELECT now() FROM system.local"),
+        ref body => panic!("Unexpected request body {:?}", body)
+    }
+}
+
+#[test]
+fn test_connection_manager_has_broken() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT invalid"))
+        .then(MockResponse::simple_error(AdditionalErrorInfo::Invalid, "Invalid query"));
+    let manager = manager(&server);
+    let mut connection = manager.connect().unwrap();
+
+    // server errors don't break a connection
+    assert!(connection.query(QueryBuilder::new("SELECT invalid").finalize(), false, false).is_err());
+    assert!(!manager.has_broken(&mut connection));
+
+    // e.g. the connection was reset
+    server.close_connections();
+    assert!(connection.query(QueryBuilder::new("SELECT 1").finalize(), false, false).is_err());
+    assert!(manager.has_broken(&mut connection));
+    assert!(manager.is_valid(&mut connection).is_err());
+    // an error of a single connection doesn't mark the node down
+    assert!(manager.node_state().is_up());
+
+    let mut connection = manager.connect().unwrap();
+    assert!(!manager.has_broken(&mut connection));
+    connection.end();
+    assert!(manager.has_broken(&mut connection));
+}
+
+#[test]
+fn test_connection_manager_has_broken_after_partial_response() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT slow"))
+        .then(MockAction::Delay(Duration::from_millis(200), MockResponse::void()));
+    server.when(RequestMatcher::query("SELECT stalled"))
+        .then(MockAction::RespondPartially(MockResponse::void()));
+    let manager = manager(&server);
+    let mut connection = manager.connect().unwrap();
+
+    // nothing is read yet, so a late response is discarded later
+    let query = QueryBuilder::new("SELECT slow").timeout(Duration::from_millis(50)).finalize();
+    assert!(connection.query(query, false, false).is_err());
+    assert!(!manager.has_broken(&mut connection));
+
+    // the rest of the frame would be read as the next response
+    let query = QueryBuilder::new("SELECT stalled").timeout(Duration::from_millis(300)).finalize();
+    match connection.query(query, false, false) {
+        Err(Error::Timeout(timeout)) => assert_eq!(timeout, Duration::from_millis(300)),
+        other => panic!("Timeout error expected, got {:?}", other)
+    }
+    assert!(connection.is_broken());
+    assert!(manager.has_broken(&mut connection));
+}
+
+#[test]
+fn test_connection_manager_request_timeout() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT hung")).then(MockAction::Ignore);
+    let mut manager = manager(&server);
+    manager.request_timeout(Some(Duration::from_millis(100)));
+    let mut connection = manager.connect().unwrap();
+
+    match connection.query(QueryBuilder::new("SELECT hung").finalize(), false, false) {
+        Err(Error::Timeout(timeout)) => assert_eq!(timeout, Duration::from_millis(100)),
+        other => panic!("Timeout error expected, got {:?}", other)
+    }
+    // the connection is still usable
+    connection.query(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+}
+
+#[test]
+fn test_connection_manager_heartbeat() {
+    let server = MockServer::start().unwrap();
+    let mut manager = manager(&server);
+    manager.heartbeat_interval(Some(Duration::from_millis(50)));
+    let mut connection = manager.connect().unwrap();
+    let mut other_connection = manager.connect().unwrap();
+
+    thread::sleep(Duration::from_millis(200));
+    let requests = server.requests();
+    let mut connection_ids: Vec<usize> = requests.iter().map(|request| request.connection_id).collect();
+    connection_ids.sort();
+    connection_ids.dedup();
+    assert_eq!(connection_ids.len(), 2);
+    for connection_id in connection_ids {
+        let heartbeats = requests.iter()
+            .filter(|request| request.connection_id == connection_id && request.opcode == Opcode::Options)
+            .count();
+        assert!(heartbeats >= 2);
+    }
+    assert!(!manager.has_broken(&mut connection));
+    assert!(!manager.has_broken(&mut other_connection));
+
+    // e.g. a firewall silently drops packets of the connection
+    server.when(RequestMatcher::opcode(Opcode::Options)).then(MockAction::Ignore);
+    thread::sleep(Duration::from_millis(300));
+    assert!(manager.has_broken(&mut connection));
+    assert!(!manager.node_state().is_up());
+
+    server.reset_rules();
+    let mut connection = manager.connect().unwrap();
+    assert!(!manager.has_broken(&mut connection));
+    assert!(manager.node_state().is_up());
+}
@@ -0,0 +1,177 @@
+extern crate cdrs;
+extern crate log;
+extern crate r2d2;
+extern crate uuid;
+
+use std::collections::HashMap;
+use std::sync::{Mutex, Once};
+use std::time::Duration;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, QueryBuilder, Session};
+use cdrs::compression::Compression;
+use cdrs::connection_manager::ConnectionManager;
+use cdrs::consistency::Consistency;
+use cdrs::execution_info::ExecutionInfo;
+use cdrs::frame::{Frame, Opcode, Version};
+use cdrs::frame::frame_query::{ParamsReqQuery, QueryFlags};
+use cdrs::frame::frame_result::ColType;
+use cdrs::testing::{MockAction, MockResponse, MockRows, MockServer, RequestMatcher, prepared_id};
+use cdrs::transport::TransportTcp;
+use cdrs::types::{CBytes, CBytesShort};
+use log::{LogLevel, LogLevelFilter, LogMetadata, LogRecord};
+use r2d2::ManageConnection;
+use uuid::Uuid;
+
+static LOGGER: Once = Once::new();
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct WarningsLogger;
+
+impl log::Log for WarningsLogger {
+    fn enabled(&self, metadata: &LogMetadata) -> bool {
+        return metadata.level() <= LogLevel::Warn;
+    }
+
+    fn log(&self, record: &LogRecord) {
+        if self.enabled(record.metadata()) {
+            WARNINGS.lock().unwrap().push(record.args().to_string());
+        }
+    }
+}
+
+/// Returns logged warnings which contain `pattern`, as tests run in parallel
+/// warnings of other tests are logged as well.
+fn logged_warnings(pattern: &str) -> Vec<String> {
+    return WARNINGS.lock().unwrap().iter().filter(|warning| warning.contains(pattern)).cloned().collect();
+}
+
+fn connect(server: &MockServer) -> Session<PasswordAuthenticator, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    return CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+}
+
+#[test]
+fn test_execution_info() {
+    let server = MockServer::start().unwrap();
+    let tracing_id = Uuid::parse_str("a5a4b4e0-7b7a-11e7-9c4b-4b2b5c3c2d1e").unwrap();
+    let rows = MockRows::new("ks", "t")
+        .column("id", ColType::Int)
+        .paging_state(vec![1, 2, 3]);
+    server.when(RequestMatcher::query("SELECT id FROM ks.t"))
+        .then(MockAction::Delay(Duration::from_millis(50),
+                                MockResponse::rows(rows)
+                                    .with_tracing_id(tracing_id)
+                                    .with_warnings(vec!["Read 1000 tombstones".to_string()])));
+
+    let mut session = connect(&server);
+    let query = QueryBuilder::new("SELECT id FROM ks.t").consistency(Consistency::Quorum).finalize();
+    let (_, info) = session.query_with_info(query, true, true).unwrap();
+
+    assert_eq!(info.warnings, vec!["Read 1000 tombstones".to_string()]);
+    assert_eq!(info.tracing_id, Some(tracing_id));
+    assert_eq!(info.paging_state.map(|state| state.into_plain()), Some(vec![1, 2, 3]));
+    assert_eq!(info.node, Some(server.addr()));
+    assert_eq!(info.consistency, Some(Consistency::Quorum));
+    assert_eq!(info.attempts, 1);
+    assert!(info.latency >= Duration::from_millis(50), "{:?}", info.latency);
+    assert!(info.schema_in_agreement.is_none());
+}
+
+#[test]
+fn test_execution_info_defaults() {
+    let server = MockServer::start().unwrap();
+    let query = "UPDATE ks.t SET v = 1 WHERE id = ?";
+    let mut session = connect(&server);
+    session.prepare(query.to_string(), false, false).unwrap();
+
+    let params = ParamsReqQuery {
+        consistency: Consistency::LocalOne,
+        flags: vec![QueryFlags::Value],
+        values: vec![],
+        page_size: 0,
+        paging_state: CBytes::new(vec![]),
+        serial_consistency: Consistency::Serial,
+        timestamp: 0
+    };
+    let (_, info) = session.execute_with_info(CBytesShort::new(prepared_id(query)), params, Default::default(),
+                                              false, false)
+        .unwrap();
+
+    assert!(info.warnings.is_empty());
+    assert!(info.tracing_id.is_none());
+    // the response isn't rows
+    assert!(info.paging_state.is_none());
+    assert_eq!(info.consistency, Some(Consistency::LocalOne));
+}
+
+#[test]
+fn test_execution_info_doesnt_parse_rows() {
+    // rows result with paging state which is followed by malformed column specs
+    let mut body = vec![0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 3, 1, 2, 3];
+    body.extend(vec![0xff; 8]);
+    let response = Frame {
+        version: Version::Response,
+        flags: vec![],
+        opcode: Opcode::Result,
+        stream: 1,
+        body: body,
+        tracing_id: None,
+        warnings: vec![],
+        custom_payload: HashMap::new()
+    };
+
+    let info = ExecutionInfo::new(&response, None, None, Duration::from_millis(1));
+    assert_eq!(info.paging_state.map(|state| state.into_plain()), Some(vec![1, 2, 3]));
+
+    // the last page doesn't have paging state
+    let mut response = response;
+    response.body = vec![0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 1, 0xff];
+    assert!(ExecutionInfo::new(&response, None, None, Duration::from_millis(1)).paging_state.is_none());
+}
+
+fn init_logger() {
+    LOGGER.call_once(|| {
+        log::set_logger(|max_level| {
+            max_level.set(LogLevelFilter::Warn);
+            return Box::new(WarningsLogger);
+        }).unwrap();
+    });
+}
+
+#[test]
+fn test_log_warnings() {
+    init_logger();
+
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query_contains("FROM ks.logged"))
+        .then(MockResponse::void().with_warnings(vec!["Batch for ks.logged is too big".to_string()]));
+    server.when(RequestMatcher::query_contains("FROM ks.silent"))
+        .then(MockResponse::void().with_warnings(vec!["Batch for ks.silent is too big".to_string()]));
+
+    let mut session = connect(&server);
+    session.query(QueryBuilder::new("SELECT * FROM ks.silent").finalize(), false, true).unwrap();
+    assert!(logged_warnings("ks.silent").is_empty());
+
+    session.log_warnings(true);
+    let response = session.query(QueryBuilder::new("SELECT * FROM ks.logged").finalize(), false, true).unwrap();
+    assert_eq!(response.warnings(), vec!["Batch for ks.logged is too big".to_string()]);
+    assert_eq!(logged_warnings("ks.logged"), vec!["Server warning: Batch for ks.logged is too big".to_string()]);
+}
+
+#[test]
+fn test_connection_manager_log_warnings() {
+    init_logger();
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query_contains("FROM ks.pooled"))
+        .then(MockResponse::void().with_warnings(vec!["Batch for ks.pooled is too big".to_string()]));
+
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let mut manager = ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"),
+                                             Compression::None);
+    manager.log_warnings(true);
+    let mut connection = manager.connect().unwrap();
+    connection.query(QueryBuilder::new("SELECT * FROM ks.pooled").finalize(), false, true).unwrap();
+    assert_eq!(logged_warnings("ks.pooled"), vec!["Server warning: Batch for ks.pooled is too big".to_string()]);
+}
@@ -1,7 +1,12 @@
 extern crate cdrs;
 
-use cdrs::AsByte;
+use std::io::Cursor;
+use std::collections::HashMap;
+
+use cdrs::{AsByte, IntoBytes};
 use cdrs::frame::*;
+use cdrs::frame::parser::parse_frame;
+use cdrs::compression::Compression;
 
 #[test]
 fn test_frame_version_as_byte() {
@@ -112,3 +117,181 @@ fn test_opcode_from() {
     assert_eq!(Opcode::from(0x0F), Opcode::AuthResponse);
     assert_eq!(Opcode::from(0x10), Opcode::AuthSuccess);
 }
+
+#[test]
+fn test_frame_custom_payload() {
+    let mut frame = Frame::new_req_options();
+    let mut payload = HashMap::new();
+    payload.insert("k".to_string(), vec![1, 2]);
+    frame.custom_payload = payload.clone();
+
+    let bytes = frame.into_cbytes();
+    assert!(Flag::has_custom_payload(bytes[1]));
+
+    let mut cursor = Cursor::new(bytes);
+    let parsed = parse_frame(&mut cursor, &Compression::None).unwrap();
+    assert_eq!(parsed.custom_payload(), payload);
+    assert!(parsed.body.is_empty());
+}
+
+#[test]
+fn test_frame_query_params_encoding() {
+    use cdrs::consistency::Consistency;
+    use cdrs::frame::frame_query::BodyReqQuery;
+    use cdrs::types::CBytes;
+    use cdrs::types::value::Value;
+
+    let body = BodyReqQuery::new(String::new(),
+        Consistency::One,
+        Some(vec![Value::new_normal(vec![0, 0, 0, 1])]),
+        None,
+        Some(100),
+        Some(CBytes::new(vec![7, 8])),
+        Some(Consistency::LocalSerial),
+        Some(1500000000000000));
+    assert_eq!(body.query_params.into_cbytes(), vec![
+        // consistency
+        0, 1,
+        // values, page size, paging state, serial consistency and timestamp flags
+        0x3d,
+        // values count and the value
+        0, 1, 0, 0, 0, 4, 0, 0, 0, 1,
+        // page size
+        0, 0, 0, 100,
+        // paging state
+        0, 0, 0, 2, 7, 8,
+        // serial consistency
+        0, 9,
+        // timestamp is [long]
+        0, 5, 0x54, 0x3d, 0xf7, 0x29, 0xc0, 0
+    ]);
+
+    let body = BodyReqQuery::new(String::new(), Consistency::Quorum, None, None, None, None, None, None);
+    assert_eq!(body.query_params.into_cbytes(), vec![0, 4, 0]);
+
+    // values count is written even if there are no values
+    let body = BodyReqQuery::new(String::new(), Consistency::Quorum, Some(vec![]), None, None, None, None, None);
+    assert_eq!(body.query_params.into_cbytes(), vec![0, 4, 1, 0, 0]);
+}
+
+#[test]
+fn test_frame_request_body_round_trip() {
+    use cdrs::consistency::Consistency;
+    use cdrs::frame::frame_request::RequestBody;
+    use cdrs::types::CBytes;
+    use cdrs::types::value::Value;
+
+    let frame = Frame::new_req_query("SELECT * FROM ks.t WHERE id = ?".to_string(),
+        Consistency::LocalQuorum,
+        Some(vec![Value::new_normal(vec![0, 0, 0, 1]), Value::new_null()]),
+        None,
+        Some(100),
+        Some(CBytes::new(vec![7, 8])),
+        None,
+        Some(42),
+        vec![]);
+
+    let bytes = frame.into_cbytes();
+    let mut cursor = Cursor::new(bytes);
+    let parsed = parse_frame(&mut cursor, &Compression::None).unwrap();
+    assert_eq!(parsed.version, Version::Request);
+
+    match parsed.get_request_body().unwrap() {
+        RequestBody::Query(ref query) => {
+            let params = &query.query_params;
+            assert_eq!(query.query.as_str(), "SELECT * FROM ks.t WHERE id = ?");
+            assert_eq!(params.consistency, Consistency::LocalQuorum);
+            assert_eq!(params.values.len(), 2);
+            assert_eq!(params.values[0].body, vec![0, 0, 0, 1]);
+            assert_eq!(params.page_size, 100);
+            assert_eq!(params.paging_state.as_plain(), vec![7, 8]);
+            assert_eq!(params.timestamp, 42);
+        },
+        ref body => panic!("Unexpected request body {:?}", body)
+    }
+}
+
+#[test]
+fn test_frame_request_body_errors() {
+    use cdrs::frame::frame_batch::BatchType;
+    use cdrs::frame::frame_request::RequestBody;
+
+    assert!(RequestBody::from(vec![], &Opcode::Result).is_err());
+    assert!(RequestBody::from(vec![], &Opcode::Ready).is_err());
+    assert!(RequestBody::from(vec![], &Opcode::Options).is_ok());
+    assert!(RequestBody::from(vec![3, 0, 0, 0, 1, 0], &Opcode::Batch).is_err());
+
+    assert_eq!(BatchType::try_from_byte(2).unwrap(), BatchType::Counter);
+    assert!(BatchType::try_from_byte(3).is_err());
+}
+
+#[test]
+fn test_frame_batch_round_trip() {
+    use cdrs::consistency::Consistency;
+    use cdrs::frame::frame_batch::{BatchQuery, BatchQuerySubject, BatchType, BodyReqBatch};
+    use cdrs::frame::frame_request::RequestBody;
+    use cdrs::types::{CBytesShort, CStringLong};
+    use cdrs::types::value::Value;
+
+    let body = BodyReqBatch {
+        batch_type: BatchType::Unlogged,
+        queries: vec![
+            BatchQuery {
+                subject: BatchQuerySubject::QueryString(CStringLong::new("INSERT INTO ks.t (id) VALUES (?)".to_string())),
+                values: vec![Value::new_normal(vec![0, 0, 0, 1])]
+            },
+            BatchQuery {
+                subject: BatchQuerySubject::PreparedId(CBytesShort::new(vec![1, 2, 3])),
+                values: vec![Value::new_null(), Value::new_not_set()]
+            }
+        ],
+        consistency: Consistency::Quorum,
+        serial_consistency: Some(Consistency::LocalSerial),
+        timestamp: Some(1500000000000000)
+    };
+    let bytes = body.into_cbytes();
+    // type, queries count, first query's kind and string length
+    assert_eq!(&bytes[..8], &[1, 0, 2, 0, 0, 0, 0, 32]);
+    // consistency, flags (serial consistency and timestamp), serial consistency, timestamp
+    let tail = &bytes[bytes.len() - 13..];
+    assert_eq!(tail, &[0, 4, 0x30, 0, 9, 0, 5, 0x54, 0x3d, 0xf7, 0x29, 0xc0, 0]);
+
+    let frame = Frame::new_req_batch(body, vec![]);
+    let mut cursor = Cursor::new(frame.into_cbytes());
+    let parsed = parse_frame(&mut cursor, &Compression::None).unwrap();
+    assert_eq!(parsed.opcode, Opcode::Batch);
+
+    match parsed.get_request_body().unwrap() {
+        RequestBody::Batch(ref batch) => {
+            assert_eq!(batch.batch_type, BatchType::Unlogged);
+            assert_eq!(batch.consistency, Consistency::Quorum);
+            assert_eq!(batch.serial_consistency, Some(Consistency::LocalSerial));
+            assert_eq!(batch.timestamp, Some(1500000000000000));
+            assert_eq!(batch.queries.len(), 2);
+            match batch.queries[0].subject {
+                BatchQuerySubject::QueryString(ref query) => {
+                    assert_eq!(query.as_str(), "INSERT INTO ks.t (id) VALUES (?)");
+                },
+                ref subject => panic!("Unexpected subject {:?}", subject)
+            }
+            assert_eq!(batch.queries[0].values[0].body, vec![0, 0, 0, 1]);
+            match batch.queries[1].subject {
+                BatchQuerySubject::PreparedId(ref id) => assert_eq!(id.as_slice(), &[1, 2, 3]),
+                ref subject => panic!("Unexpected subject {:?}", subject)
+            }
+            assert_eq!(batch.queries[1].values[0].into_cbytes(), vec![0xff, 0xff, 0xff, 0xff]);
+            assert_eq!(batch.queries[1].values[1].into_cbytes(), vec![0xff, 0xff, 0xff, 0xfe]);
+        },
+        ref body => panic!("Unexpected request body {:?}", body)
+    }
+
+    // flags are not set if neither serial consistency nor timestamp is provided
+    let body = BodyReqBatch {
+        batch_type: BatchType::Logged,
+        queries: vec![],
+        consistency: Consistency::One,
+        serial_consistency: None,
+        timestamp: None
+    };
+    assert_eq!(body.into_cbytes(), vec![0, 0, 0, 0, 1, 0]);
+}
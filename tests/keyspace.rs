@@ -0,0 +1,113 @@
+extern crate cdrs;
+extern crate r2d2;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, QueryBuilder, Session};
+use cdrs::compression::Compression;
+use cdrs::connection_manager::ConnectionManager;
+use cdrs::error::Error;
+use cdrs::frame::frame_error::AdditionalErrorInfo;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::testing::{MockResponse, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use r2d2::ManageConnection;
+
+fn connect(server: &MockServer, keyspace: Option<&str>)
+    -> Result<Session<PasswordAuthenticator<'static>, TransportTcp>, Error> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let mut cdrs = CDRS::new(transport, PasswordAuthenticator::new("user", "pass"));
+    cdrs.keyspace(keyspace.map(|keyspace| keyspace.to_string()));
+    return cdrs.start(Compression::None);
+}
+
+fn manager(server: &MockServer) -> ConnectionManager<PasswordAuthenticator<'static>, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    return ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"), Compression::None);
+}
+
+fn use_queries(server: &MockServer) -> Vec<String> {
+    return server.requests().iter()
+        .filter_map(|request| match request.body {
+            RequestBody::Query(ref query) if query.query.as_str().starts_with("USE ") => {
+                Some(query.query.as_str().to_string())
+            },
+            _ => None
+        })
+        .collect();
+}
+
+fn mock_missing_keyspace(server: &MockServer) {
+    server.when(RequestMatcher::query("USE missing"))
+        .then(MockResponse::simple_error(AdditionalErrorInfo::Invalid, "Keyspace 'missing' does not exist"));
+}
+
+#[test]
+fn test_session_tracks_keyspace() {
+    let server = MockServer::start().unwrap();
+    mock_missing_keyspace(&server);
+
+    let mut session = connect(&server, None).unwrap();
+    assert_eq!(session.keyspace(), None);
+    assert!(use_queries(&server).is_empty());
+
+    session.use_keyspace("ks").unwrap();
+    assert_eq!(session.keyspace(), Some("ks".to_string()));
+
+    // USE queries made via `query` are tracked as well
+    session.query(QueryBuilder::new("USE other").finalize(), false, false).unwrap();
+    assert_eq!(session.keyspace(), Some("other".to_string()));
+
+    match session.use_keyspace("missing") {
+        Err(Error::Server(_)) => {},
+        other => panic!("Unexpected result {:?}", other)
+    }
+    assert_eq!(session.keyspace(), Some("other".to_string()));
+}
+
+#[test]
+fn test_cdrs_keyspace() {
+    let server = MockServer::start().unwrap();
+    mock_missing_keyspace(&server);
+
+    let session = connect(&server, Some("ks")).unwrap();
+    assert_eq!(session.keyspace(), Some("ks".to_string()));
+    assert_eq!(use_queries(&server), vec!["USE ks"]);
+
+    match connect(&server, Some("missing")) {
+        Err(Error::Server(_)) => {},
+        other => panic!("Unexpected result {:?}", other.map(|session| session.keyspace()))
+    }
+}
+
+#[test]
+fn test_connection_manager_keyspace() {
+    let server = MockServer::start().unwrap();
+    let mut manager = manager(&server);
+    manager.keyspace(Some("ks".to_string()));
+
+    let mut connection = manager.connect().unwrap();
+    assert_eq!(connection.keyspace(), Some("ks".to_string()));
+
+    // a connection which replaces a broken one uses the keyspace as well
+    server.close_connections();
+    assert!(connection.query(QueryBuilder::new("SELECT 1").finalize(), false, false).is_err());
+    assert!(manager.has_broken(&mut connection));
+    let connection = manager.connect().unwrap();
+    assert_eq!(connection.keyspace(), Some("ks".to_string()));
+    assert_eq!(use_queries(&server), vec!["USE ks", "USE ks"]);
+}
+
+#[test]
+fn test_connection_manager_missing_keyspace() {
+    let server = MockServer::start().unwrap();
+    mock_missing_keyspace(&server);
+    let mut manager = manager(&server);
+    manager.keyspace(Some("missing".to_string()));
+
+    match manager.connect() {
+        Err(Error::Server(_)) => {},
+        other => panic!("Unexpected result {:?}", other.map(|session| session.keyspace()))
+    }
+    // the node is reachable, only the keyspace is wrong
+    assert!(manager.node_state().is_up());
+}
@@ -1,10 +1,41 @@
 extern crate cdrs;
 extern crate byteorder;
+extern crate uuid;
+extern crate log;
+extern crate r2d2;
+extern crate socket2;
+#[cfg(feature = "ssl")]
+extern crate openssl;
+#[cfg(feature = "tls-rustls")]
+extern crate rustls;
+#[cfg(feature = "async")]
+extern crate futures;
+#[cfg(feature = "async")]
+extern crate tokio_core;
 
 mod frame;
 mod types;
 
+#[cfg(feature = "async")]
+mod async_session;
 mod authenticators;
+mod client;
+mod cluster;
 mod compression;
+mod connection_manager;
 mod consistency;
+mod execution_info;
+mod keyspace;
+mod load_balancing;
+mod lwt;
+mod metadata;
+mod mock_server;
+mod query_trace;
+mod reconnection_policy;
 mod rows;
+mod schema_agreement;
+mod schema_change;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod speculative_execution;
+mod transport;
@@ -0,0 +1,195 @@
+extern crate cdrs;
+extern crate r2d2;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::QueryBuilder;
+use cdrs::cluster::Cluster;
+use cdrs::compression::Compression;
+use cdrs::connection_manager::{ConnectionManager, LOCATION_QUERY};
+use cdrs::consistency::Consistency;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::frame::frame_result::ColType;
+use cdrs::load_balancing::{DcAwareRoundRobinPolicy, LoadBalancingPolicy, NodeDistance,
+                           RackAwareRoundRobinPolicy, ReplicaLocator, RoundRobinPolicy, TokenAwarePolicy};
+use cdrs::node_state::{NodeLocation, NodeState};
+use cdrs::reconnection_policy::ConstantReconnectionPolicy;
+use cdrs::testing::{MockResponse, MockRows, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use cdrs::types::CBytes;
+
+fn node(datacenter: &str, rack: &str) -> NodeState {
+    let state = NodeState::new();
+    state.set_location(NodeLocation {
+        datacenter: Some(datacenter.to_string()),
+        rack: Some(rack.to_string())
+    });
+    return state;
+}
+
+fn nodes() -> Vec<NodeState> {
+    return vec![node("dc1", "r1"), node("dc2", "r1"), node("dc1", "r2"), node("dc1", "r1"), NodeState::new()];
+}
+
+#[test]
+fn test_round_robin_policy() {
+    let nodes = nodes();
+    let policy = RoundRobinPolicy::new();
+    assert_eq!(policy.new_query_plan(&nodes, None, None), vec![0, 1, 2, 3, 4]);
+    assert_eq!(policy.new_query_plan(&nodes, None, None), vec![1, 2, 3, 4, 0]);
+
+    nodes[2].mark_down();
+    assert_eq!(policy.new_query_plan(&nodes, None, None), vec![3, 4, 0, 1]);
+}
+
+#[test]
+fn test_dc_aware_round_robin_policy() {
+    let nodes = nodes();
+    let policy = DcAwareRoundRobinPolicy::new("dc1");
+    assert_eq!(policy.distance(&nodes[0]), NodeDistance::Local);
+    assert_eq!(policy.distance(&nodes[1]), NodeDistance::Remote);
+    // location isn't known yet
+    assert_eq!(policy.distance(&nodes[4]), NodeDistance::Remote);
+
+    assert_eq!(policy.new_query_plan(&nodes, Some(&Consistency::One), None), vec![0, 2, 3, 1, 4]);
+    assert_eq!(policy.new_query_plan(&nodes, None, None), vec![2, 3, 0, 4, 1]);
+
+    // remote datacenters aren't used for local consistency levels
+    assert_eq!(policy.new_query_plan(&nodes, Some(&Consistency::LocalQuorum), None), vec![3, 0, 2]);
+
+    // remote nodes are used only when local ones are down
+    nodes[0].mark_down();
+    nodes[2].mark_down();
+    nodes[3].mark_down();
+    assert_eq!(policy.new_query_plan(&nodes, Some(&Consistency::Quorum), None), vec![4, 1]);
+    assert_eq!(policy.new_query_plan(&nodes, Some(&Consistency::LocalOne), None), Vec::<usize>::new());
+}
+
+#[test]
+fn test_rack_aware_round_robin_policy() {
+    let nodes = nodes();
+    let policy = RackAwareRoundRobinPolicy::new("dc1", "r1");
+    assert_eq!(policy.distance(&nodes[2]), NodeDistance::Local);
+    assert_eq!(policy.distance(&nodes[1]), NodeDistance::Remote);
+
+    assert_eq!(policy.new_query_plan(&nodes, None, None), vec![0, 3, 2, 1, 4]);
+    assert_eq!(policy.new_query_plan(&nodes, None, None), vec![3, 0, 2, 4, 1]);
+    assert_eq!(policy.new_query_plan(&nodes, Some(&Consistency::LocalOne), None), vec![3, 0, 2]);
+}
+
+/// Locator which maps the first byte of a routing key to replicas.
+struct FixedReplicas(Vec<Vec<usize>>);
+
+impl ReplicaLocator for FixedReplicas {
+    fn replicas(&self, routing_key: &[u8], _: &[NodeState]) -> Vec<usize> {
+        return self.0[routing_key[0] as usize].clone();
+    }
+}
+
+#[test]
+fn test_token_aware_policy() {
+    let nodes = nodes();
+    let policy = TokenAwarePolicy::new(DcAwareRoundRobinPolicy::new("dc1"),
+                                       Arc::new(FixedReplicas(vec![vec![1, 3], vec![2, 3]])));
+    assert_eq!(policy.distance(&nodes[1]), NodeDistance::Remote);
+
+    // remote replica isn't preferred
+    assert_eq!(policy.new_query_plan(&nodes, None, Some(&[0])), vec![3, 0, 2, 1, 4]);
+    assert_eq!(policy.new_query_plan(&nodes, Some(&Consistency::LocalOne), Some(&[0])), vec![3, 2, 0]);
+    // local replicas keep round-robin order of the child policy
+    assert_eq!(policy.new_query_plan(&nodes, None, Some(&[1])), vec![3, 2, 0, 1, 4]);
+    assert_eq!(policy.new_query_plan(&nodes, None, Some(&[1])), vec![2, 3, 0, 4, 1]);
+    // requests without a routing key are routed by the child policy
+    assert_eq!(policy.new_query_plan(&nodes, None, None), vec![2, 3, 0, 1, 4]);
+
+    // replicas which are down are skipped
+    nodes[3].mark_down();
+    assert_eq!(policy.new_query_plan(&nodes, Some(&Consistency::LocalOne), Some(&[0])), vec![2, 0]);
+}
+
+fn located_server(datacenter: &str, rack: &str) -> MockServer {
+    let server = MockServer::start().unwrap();
+    let location = MockRows::new("system", "local")
+        .column("data_center", ColType::Varchar)
+        .column("rack", ColType::Varchar)
+        .row(vec![CBytes::new(datacenter.as_bytes().to_vec()), CBytes::new(rack.as_bytes().to_vec())]);
+    server.when(RequestMatcher::query(LOCATION_QUERY)).then(MockResponse::rows(location));
+    return server;
+}
+
+fn queries_count(server: &MockServer) -> usize {
+    return server.requests().iter()
+        .filter(|request| match request.body {
+            RequestBody::Query(ref query) => query.query.as_str() == "SELECT 1",
+            _ => false
+        })
+        .count();
+}
+
+#[test]
+fn test_cluster_dc_aware_load_balancing() {
+    let servers = vec![located_server("dc2", "r1"), located_server("dc1", "r1"), located_server("dc1", "r2")];
+    let mut cluster = Cluster::new();
+    for server in servers.iter() {
+        let config = r2d2::Config::builder()
+            .pool_size(1)
+            .connection_timeout(Duration::from_millis(500))
+            .build();
+        let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+        let mut manager = ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"),
+                                                 Compression::None);
+        // downed nodes aren't connected on demand
+        manager.reconnection_policy(Some(Arc::new(ConstantReconnectionPolicy::new(Duration::from_secs(60)))));
+        cluster.add_node(config, manager).unwrap();
+    }
+    cluster.load_balancing_policy(Arc::new(DcAwareRoundRobinPolicy::new("dc1")));
+
+    let location = cluster.node_state(0).unwrap().location();
+    assert_eq!(location.datacenter, Some("dc2".to_string()));
+    assert_eq!(location.rack, Some("r1".to_string()));
+
+    for _ in 0..4 {
+        cluster.query(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+    }
+    assert_eq!(queries_count(&servers[0]), 0);
+    assert_eq!(queries_count(&servers[1]), 2);
+    assert_eq!(queries_count(&servers[2]), 2);
+
+    cluster.node_state(1).unwrap().mark_down();
+    cluster.node_state(2).unwrap().mark_down();
+    let query = QueryBuilder::new("SELECT 1").consistency(Consistency::LocalOne).finalize();
+    assert!(cluster.query(query, false, false).is_err());
+    cluster.query(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+    assert_eq!(queries_count(&servers[0]), 1);
+}
+
+#[test]
+fn test_cluster_token_aware_load_balancing() {
+    let servers = vec![located_server("dc1", "r1"), located_server("dc1", "r1"), located_server("dc2", "r1")];
+    let mut cluster = Cluster::new();
+    for server in servers.iter() {
+        let config = r2d2::Config::builder().pool_size(1).build();
+        let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+        let manager = ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"),
+                                             Compression::None);
+        cluster.add_node(config, manager).unwrap();
+    }
+    let locator = FixedReplicas(vec![vec![1, 2]]);
+    cluster.load_balancing_policy(Arc::new(TokenAwarePolicy::new(DcAwareRoundRobinPolicy::new("dc1"),
+                                                                 Arc::new(locator))));
+
+    for _ in 0..3 {
+        let query = QueryBuilder::new("SELECT 1").routing_key(vec![0]).finalize();
+        cluster.query(query, false, false).unwrap();
+    }
+    assert_eq!(queries_count(&servers[0]), 0);
+    assert_eq!(queries_count(&servers[1]), 3);
+    assert_eq!(queries_count(&servers[2]), 0);
+
+    cluster.query(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+    cluster.query(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+    assert_eq!(queries_count(&servers[0]), 1);
+    assert_eq!(queries_count(&servers[1]), 4);
+}
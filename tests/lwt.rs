@@ -0,0 +1,207 @@
+extern crate cdrs;
+extern crate r2d2;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{BatchQueryBuilder, CDRS, QueryBuilder, Session};
+use cdrs::compression::Compression;
+use cdrs::connection_manager::ConnectionManager;
+use cdrs::consistency::Consistency;
+use cdrs::error::Error;
+use cdrs::frame::frame_error::{AdditionalErrorInfo, CDRSError, WriteTimeoutError, WriteType};
+use cdrs::frame::Opcode;
+use cdrs::frame::frame_query::{ParamsReqQuery, QueryFlags};
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::frame::frame_result::ColType;
+use cdrs::lwt::{APPLIED_COLUMN, LwtResult};
+use cdrs::testing::{MockResponse, MockRows, MockServer, RequestMatcher, prepared_id};
+use cdrs::transport::TransportTcp;
+use cdrs::types::{CBytes, CBytesShort, CString, IntoRustByName};
+use r2d2::ManageConnection;
+
+const INSERT: &'static str = "INSERT INTO ks.leases (name, owner) VALUES ('leader', 'a') IF NOT EXISTS";
+
+fn connect(server: &MockServer) -> Session<PasswordAuthenticator, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    return CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+}
+
+fn applied() -> MockResponse {
+    return MockResponse::rows(MockRows::new("ks", "leases")
+        .column(APPLIED_COLUMN, ColType::Boolean)
+        .row(vec![CBytes::new(vec![1])]));
+}
+
+fn not_applied(owner: &str) -> MockResponse {
+    return MockResponse::rows(MockRows::new("ks", "leases")
+        .column(APPLIED_COLUMN, ColType::Boolean)
+        .column("name", ColType::Varchar)
+        .column("owner", ColType::Varchar)
+        .row(vec![CBytes::new(vec![0]), CBytes::new(b"leader".to_vec()), CBytes::new(owner.as_bytes().to_vec())]));
+}
+
+fn write_timeout(write_type: WriteType) -> MockResponse {
+    let additional_info = AdditionalErrorInfo::WriteTimeout(WriteTimeoutError {
+        cl: Consistency::Serial,
+        received: 0,
+        blockfor: 2,
+        write_type: write_type
+    });
+    return MockResponse::error(CDRSError {
+        error_code: additional_info.error_code(),
+        message: CString::new("Operation timed out".to_string()),
+        additional_info: additional_info
+    });
+}
+
+fn insert_attempts(server: &MockServer) -> usize {
+    return server.requests().iter()
+        .filter(|request| match request.body {
+            RequestBody::Query(ref query) => query.query.as_str() == INSERT,
+            _ => false
+        })
+        .count();
+}
+
+#[test]
+fn test_lwt_result() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query(INSERT)).times(1).then(applied());
+    server.when(RequestMatcher::query(INSERT)).then(not_applied("b"));
+    server.when(RequestMatcher::query("SELECT owner FROM ks.leases"))
+        .then(MockResponse::rows(MockRows::new("ks", "leases").column("owner", ColType::Varchar)));
+
+    let mut session = connect(&server);
+    let result = session.lwt(QueryBuilder::new(INSERT).finalize(), false, false).unwrap();
+    assert!(result.applied());
+    assert!(result.existing().is_none());
+
+    let result = session.lwt(QueryBuilder::new(INSERT).finalize(), false, false).unwrap();
+    assert!(!result.applied());
+    let existing = result.existing().unwrap();
+    let owner: String = existing.get_by_name("owner").unwrap().unwrap();
+    assert_eq!(owner, "b");
+    assert_eq!(result.rows().len(), 1);
+
+    // results of non-conditional queries don't have [applied] column
+    assert!(session.lwt(QueryBuilder::new("SELECT owner FROM ks.leases").finalize(), false, false).is_err());
+    let void = session.query(QueryBuilder::new("UPDATE ks.leases SET owner = 'c'").finalize(), false, false)
+        .unwrap();
+    assert!(LwtResult::from_frame(&void).is_err());
+}
+
+#[test]
+fn test_session_serial_consistency() {
+    let server = MockServer::start().unwrap();
+    let mut session = connect(&server);
+    session.serial_consistency(Some(Consistency::LocalSerial));
+
+    session.query(QueryBuilder::new("UPDATE ks.leases SET owner = 'a'").finalize(), false, false).unwrap();
+    let query = QueryBuilder::new("UPDATE ks.leases SET owner = 'b'").serial_consistency(Consistency::Serial).finalize();
+    session.query(query, false, false).unwrap();
+    let batch = BatchQueryBuilder::new()
+        .add_query("UPDATE ks.leases SET owner = 'c'".to_string(), vec![])
+        .finalize();
+    session.batch(batch, false, false).unwrap();
+
+    let serial_consistencies: Vec<Option<Consistency>> = server.requests().iter()
+        .filter_map(|request| match request.body {
+            RequestBody::Query(ref query) => {
+                let params = &query.query_params;
+                if params.flags.contains(&QueryFlags::WithSerialConsistency) {
+                    Some(Some(params.serial_consistency.clone()))
+                } else {
+                    Some(None)
+                }
+            },
+            RequestBody::Batch(ref batch) => Some(batch.serial_consistency.clone()),
+            _ => None
+        })
+        .collect();
+    assert_eq!(serial_consistencies, vec![Some(Consistency::LocalSerial), Some(Consistency::Serial),
+                                          Some(Consistency::LocalSerial)]);
+}
+
+#[test]
+fn test_lwt_retries_cas_write_timeouts() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query(INSERT)).times(2).then(write_timeout(WriteType::Cas));
+    server.when(RequestMatcher::query(INSERT)).then(applied());
+
+    let mut session = connect(&server);
+    match session.lwt(QueryBuilder::new(INSERT).finalize(), false, false) {
+        Err(Error::Server(_)) => {},
+        other => panic!("Unexpected result {:?}", other.map(|result| result.applied()))
+    }
+    assert_eq!(insert_attempts(&server), 1);
+
+    session.cas_timeout_retries(3);
+    assert!(session.lwt(QueryBuilder::new(INSERT).finalize(), false, false).unwrap().applied());
+    assert_eq!(insert_attempts(&server), 3);
+}
+
+#[test]
+fn test_lwt_does_not_retry_other_write_timeouts() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query(INSERT)).then(write_timeout(WriteType::Simple));
+
+    let mut session = connect(&server);
+    session.cas_timeout_retries(3);
+    assert!(session.lwt(QueryBuilder::new(INSERT).finalize(), false, false).is_err());
+    assert_eq!(insert_attempts(&server), 1);
+}
+
+#[test]
+fn test_lwt_execute_and_batch() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::execute(prepared_id(INSERT))).times(1).then(write_timeout(WriteType::Cas));
+    server.when(RequestMatcher::execute(prepared_id(INSERT))).then(applied());
+    server.when(RequestMatcher::opcode(Opcode::Batch)).times(1).then(write_timeout(WriteType::Cas));
+    server.when(RequestMatcher::opcode(Opcode::Batch)).then(not_applied("b"));
+
+    let mut session = connect(&server);
+    session.cas_timeout_retries(1);
+    let params = ParamsReqQuery {
+        consistency: Consistency::Quorum,
+        flags: vec![],
+        values: vec![],
+        page_size: 0,
+        paging_state: CBytes::new(vec![]),
+        serial_consistency: Consistency::Serial,
+        timestamp: 0
+    };
+    let result = session.lwt_execute(CBytesShort::new(prepared_id(INSERT)), params, false, false).unwrap();
+    assert!(result.applied());
+
+    let batch = BatchQueryBuilder::new()
+        .add_query_prepared(CBytesShort::new(prepared_id(INSERT)), vec![])
+        .finalize();
+    let result = session.lwt_batch(batch, false, false).unwrap();
+    assert!(!result.applied());
+    let owner: String = result.existing().unwrap().get_by_name("owner").unwrap().unwrap();
+    assert_eq!(owner, "b");
+
+    let attempts = server.requests().iter().filter(|request| request.opcode != Opcode::Startup).count();
+    assert_eq!(attempts, 4);
+}
+
+#[test]
+fn test_connection_manager_lwt_options() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query(INSERT)).times(1).then(write_timeout(WriteType::Cas));
+    server.when(RequestMatcher::query(INSERT)).then(applied());
+
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let mut manager = ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"),
+                                             Compression::None);
+    manager.serial_consistency(Some(Consistency::LocalSerial)).cas_timeout_retries(1);
+    let mut connection = manager.connect().unwrap();
+
+    assert!(connection.lwt(QueryBuilder::new(INSERT).finalize(), false, false).unwrap().applied());
+    assert_eq!(insert_attempts(&server), 2);
+    let serial_consistency = match server.requests().last().unwrap().body {
+        RequestBody::Query(ref query) => query.query_params.serial_consistency.clone(),
+        ref other => panic!("Unexpected request {:?}", other)
+    };
+    assert_eq!(serial_consistency, Consistency::LocalSerial);
+}
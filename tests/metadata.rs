@@ -0,0 +1,392 @@
+extern crate cdrs;
+
+use std::collections::{BTreeMap, HashMap};
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, Session};
+use cdrs::compression::Compression;
+use cdrs::frame::frame_event::ServerEvent;
+use cdrs::frame::frame_register::SimpleServerEvent;
+use cdrs::frame::frame_result::{BodyResResultSchemaChange, ChangeSchemeOptions, ChangeType, ColType,
+                                ColTypeOption, ColTypeOptionValue, CTuple, CUdt, Target};
+use cdrs::metadata::{ClusteringOrder, ColumnKind, Metadata, ReplicationStrategy};
+use cdrs::metadata::type_parser::{DURATION_CLASS, UserTypeDefinitions, parse_type};
+use cdrs::testing::{MockResponse, MockRows, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use cdrs::types::{CBytes, CString, to_int};
+
+fn connect(server: &MockServer) -> Session<PasswordAuthenticator, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    return CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+}
+
+fn simple(id: ColType) -> ColTypeOption {
+    return ColTypeOption { id: id, value: None };
+}
+
+fn list_of(item_type: ColTypeOption) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::List,
+        value: Some(ColTypeOptionValue::CList(Box::new(item_type)))
+    };
+}
+
+fn map_of(key_type: ColTypeOption, value_type: ColTypeOption) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Map,
+        value: Some(ColTypeOptionValue::CMap((Box::new(key_type), Box::new(value_type))))
+    };
+}
+
+fn udt(name: &str, fields: Vec<(&str, ColTypeOption)>) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Udt,
+        value: Some(ColTypeOptionValue::UdtType(CUdt {
+            ks: CString::new("ks".to_string()),
+            udt_name: CString::new(name.to_string()),
+            descriptions: fields.into_iter()
+                .map(|(name, col_type)| (CString::new(name.to_string()), col_type))
+                .collect()
+        }))
+    };
+}
+
+fn user_types() -> UserTypeDefinitions {
+    let mut user_types = HashMap::new();
+    user_types.insert("phone".to_string(), vec![("number".to_string(), "text".to_string())]);
+    user_types.insert("address".to_string(), vec![
+        ("street".to_string(), "text".to_string()),
+        ("phones".to_string(), "list<frozen<phone>>".to_string())
+    ]);
+    user_types.insert("Quoted Type".to_string(), vec![("value".to_string(), "int".to_string())]);
+    return user_types;
+}
+
+fn address_type() -> ColTypeOption {
+    let phone = udt("phone", vec![("number", simple(ColType::Varchar))]);
+    return udt("address", vec![("street", simple(ColType::Varchar)), ("phones", list_of(phone))]);
+}
+
+#[test]
+fn test_parse_type() {
+    let user_types = user_types();
+    let parse = |type_string: &str| parse_type(type_string, "ks", &user_types).unwrap();
+
+    assert_eq!(parse("int"), simple(ColType::Int));
+    assert_eq!(parse("text"), simple(ColType::Varchar));
+    assert_eq!(parse("varchar"), simple(ColType::Varchar));
+    assert_eq!(parse("TIMEUUID"), simple(ColType::Timeuuid));
+    assert_eq!(parse("map<text, frozen<list<int>>>"),
+               map_of(simple(ColType::Varchar), list_of(simple(ColType::Int))));
+    assert_eq!(parse("frozen<tuple<int, text>>"), ColTypeOption {
+        id: ColType::Tuple,
+        value: Some(ColTypeOptionValue::TupleType(CTuple {
+            types: vec![simple(ColType::Int), simple(ColType::Varchar)]
+        }))
+    });
+    assert_eq!(parse("set<uuid>"), ColTypeOption {
+        id: ColType::Set,
+        value: Some(ColTypeOptionValue::CSet(Box::new(simple(ColType::Uuid))))
+    });
+    assert_eq!(parse("frozen<address>"), address_type());
+    assert_eq!(parse("frozen<\"Quoted Type\">"), udt("Quoted Type", vec![("value", simple(ColType::Int))]));
+    assert_eq!(parse("'org.apache.cassandra.db.marshal.BytesType'"), ColTypeOption {
+        id: ColType::Custom,
+        value: Some(ColTypeOptionValue::CString(CString::new("org.apache.cassandra.db.marshal.BytesType"
+            .to_string())))
+    });
+    assert_eq!(parse("duration"), ColTypeOption {
+        id: ColType::Custom,
+        value: Some(ColTypeOptionValue::CString(CString::new(DURATION_CLASS.to_string())))
+    });
+}
+
+#[test]
+fn test_parse_invalid_type() {
+    let user_types = user_types();
+    for type_string in vec!["", "unknown", "list<int", "map<int>", "list<int, int>", "int<text>", "int text"] {
+        assert!(parse_type(type_string, "ks", &user_types).is_err(), "{} should be invalid", type_string);
+    }
+
+    // recursive types can't be created by Cassandra but shouldn't overflow the stack
+    let mut recursive = HashMap::new();
+    recursive.insert("node".to_string(), vec![("next".to_string(), "frozen<node>".to_string())]);
+    assert!(parse_type("node", "ks", &recursive).is_err());
+}
+
+#[test]
+fn test_replication_strategy() {
+    let options = |pairs: Vec<(&str, &str)>| -> BTreeMap<String, String> {
+        return pairs.into_iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+    };
+
+    assert_eq!(ReplicationStrategy::from_options(&options(vec![
+        ("class", "org.apache.cassandra.locator.SimpleStrategy"), ("replication_factor", "3")
+    ])), ReplicationStrategy::SimpleStrategy { replication_factor: 3 });
+
+    let mut datacenters = BTreeMap::new();
+    datacenters.insert("dc1".to_string(), 3);
+    datacenters.insert("dc2".to_string(), 2);
+    assert_eq!(ReplicationStrategy::from_options(&options(vec![
+        ("class", "NetworkTopologyStrategy"), ("dc1", "3"), ("dc2", "2/1")
+    ])), ReplicationStrategy::NetworkTopologyStrategy { datacenters: datacenters });
+
+    assert_eq!(ReplicationStrategy::from_options(&options(vec![
+        ("class", "org.apache.cassandra.locator.LocalStrategy")
+    ])), ReplicationStrategy::LocalStrategy);
+
+    let custom = options(vec![("class", "com.example.CustomStrategy"), ("factor", "x")]);
+    assert_eq!(ReplicationStrategy::from_options(&custom), ReplicationStrategy::Other {
+        class: "com.example.CustomStrategy".to_string(),
+        options: custom.clone()
+    });
+}
+
+fn text(value: &str) -> CBytes {
+    return CBytes::new(value.as_bytes().to_vec());
+}
+
+fn int(value: i32) -> CBytes {
+    return CBytes::new(to_int(value as i64));
+}
+
+fn boolean(value: bool) -> CBytes {
+    return CBytes::new(vec![value as u8]);
+}
+
+fn push_text(bytes: &mut Vec<u8>, value: &str) {
+    bytes.extend(to_int(value.len() as i64));
+    bytes.extend_from_slice(value.as_bytes());
+}
+
+fn text_list(items: Vec<&str>) -> CBytes {
+    let mut bytes = to_int(items.len() as i64);
+    for item in items {
+        push_text(&mut bytes, item);
+    }
+    return CBytes::new(bytes);
+}
+
+fn text_map(pairs: Vec<(&str, &str)>) -> CBytes {
+    let mut bytes = to_int(pairs.len() as i64);
+    for (key, value) in pairs {
+        push_text(&mut bytes, key);
+        push_text(&mut bytes, value);
+    }
+    return CBytes::new(bytes);
+}
+
+fn text_list_type() -> ColTypeOption {
+    return list_of(simple(ColType::Varchar));
+}
+
+fn text_map_type() -> ColTypeOption {
+    return map_of(simple(ColType::Varchar), simple(ColType::Varchar));
+}
+
+fn schema_query(table: &str, keyspace: Option<&str>) -> String {
+    return match keyspace {
+        Some(keyspace) => format!("SELECT * FROM system_schema.{} WHERE keyspace_name = '{}'", table, keyspace),
+        None => format!("SELECT * FROM system_schema.{}", table)
+    };
+}
+
+/// Mocks `system_schema` tables of keyspace `ks` with a table `users`, optionally
+/// with one more table `events`. Tables which aren't mocked return no rows.
+fn mock_schema(server: &MockServer, keyspace: Option<&str>, with_events: bool) {
+    let mock = |table: &str, rows: MockRows| {
+        server.when(RequestMatcher::query(schema_query(table, keyspace).as_str()))
+            .then(MockResponse::rows(rows));
+    };
+
+    mock("keyspaces", MockRows::new("system_schema", "keyspaces")
+        .column("keyspace_name", ColType::Varchar)
+        .column("durable_writes", ColType::Boolean)
+        .column_with_type("replication", text_map_type())
+        .row(vec![text("ks"), boolean(false),
+                  text_map(vec![("class", "org.apache.cassandra.locator.NetworkTopologyStrategy"), ("dc1", "3")])]));
+
+    let mut tables = MockRows::new("system_schema", "tables")
+        .column("keyspace_name", ColType::Varchar)
+        .column("table_name", ColType::Varchar)
+        .row(vec![text("ks"), text("users")]);
+    if with_events {
+        tables = tables.row(vec![text("ks"), text("events")]);
+    }
+    mock("tables", tables);
+
+    let column = |table: &str, name: &str, kind: &str, position: i32, order: &str, col_type: &str| {
+        return vec![text("ks"), text(table), text(name), text(kind), int(position), text(order), text(col_type)];
+    };
+    let mut columns = MockRows::new("system_schema", "columns")
+        .column("keyspace_name", ColType::Varchar)
+        .column("table_name", ColType::Varchar)
+        .column("column_name", ColType::Varchar)
+        .column("kind", ColType::Varchar)
+        .column("position", ColType::Int)
+        .column("clustering_order", ColType::Varchar)
+        .column("type", ColType::Varchar)
+        .row(column("users", "name", "regular", -1, "none", "text"))
+        .row(column("users", "created", "clustering", 0, "desc", "timestamp"))
+        .row(column("users", "bucket", "partition_key", 1, "none", "int"))
+        .row(column("users", "address", "regular", -1, "none", "frozen<address>"))
+        .row(column("users", "id", "partition_key", 0, "none", "uuid"))
+        .row(column("users", "tags", "static", -1, "none", "set<text>"))
+        .row(column("users_by_name", "name", "partition_key", 0, "none", "text"))
+        .row(column("users_by_name", "id", "clustering", 0, "asc", "uuid"));
+    if with_events {
+        columns = columns.row(column("events", "id", "partition_key", 0, "none", "timeuuid"));
+    }
+    mock("columns", columns);
+
+    mock("types", MockRows::new("system_schema", "types")
+        .column("keyspace_name", ColType::Varchar)
+        .column("type_name", ColType::Varchar)
+        .column_with_type("field_names", text_list_type())
+        .column_with_type("field_types", text_list_type())
+        .row(vec![text("ks"), text("address"), text_list(vec!["street", "phones"]),
+                  text_list(vec!["text", "list<frozen<phone>>"])])
+        .row(vec![text("ks"), text("phone"), text_list(vec!["number"]), text_list(vec!["text"])]));
+
+    mock("indexes", MockRows::new("system_schema", "indexes")
+        .column("keyspace_name", ColType::Varchar)
+        .column("table_name", ColType::Varchar)
+        .column("index_name", ColType::Varchar)
+        .column("kind", ColType::Varchar)
+        .column_with_type("options", text_map_type())
+        .row(vec![text("ks"), text("users"), text("users_tags_idx"), text("COMPOSITES"),
+                  text_map(vec![("target", "values(tags)")])]));
+
+    mock("views", MockRows::new("system_schema", "views")
+        .column("keyspace_name", ColType::Varchar)
+        .column("view_name", ColType::Varchar)
+        .column("base_table_name", ColType::Varchar)
+        .column("include_all_columns", ColType::Boolean)
+        .column("where_clause", ColType::Varchar)
+        .row(vec![text("ks"), text("users_by_name"), text("users"), boolean(false),
+                  text("name IS NOT NULL AND id IS NOT NULL")]));
+
+    mock("functions", MockRows::new("system_schema", "functions")
+        .column("keyspace_name", ColType::Varchar)
+        .column("function_name", ColType::Varchar)
+        .column_with_type("argument_names", text_list_type())
+        .column_with_type("argument_types", text_list_type())
+        .column("return_type", ColType::Varchar)
+        .column("language", ColType::Varchar)
+        .column("body", ColType::Varchar)
+        .column("called_on_null_input", ColType::Boolean)
+        .row(vec![text("ks"), text("plus"), text_list(vec!["a", "b"]), text_list(vec!["int", "int"]),
+                  text("int"), text("java"), text("return a + b;"), boolean(true)]));
+
+    mock("aggregates", MockRows::new("system_schema", "aggregates")
+        .column("keyspace_name", ColType::Varchar)
+        .column("aggregate_name", ColType::Varchar)
+        .column_with_type("argument_types", text_list_type())
+        .column("state_func", ColType::Varchar)
+        .column("state_type", ColType::Varchar)
+        .column("final_func", ColType::Varchar)
+        .column("initcond", ColType::Varchar)
+        .column("return_type", ColType::Varchar)
+        .row(vec![text("ks"), text("total"), text_list(vec!["int"]), text("plus"), text("int"),
+                  CBytes::new_null(), text("0"), text("int")]));
+}
+
+#[test]
+fn test_metadata_refresh() {
+    let server = MockServer::start().unwrap();
+    mock_schema(&server, None, false);
+
+    let mut session = connect(&server);
+    let metadata = Metadata::new();
+    assert!(metadata.keyspace_names().is_empty());
+    metadata.refresh(&mut session).unwrap();
+    assert_eq!(metadata.keyspace_names(), vec!["ks".to_string()]);
+
+    let keyspace = metadata.keyspace("ks").unwrap();
+    assert!(!keyspace.durable_writes);
+    let mut datacenters = BTreeMap::new();
+    datacenters.insert("dc1".to_string(), 3);
+    assert_eq!(keyspace.replication, ReplicationStrategy::NetworkTopologyStrategy { datacenters: datacenters });
+
+    let users = metadata.table("ks", "users").unwrap();
+    let names = |columns: &Vec<_>| -> Vec<String> {
+        return columns.iter().map(|column: &cdrs::metadata::ColumnMetadata| column.name.clone()).collect();
+    };
+    assert_eq!(names(&users.partition_key), vec!["id", "bucket"]);
+    assert_eq!(names(&users.clustering_columns), vec!["created"]);
+    assert_eq!(names(&users.columns), vec!["id", "bucket", "created", "address", "name", "tags"]);
+
+    let created = users.column("created").unwrap();
+    assert_eq!(created.kind, ColumnKind::Clustering);
+    assert_eq!(created.clustering_order, ClusteringOrder::Desc);
+    assert_eq!(created.col_type, simple(ColType::Timestamp));
+    let address = users.column("address").unwrap();
+    assert_eq!(address.type_string, "frozen<address>");
+    assert_eq!(address.col_type, address_type());
+    assert_eq!(users.column("tags").unwrap().kind, ColumnKind::Static);
+
+    assert_eq!(users.indexes.len(), 1);
+    assert_eq!(users.indexes[0].name, "users_tags_idx");
+    assert_eq!(users.indexes[0].options.get("target"), Some(&"values(tags)".to_string()));
+
+    let view = keyspace.views.get("users_by_name").unwrap();
+    assert_eq!(view.base_table, "users");
+    assert!(!view.include_all_columns);
+    assert_eq!(names(&view.table.partition_key), vec!["name"]);
+    assert_eq!(view.table.clustering_columns[0].clustering_order, ClusteringOrder::Asc);
+    // views aren't tables
+    assert!(metadata.table("ks", "users_by_name").is_none());
+
+    let phone = metadata.user_type("ks", "phone").unwrap();
+    assert_eq!(phone.fields, vec![("number".to_string(), simple(ColType::Varchar))]);
+    assert_eq!(metadata.user_type("ks", "address").unwrap().fields[1].1,
+               list_of(udt("phone", vec![("number", simple(ColType::Varchar))])));
+
+    assert_eq!(keyspace.functions.len(), 1);
+    let function = &keyspace.functions[0];
+    assert_eq!(function.name, "plus");
+    assert_eq!(function.argument_names, vec!["a", "b"]);
+    assert_eq!(function.argument_types, vec![simple(ColType::Int), simple(ColType::Int)]);
+    assert_eq!(function.return_type, simple(ColType::Int));
+    assert_eq!(function.language, "java");
+    assert!(function.called_on_null_input);
+
+    assert_eq!(keyspace.aggregates.len(), 1);
+    let aggregate = &keyspace.aggregates[0];
+    assert_eq!(aggregate.state_func, "plus");
+    assert_eq!(aggregate.final_func, None);
+    assert_eq!(aggregate.initcond, Some("0".to_string()));
+}
+
+#[test]
+fn test_metadata_schema_change_events() {
+    let server = MockServer::start().unwrap();
+    mock_schema(&server, None, false);
+    mock_schema(&server, Some("ks"), true);
+
+    let mut session = connect(&server);
+    let metadata = Metadata::new();
+    metadata.refresh(&mut session).unwrap();
+    assert!(metadata.table("ks", "events").is_none());
+
+    let mut listener = connect(&server).listen_for(vec![SimpleServerEvent::SchemaChange]).unwrap();
+    assert_eq!(server.push_event(ServerEvent::SchemaChange(BodyResResultSchemaChange {
+        change_type: ChangeType::Created,
+        target: Target::Table,
+        options: ChangeSchemeOptions::Table(("ks".to_string(), "events".to_string()))
+    })), 1);
+    let event = listener.next().unwrap();
+    metadata.process_event(&mut session, &event).unwrap();
+    assert_eq!(metadata.table("ks", "events").unwrap().partition_key[0].col_type, simple(ColType::Timeuuid));
+    assert!(metadata.table("ks", "users").is_some());
+
+    server.push_event(ServerEvent::SchemaChange(BodyResResultSchemaChange {
+        change_type: ChangeType::Dropped,
+        target: Target::Keyspace,
+        options: ChangeSchemeOptions::Keyspace("ks".to_string())
+    }));
+    let event = listener.next().unwrap();
+    metadata.process_event(&mut session, &event).unwrap();
+    assert!(metadata.keyspace("ks").is_none());
+}
@@ -0,0 +1,295 @@
+extern crate cdrs;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{BatchQueryBuilder, CDRS, QueryBuilder, Session};
+use cdrs::compression::Compression;
+use cdrs::consistency::Consistency;
+use cdrs::error::Error;
+use cdrs::frame::{Frame, Opcode};
+use cdrs::frame::frame_batch::{BatchQuerySubject, BatchType};
+use cdrs::frame::frame_error::AdditionalErrorInfo;
+use cdrs::frame::frame_event::{ServerEvent, StatusChange, StatusChangeType, TopologyChange,
+                               TopologyChangeType, EVENT_STREAM_ID};
+use cdrs::frame::frame_query::{ParamsReqQuery, QueryFlags};
+use cdrs::frame::frame_register::SimpleServerEvent;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::frame::frame_response::ResponseBody;
+use cdrs::frame::frame_result::{ColType, ResResultBody};
+use cdrs::frame::parser::parse_frame;
+use cdrs::testing::{MockAction, MockResponse, MockRows, MockServer, RequestMatcher, prepared_id,
+                    MOCK_KEYSPACE, MOCK_TABLE};
+use cdrs::transport::TransportTcp;
+use cdrs::types::{CBytes, CBytesShort, CInet, IntoRustByName, to_int};
+use cdrs::types::value::Value;
+use cdrs::IntoBytes;
+
+fn connect(server: &MockServer) -> Session<PasswordAuthenticator, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    return CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+}
+
+#[test]
+fn test_mock_server_rows() {
+    let server = MockServer::start().unwrap();
+    let rows = MockRows::new(MOCK_KEYSPACE, MOCK_TABLE)
+        .column("id", ColType::Int)
+        .column("name", ColType::Varchar)
+        .row(vec![CBytes::new(to_int(1)), CBytes::new(b"one".to_vec())])
+        .row(vec![CBytes::new(to_int(2)), CBytes::new(b"two".to_vec())]);
+    server.when(RequestMatcher::query("SELECT * FROM mock.mock"))
+        .then(MockResponse::rows(rows));
+
+    let mut session = connect(&server);
+    let rows = session.query(QueryBuilder::new("SELECT * FROM mock.mock").finalize(), false, false)
+        .unwrap()
+        .get_body()
+        .into_rows()
+        .unwrap();
+
+    assert_eq!(rows.len(), 2);
+    let id: i32 = rows[1].get_by_name("id").unwrap().unwrap();
+    let name: String = rows[1].get_by_name("name").unwrap().unwrap();
+    assert_eq!(id, 2);
+    assert_eq!(name, "two".to_string());
+}
+
+#[test]
+fn test_mock_server_records_requests() {
+    let server = MockServer::start().unwrap();
+    let mut session = connect(&server);
+
+    let query = QueryBuilder::new("INSERT INTO mock.mock (id) VALUES (?)")
+        .values(vec![Value::new_normal(to_int(7))])
+        .consistency(Consistency::Quorum)
+        .timestamp(1000)
+        .finalize();
+    session.query(query, true, false).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].opcode, Opcode::Startup);
+    assert_eq!(requests[1].opcode, Opcode::Query);
+    assert!(requests[1].tracing);
+    match requests[1].body {
+        RequestBody::Query(ref query) => {
+            assert_eq!(query.query.as_str(), "INSERT INTO mock.mock (id) VALUES (?)");
+            assert_eq!(query.query_params.consistency, Consistency::Quorum);
+            assert_eq!(query.query_params.values.len(), 1);
+            assert_eq!(query.query_params.values[0].body, to_int(7));
+            assert_eq!(query.query_params.timestamp, 1000);
+        },
+        ref body => panic!("Unexpected request body {:?}", body)
+    }
+}
+
+#[test]
+fn test_mock_server_batch() {
+    use std::collections::HashMap;
+
+    let server = MockServer::start().unwrap();
+    let mut response_payload = HashMap::new();
+    response_payload.insert("served-by".to_string(), b"node1".to_vec());
+    server.when(RequestMatcher::opcode(Opcode::Batch))
+        .then(MockResponse::void().with_custom_payload(response_payload.clone()));
+
+    let mut session = connect(&server);
+    let mut request_payload = HashMap::new();
+    request_payload.insert("tenant".to_string(), b"a".to_vec());
+    let batch = BatchQueryBuilder::new()
+        .batch_type(BatchType::Unlogged)
+        .add_query("INSERT INTO mock.mock (id) VALUES (?)".to_string(), vec![Value::new_normal(to_int(1))])
+        .add_query_prepared(CBytesShort::new(vec![9, 9]), vec![Value::new_normal(to_int(2)), Value::new_null()])
+        .consistency(Consistency::Quorum)
+        .serial_consistency(Consistency::LocalSerial)
+        .timestamp(1000)
+        .custom_payload(request_payload.clone())
+        .finalize();
+    let response = session.batch(batch, false, false).unwrap();
+    assert_eq!(response.custom_payload(), response_payload);
+
+    let requests = server.requests();
+    assert_eq!(requests[1].opcode, Opcode::Batch);
+    assert_eq!(requests[1].custom_payload, request_payload);
+    match requests[1].body {
+        RequestBody::Batch(ref batch) => {
+            assert_eq!(batch.batch_type, BatchType::Unlogged);
+            assert_eq!(batch.consistency, Consistency::Quorum);
+            assert_eq!(batch.serial_consistency, Some(Consistency::LocalSerial));
+            assert_eq!(batch.timestamp, Some(1000));
+            assert_eq!(batch.queries.len(), 2);
+            match batch.queries[0].subject {
+                BatchQuerySubject::QueryString(ref query) => {
+                    assert_eq!(query.as_str(), "INSERT INTO mock.mock (id) VALUES (?)");
+                },
+                ref subject => panic!("Unexpected subject {:?}", subject)
+            }
+            assert_eq!(batch.queries[0].values[0].body, to_int(1));
+            match batch.queries[1].subject {
+                BatchQuerySubject::PreparedId(ref id) => assert_eq!(id.as_slice(), &[9, 9]),
+                ref subject => panic!("Unexpected subject {:?}", subject)
+            }
+            assert_eq!(batch.queries[1].values.len(), 2);
+        },
+        ref body => panic!("Unexpected request body {:?}", body)
+    }
+}
+
+#[test]
+fn test_mock_server_default_responses() {
+    let server = MockServer::start().unwrap();
+    let mut session = connect(&server);
+
+    match session.query(QueryBuilder::new("USE my_keyspace").finalize(), false, false)
+        .unwrap()
+        .get_body() {
+        ResponseBody::Result(ResResultBody::SetKeyspace(ref body)) => {
+            assert_eq!(body.body.as_str(), "my_keyspace");
+        },
+        ref body => panic!("Unexpected response body {:?}", body)
+    }
+
+    let query = "SELECT * FROM mock.mock WHERE id = ?";
+    let id = match session.prepare(query.to_string(), false, false).unwrap().get_body() {
+        ResponseBody::Result(ResResultBody::Prepared(ref prepared)) => prepared.id.clone(),
+        ref body => panic!("Unexpected response body {:?}", body)
+    };
+    assert_eq!(id.as_slice(), prepared_id(query).as_slice());
+
+    server.when(RequestMatcher::execute(prepared_id(query)))
+        .then(MockResponse::rows(MockRows::new(MOCK_KEYSPACE, MOCK_TABLE).column("id", ColType::Int)));
+    let params = ParamsReqQuery {
+        consistency: Consistency::One,
+        flags: vec![QueryFlags::Value],
+        values: vec![Value::new_normal(to_int(1))],
+        page_size: 0,
+        paging_state: CBytes::new(vec![]),
+        serial_consistency: Consistency::Serial,
+        timestamp: 0
+    };
+    let rows = session.execute(id, params, false, false).unwrap().get_body().into_rows().unwrap();
+    assert!(rows.is_empty());
+}
+
+#[test]
+fn test_mock_server_error() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query_contains("broken"))
+        .times(1)
+        .then(MockResponse::simple_error(AdditionalErrorInfo::Overloaded, "Overloaded"));
+
+    let mut session = connect(&server);
+    match session.query(QueryBuilder::new("SELECT broken").finalize(), false, false) {
+        Err(Error::Server(err)) => {
+            assert_eq!(err.error_code, 0x1001);
+            assert_eq!(err.message.as_str(), "Overloaded");
+        },
+        other => panic!("Unexpected result {:?}", other.map(|frame| frame.opcode))
+    }
+
+    // the rule is applied only once
+    assert!(session.query(QueryBuilder::new("SELECT broken").finalize(), false, false).is_ok());
+}
+
+#[test]
+fn test_mock_server_delay() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT slow"))
+        .then(MockAction::Delay(Duration::from_millis(300), MockResponse::void()));
+
+    let mut session = connect(&server);
+    let query = QueryBuilder::new("SELECT slow").timeout(Duration::from_millis(50)).finalize();
+    match session.query(query, false, false) {
+        Err(Error::Timeout(_)) => {},
+        other => panic!("Unexpected result {:?}", other.map(|frame| frame.opcode))
+    }
+}
+
+#[test]
+fn test_mock_server_close_connection() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::opcode(Opcode::Query))
+        .then(MockAction::CloseConnection);
+
+    let mut session = connect(&server);
+    match session.query(QueryBuilder::new("SELECT 1").finalize(), false, false) {
+        Err(Error::Io(_)) => {},
+        other => panic!("Unexpected result {:?}", other.map(|frame| frame.opcode))
+    }
+}
+
+#[test]
+fn test_mock_server_invalid_request() {
+    let server = MockServer::start().unwrap();
+    let invalid_frames = vec![
+        // RESULT is a response opcode
+        (0x08, vec![]),
+        // unknown batch type
+        (0x0D, vec![9, 0, 0, 0, 1, 0]),
+        // query string is not valid UTF-8
+        (0x07, vec![0, 0, 0, 1, 0xff, 0, 1, 0])
+    ];
+
+    for (opcode, body) in invalid_frames {
+        let mut frame = vec![0x04, 0, 0, 1, opcode, 0, 0, 0, body.len() as u8];
+        frame.extend_from_slice(body.as_slice());
+
+        let mut socket = TcpStream::connect(server.addr()).unwrap();
+        socket.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+        socket.write_all(frame.as_slice()).unwrap();
+        // the connection is closed without a response
+        let mut buf = [0; 9];
+        match socket.read(&mut buf) {
+            Ok(read) => assert_eq!(read, 0),
+            Err(err) => assert_eq!(err.kind(), std::io::ErrorKind::ConnectionReset)
+        }
+    }
+
+    assert!(server.requests().is_empty());
+    let mut session = connect(&server);
+    assert!(session.query(QueryBuilder::new("SELECT 1").finalize(), false, false).is_ok());
+}
+
+#[test]
+fn test_mock_server_push_event() {
+    use std::io::Write;
+
+    let server = MockServer::start().unwrap();
+    let mut socket = TcpStream::connect(server.addr()).unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    socket.write_all(Frame::new_req_startup(None).into_cbytes().as_slice()).unwrap();
+    assert_eq!(parse_frame(&mut socket, &Compression::None).unwrap().opcode, Opcode::Ready);
+    socket.write_all(Frame::new_req_register(vec![SimpleServerEvent::StatusChange]).into_cbytes().as_slice())
+        .unwrap();
+    assert_eq!(parse_frame(&mut socket, &Compression::None).unwrap().opcode, Opcode::Ready);
+
+    // the connection hasn't registered for topology changes
+    let topology_change = ServerEvent::TopologyChange(TopologyChange {
+        change_type: TopologyChangeType::NewNode,
+        addr: CInet { addr: "127.0.0.2:9042".parse().unwrap() }
+    });
+    assert_eq!(server.push_event(topology_change), 0);
+    assert_eq!(server.push_event(ServerEvent::StatusChange(StatusChange {
+        change_type: StatusChangeType::Up,
+        addr: CInet { addr: "127.0.0.3:9042".parse().unwrap() }
+    })), 1);
+
+    let frame = parse_frame(&mut socket, &Compression::None).unwrap();
+    assert_eq!(frame.opcode, Opcode::Event);
+    assert_eq!(frame.stream, EVENT_STREAM_ID);
+    match frame.get_body() {
+        ResponseBody::Event(ref body) => match body.event {
+            ServerEvent::StatusChange(ref change) => {
+                assert_eq!(change.change_type, StatusChangeType::Up);
+                assert_eq!(change.addr.addr, "127.0.0.3:9042".parse().unwrap());
+            },
+            ref event => panic!("Unexpected event {:?}", event)
+        },
+        ref body => panic!("Unexpected response body {:?}", body)
+    }
+}
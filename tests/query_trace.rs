@@ -0,0 +1,169 @@
+extern crate cdrs;
+extern crate uuid;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, Session};
+use cdrs::compression::Compression;
+use cdrs::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+use cdrs::query_trace::QueryTrace;
+use cdrs::testing::{MockResponse, MockRows, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use cdrs::types::CBytes;
+use cdrs::types::rows::Row;
+use cdrs::types::value::Bytes;
+use uuid::Uuid;
+
+const TRACING_ID: &'static str = "a5a4b4e0-7b7a-11e7-9c4b-4b2b5c3c2d1e";
+
+fn connect(server: &MockServer) -> Session<PasswordAuthenticator, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    return CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+}
+
+fn bytes<T: Into<Bytes>>(value: T) -> CBytes {
+    return CBytes::new(value.into().into_plain());
+}
+
+fn ip(last: u8) -> IpAddr {
+    return IpAddr::V4(Ipv4Addr::new(127, 0, 0, last));
+}
+
+fn session_rows(duration: Option<i32>) -> MockRows {
+    let parameters_type = ColTypeOption {
+        id: ColType::Map,
+        value: Some(ColTypeOptionValue::CMap((
+            Box::new(ColTypeOption { id: ColType::Varchar, value: None }),
+            Box::new(ColTypeOption { id: ColType::Varchar, value: None })
+        )))
+    };
+    let mut parameters = HashMap::new();
+    parameters.insert("query", "SELECT * FROM ks.t");
+
+    return MockRows::new("system_traces", "sessions")
+        .column("request", ColType::Varchar)
+        .column("coordinator", ColType::Inet)
+        .column("duration", ColType::Int)
+        .column("started_at", ColType::Timestamp)
+        .column_with_type("parameters", parameters_type)
+        .row(vec![
+            bytes("Execute CQL3 query"),
+            bytes(ip(1)),
+            duration.map(bytes).unwrap_or(CBytes::new_null()),
+            bytes(1500000000000i64),
+            bytes(parameters)
+        ]);
+}
+
+fn event_rows(events: Vec<(u8, &str, i32, &str)>) -> MockRows {
+    let mut rows = MockRows::new("system_traces", "events")
+        .column("source", ColType::Inet)
+        .column("thread", ColType::Varchar)
+        .column("source_elapsed", ColType::Int)
+        .column("activity", ColType::Varchar);
+    for (source, thread, elapsed, activity) in events {
+        rows = rows.row(vec![bytes(ip(source)), bytes(thread), bytes(elapsed), bytes(activity)]);
+    }
+    return rows;
+}
+
+fn rows(rows: MockRows) -> Vec<Row> {
+    return Row::from_frame_body(rows.into_body());
+}
+
+fn tracing_id() -> Uuid {
+    return Uuid::parse_str(TRACING_ID).unwrap();
+}
+
+#[test]
+fn test_query_trace_from_rows() {
+    let session = rows(session_rows(Some(1200))).remove(0);
+    // events are kept in the order they are returned, i.e. ordered by their ids
+    let events = rows(event_rows(vec![(1, "Native-Transport-Requests-1", 100, "Parsing query"),
+                                      (2, "ReadStage-2", 20, "Executing single-partition query"),
+                                      (1, "Native-Transport-Requests-1", 900, "Read 1 live rows")]));
+
+    let trace = QueryTrace::from_rows(tracing_id(), &session, &events).unwrap().unwrap();
+    assert_eq!(trace.tracing_id, tracing_id());
+    assert_eq!(trace.request, "Execute CQL3 query".to_string());
+    assert_eq!(trace.coordinator, ip(1));
+    assert_eq!(trace.duration, 1200);
+    assert_eq!(trace.started_at, 1500000000000);
+    assert_eq!(trace.parameters.get("query"), Some(&"SELECT * FROM ks.t".to_string()));
+    let activities: Vec<&str> = trace.events.iter().map(|event| event.activity.as_str()).collect();
+    assert_eq!(activities, vec!["Parsing query", "Executing single-partition query", "Read 1 live rows"]);
+    assert_eq!(trace.events[1].source, ip(2));
+    assert_eq!(trace.events[1].thread, "ReadStage-2".to_string());
+    assert_eq!(trace.events[1].source_elapsed, 20);
+}
+
+#[test]
+fn test_query_trace_incomplete() {
+    let session = rows(session_rows(None)).remove(0);
+    let events = rows(event_rows(vec![(1, "Native-Transport-Requests-1", 100, "Parsing query")]));
+
+    assert!(QueryTrace::from_rows(tracing_id(), &session, &events).unwrap().is_none());
+}
+
+#[test]
+fn test_query_trace_missing_columns() {
+    let session = rows(session_rows(Some(1200))).remove(0);
+    let events = rows(MockRows::new("system_traces", "events")
+        .column("source", ColType::Inet)
+        .column("activity", ColType::Varchar)
+        .row(vec![bytes(ip(1)), bytes("Parsing query")]));
+    assert!(QueryTrace::from_rows(tracing_id(), &session, &events).is_err());
+
+    // a complete session without `request` column
+    let session = rows(MockRows::new("system_traces", "sessions")
+        .column("duration", ColType::Int)
+        .row(vec![bytes(1200)])).remove(0);
+    assert!(QueryTrace::from_rows(tracing_id(), &session, &vec![]).is_err());
+
+    // a session without `duration` column is not complete
+    let session = rows(MockRows::new("system_traces", "sessions")
+        .column("request", ColType::Varchar)
+        .row(vec![bytes("Execute CQL3 query")])).remove(0);
+    assert!(QueryTrace::from_rows(tracing_id(), &session, &vec![]).unwrap().is_none());
+}
+
+#[test]
+fn test_get_query_trace_retries_until_complete() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query_contains("FROM system_traces.sessions"))
+        .times(1)
+        .then(MockResponse::rows(MockRows::new("system_traces", "sessions").column("duration", ColType::Int)));
+    server.when(RequestMatcher::query_contains("FROM system_traces.sessions"))
+        .times(1)
+        .then(MockResponse::rows(session_rows(None)));
+    server.when(RequestMatcher::query_contains("FROM system_traces.sessions"))
+        .then(MockResponse::rows(session_rows(Some(1200))));
+    server.when(RequestMatcher::query_contains("FROM system_traces.events"))
+        .then(MockResponse::rows(event_rows(vec![(1, "Native-Transport-Requests-1", 100, "Parsing query")])));
+
+    let mut session = connect(&server);
+    let trace = session.get_query_trace(tracing_id()).unwrap();
+    assert_eq!(trace.duration, 1200);
+    assert_eq!(trace.events.len(), 1);
+
+    let session_queries = server.requests()
+        .iter()
+        .filter(|request| request.body.query_string().map(|query| query.contains("system_traces.sessions"))
+            .unwrap_or(false))
+        .count();
+    assert_eq!(session_queries, 3);
+    assert!(server.requests()[1].body.query_string().unwrap().contains(TRACING_ID));
+}
+
+#[test]
+fn test_get_query_trace_gives_up() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query_contains("FROM system_traces.sessions"))
+        .then(MockResponse::rows(session_rows(None)));
+
+    let mut session = connect(&server);
+    assert!(session.get_query_trace(tracing_id()).is_err());
+}
@@ -0,0 +1,55 @@
+extern crate cdrs;
+
+use std::time::Duration;
+
+use cdrs::reconnection_policy::{ConstantReconnectionPolicy, ExponentialReconnectionPolicy,
+                                ReconnectionPolicy};
+
+#[test]
+fn test_constant_reconnection_policy() {
+    let policy = ConstantReconnectionPolicy::new(Duration::from_millis(100));
+    let mut schedule = policy.new_schedule();
+    for _ in 0..5 {
+        assert_eq!(schedule.next_delay(), Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_exponential_reconnection_policy() {
+    let mut policy = ExponentialReconnectionPolicy::new(Duration::from_millis(100),
+                                                        Duration::from_secs(1));
+    policy.jitter(0.0);
+    let mut schedule = policy.new_schedule();
+    let delays: Vec<Duration> = (0..6).map(|_| schedule.next_delay()).collect();
+    let expected: Vec<Duration> = vec![100, 200, 400, 800, 1000, 1000].into_iter()
+        .map(Duration::from_millis)
+        .collect();
+    assert_eq!(delays, expected);
+
+    // a new schedule starts from base delay
+    assert_eq!(policy.new_schedule().next_delay(), Duration::from_millis(100));
+
+    // doesn't overflow after many attempts
+    let mut schedule = policy.new_schedule();
+    for _ in 0..100 {
+        assert!(schedule.next_delay() <= Duration::from_secs(1));
+    }
+}
+
+#[test]
+fn test_exponential_reconnection_policy_jitter() {
+    let policy = ExponentialReconnectionPolicy::new(Duration::from_millis(1000),
+                                                    Duration::from_secs(60));
+    for _ in 0..20 {
+        let delay = policy.new_schedule().next_delay();
+        assert!(delay >= Duration::from_millis(850));
+        assert!(delay <= Duration::from_millis(1150));
+    }
+
+    // jittered delays don't exceed max delay
+    let policy = ExponentialReconnectionPolicy::new(Duration::from_millis(1000),
+                                                    Duration::from_millis(1000));
+    for _ in 0..20 {
+        assert!(policy.new_schedule().next_delay() <= Duration::from_millis(1000));
+    }
+}
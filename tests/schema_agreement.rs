@@ -0,0 +1,180 @@
+extern crate cdrs;
+extern crate r2d2;
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, QueryBuilder, Session};
+use cdrs::compression::Compression;
+use cdrs::connection_manager::ConnectionManager;
+use cdrs::frame::Opcode;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::frame::frame_result::{BodyResResultSchemaChange, ChangeSchemeOptions, ChangeType, ColType,
+                                Target};
+use cdrs::node_state::{NodeState, NodeStates};
+use cdrs::testing::{MockResponse, MockRows, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use cdrs::types::CBytes;
+use cdrs::types::value::Bytes;
+use r2d2::ManageConnection;
+
+const CREATE_TABLE: &'static str = "CREATE TABLE ks.users (id int PRIMARY KEY)";
+const LOCAL_VERSION_QUERY: &'static str = "SELECT schema_version FROM system.local WHERE key = 'local'";
+const PEERS_VERSION_QUERY: &'static str = "SELECT peer, rpc_address, schema_version FROM system.peers";
+
+fn connect(server: &MockServer) -> Session<PasswordAuthenticator, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    return CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+}
+
+fn version(id: u8) -> CBytes {
+    return CBytes::new(vec![id; 16]);
+}
+
+fn versions(table: &str, versions: Vec<CBytes>) -> MockResponse {
+    let mut rows = MockRows::new("system", table).column("schema_version", ColType::Uuid);
+    for version in versions {
+        rows = rows.row(vec![version]);
+    }
+    return MockResponse::rows(rows);
+}
+
+fn mock_schema_change(server: &MockServer) {
+    server.when(RequestMatcher::query(CREATE_TABLE))
+        .then(MockResponse::schema_change(BodyResResultSchemaChange {
+            change_type: ChangeType::Created,
+            target: Target::Table,
+            options: ChangeSchemeOptions::Table(("ks".to_string(), "users".to_string()))
+        }));
+    server.when(RequestMatcher::query(LOCAL_VERSION_QUERY)).then(versions("local", vec![version(1)]));
+}
+
+fn version_checks(server: &MockServer) -> usize {
+    return server.requests().iter()
+        .filter(|request| match request.body {
+            RequestBody::Query(ref query) => query.query.as_str() == PEERS_VERSION_QUERY,
+            _ => false
+        })
+        .count();
+}
+
+#[test]
+fn test_schema_agreement_is_not_awaited_by_default() {
+    let server = MockServer::start().unwrap();
+    mock_schema_change(&server);
+
+    let mut session = connect(&server);
+    let (_, info) = session.query_with_info(QueryBuilder::new(CREATE_TABLE).finalize(), false, false).unwrap();
+    assert_eq!(info.schema_in_agreement, None);
+    assert_eq!(version_checks(&server), 0);
+}
+
+#[test]
+fn test_schema_agreement_is_awaited_after_schema_change() {
+    let server = MockServer::start().unwrap();
+    mock_schema_change(&server);
+    // the peer with unknown version is ignored, the other one catches up on the third check
+    server.when(RequestMatcher::query(PEERS_VERSION_QUERY))
+        .times(2)
+        .then(versions("peers", vec![version(2), CBytes::new_null()]));
+    server.when(RequestMatcher::query(PEERS_VERSION_QUERY))
+        .then(versions("peers", vec![version(1), CBytes::new_null()]));
+
+    let mut session = connect(&server);
+    session.schema_agreement_timeout(Some(Duration::from_secs(5)));
+
+    // other results don't wait for agreement
+    let (_, info) = session.query_with_info(QueryBuilder::new("SELECT 1").finalize(), false, false).unwrap();
+    assert_eq!(info.schema_in_agreement, None);
+    assert_eq!(version_checks(&server), 0);
+
+    let (_, info) = session.query_with_info(QueryBuilder::new(CREATE_TABLE).finalize(), false, false).unwrap();
+    assert_eq!(info.schema_in_agreement, Some(true));
+    assert_eq!(version_checks(&server), 3);
+}
+
+#[test]
+fn test_schema_agreement_doesnt_parse_other_results() {
+    let server = MockServer::start().unwrap();
+    mock_schema_change(&server);
+    // rows result which metadata is truncated, so its body would fail to parse
+    server.when(RequestMatcher::query("SELECT * FROM ks.users"))
+        .then(MockResponse::new(Opcode::Result, vec![0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 1]));
+
+    let mut session = connect(&server);
+    session.schema_agreement_timeout(Some(Duration::from_secs(5)));
+    let query = QueryBuilder::new("SELECT * FROM ks.users").finalize();
+    let (_, info) = session.query_with_info(query, false, false).unwrap();
+    assert_eq!(info.schema_in_agreement, None);
+    assert_eq!(version_checks(&server), 0);
+}
+
+#[test]
+fn test_schema_agreement_timeout() {
+    let server = MockServer::start().unwrap();
+    mock_schema_change(&server);
+    server.when(RequestMatcher::query(PEERS_VERSION_QUERY)).then(versions("peers", vec![version(2)]));
+
+    let mut session = connect(&server);
+    session.schema_agreement_timeout(Some(Duration::from_millis(300)));
+    assert_eq!(session.check_schema_agreement().unwrap(), false);
+
+    let started = Instant::now();
+    let (_, info) = session.query_with_info(QueryBuilder::new(CREATE_TABLE).finalize(), false, false).unwrap();
+    assert_eq!(info.schema_in_agreement, Some(false));
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert!(version_checks(&server) >= 2);
+}
+
+#[test]
+fn test_schema_agreement_ignores_down_peers() {
+    let server = MockServer::start().unwrap();
+    mock_schema_change(&server);
+    let ip = |last: u8| IpAddr::V4(Ipv4Addr::new(127, 0, 0, last));
+    let address = |last: u8| CBytes::new(Bytes::from(ip(last)).into_plain());
+    let peers = MockRows::new("system", "peers")
+        .column("peer", ColType::Inet)
+        .column("rpc_address", ColType::Inet)
+        .column("schema_version", ColType::Uuid)
+        .row(vec![address(2), address(12), version(1)])
+        .row(vec![address(3), CBytes::new_null(), version(2)])
+        .row(vec![address(4), address(14), version(2)]);
+    server.when(RequestMatcher::query(PEERS_VERSION_QUERY)).then(MockResponse::rows(peers));
+
+    let node_states = NodeStates::new();
+    let (peer, rpc_peer) = (NodeState::new(), NodeState::new());
+    // peers are known either by their broadcast or their rpc addresses
+    node_states.insert(ip(3), peer.clone());
+    node_states.insert(ip(14), rpc_peer.clone());
+    let mut session = connect(&server);
+    session.node_states(Some(node_states));
+    assert_eq!(session.check_schema_agreement().unwrap(), false);
+
+    peer.mark_down();
+    assert_eq!(session.check_schema_agreement().unwrap(), false);
+
+    rpc_peer.mark_down();
+    session.schema_agreement_timeout(Some(Duration::from_secs(5)));
+    let (_, info) = session.query_with_info(QueryBuilder::new(CREATE_TABLE).finalize(), false, false).unwrap();
+    assert_eq!(info.schema_in_agreement, Some(true));
+}
+
+#[test]
+fn test_connection_manager_schema_agreement_timeout() {
+    let server = MockServer::start().unwrap();
+    mock_schema_change(&server);
+    server.when(RequestMatcher::query(PEERS_VERSION_QUERY)).then(versions("peers", vec![version(1)]));
+
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let mut manager = ConnectionManager::new(transport, PasswordAuthenticator::new("user", "pass"),
+                                             Compression::None);
+    manager.schema_agreement_timeout(Some(Duration::from_secs(5)));
+    let mut connection = manager.connect().unwrap();
+
+    let (_, info) = connection.query_with_info(QueryBuilder::new(CREATE_TABLE).finalize(), false, false)
+        .unwrap();
+    assert_eq!(info.schema_in_agreement, Some(true));
+    assert_eq!(version_checks(&server), 1);
+}
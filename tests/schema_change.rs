@@ -0,0 +1,89 @@
+extern crate cdrs;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, QueryBuilder, Session};
+use cdrs::compression::Compression;
+use cdrs::frame::frame_event::ServerEvent;
+use cdrs::frame::frame_register::SimpleServerEvent;
+use cdrs::frame::frame_result::{BodyResResultSchemaChange, ChangeSchemeOptions, ChangeType, ColType,
+                                Target};
+use cdrs::schema_change::{SchemaChangeOutcome, SchemaElement};
+use cdrs::testing::{MockResponse, MockRows, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+
+fn connect(server: &MockServer) -> Session<PasswordAuthenticator, TransportTcp> {
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    return CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+}
+
+fn change(change_type: ChangeType, target: Target, options: ChangeSchemeOptions) -> BodyResResultSchemaChange {
+    return BodyResResultSchemaChange {
+        change_type: change_type,
+        target: target,
+        options: options
+    };
+}
+
+fn strings(values: Vec<&str>) -> Vec<String> {
+    return values.into_iter().map(|value| value.to_string()).collect();
+}
+
+#[test]
+fn test_schema_change_outcome_from_body() {
+    let outcome = SchemaChangeOutcome::from(change(ChangeType::Dropped, Target::Keyspace,
+                                                   ChangeSchemeOptions::Keyspace("ks".to_string())));
+    assert_eq!(outcome.change_type, ChangeType::Dropped);
+    assert_eq!(outcome.element, SchemaElement::Keyspace { keyspace: "ks".to_string() });
+    assert_eq!(outcome.keyspace(), "ks");
+
+    let table = ChangeSchemeOptions::Table(("ks".to_string(), "users".to_string()));
+    assert_eq!(SchemaChangeOutcome::from(change(ChangeType::Updated, Target::Table, table.clone())).element,
+               SchemaElement::Table { keyspace: "ks".to_string(), table: "users".to_string() });
+    assert_eq!(SchemaChangeOutcome::from(change(ChangeType::Created, Target::Type, table)).element,
+               SchemaElement::Type { keyspace: "ks".to_string(), name: "users".to_string() });
+
+    let function = ChangeSchemeOptions::Function(("ks".to_string(), "plus".to_string(), strings(vec!["int", "int"])));
+    assert_eq!(SchemaChangeOutcome::from(change(ChangeType::Created, Target::Function, function.clone())).element,
+               SchemaElement::Function {
+                   keyspace: "ks".to_string(),
+                   name: "plus".to_string(),
+                   argument_types: strings(vec!["int", "int"])
+               });
+    let outcome = SchemaChangeOutcome::from(change(ChangeType::Created, Target::Aggregate, function));
+    assert_eq!(outcome.element, SchemaElement::Aggregate {
+        keyspace: "ks".to_string(),
+        name: "plus".to_string(),
+        argument_types: strings(vec!["int", "int"])
+    });
+    assert_eq!(outcome.keyspace(), "ks");
+}
+
+#[test]
+fn test_session_ddl() {
+    let server = MockServer::start().unwrap();
+    let created = change(ChangeType::Created, Target::Table,
+                         ChangeSchemeOptions::Table(("ks".to_string(), "users".to_string())));
+    server.when(RequestMatcher::query("CREATE TABLE ks.users (id int PRIMARY KEY)"))
+        .then(MockResponse::schema_change(created.clone()));
+    server.when(RequestMatcher::query("CREATE TABLE IF NOT EXISTS ks.users (id int PRIMARY KEY)"))
+        .then(MockResponse::void());
+    server.when(RequestMatcher::query("SELECT id FROM ks.users"))
+        .then(MockResponse::rows(MockRows::new("ks", "users").column("id", ColType::Int)));
+
+    let mut session = connect(&server);
+    let outcome = session.ddl(QueryBuilder::new("CREATE TABLE ks.users (id int PRIMARY KEY)").finalize(), false, false)
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.change_type, ChangeType::Created);
+    assert_eq!(outcome.element, SchemaElement::Table { keyspace: "ks".to_string(), table: "users".to_string() });
+
+    let query = QueryBuilder::new("CREATE TABLE IF NOT EXISTS ks.users (id int PRIMARY KEY)").finalize();
+    assert_eq!(session.ddl(query, false, false).unwrap(), None);
+    assert!(session.ddl(QueryBuilder::new("SELECT id FROM ks.users").finalize(), false, false).is_err());
+
+    // the same change pushed as an event has the same outcome
+    let mut listener = connect(&server).listen_for(vec![SimpleServerEvent::SchemaChange]).unwrap();
+    assert_eq!(server.push_event(ServerEvent::SchemaChange(created)), 1);
+    assert_eq!(listener.next().unwrap().schema_change(), Some(outcome));
+}
@@ -0,0 +1,271 @@
+#![cfg(feature = "serde")]
+extern crate cdrs;
+extern crate serde_derive;
+
+use std::collections::HashMap;
+
+use cdrs::frame::frame_result::{ColSpec, ColType, ColTypeOption, ColTypeOptionValue, CUdt};
+use cdrs::serde_support::{from_rows, from_value, to_value, to_values};
+use cdrs::testing::MockRows;
+use cdrs::types::{CBytes, CString};
+use cdrs::types::cql_value::CqlValue;
+use cdrs::types::rows::Row;
+use cdrs::types::udt::UdtBuilder;
+use cdrs::types::value::Bytes;
+use cdrs::IntoBytes;
+use self::serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Address {
+    street: String,
+    zip: Option<i32>
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    Admin,
+    Guest
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Person {
+    id: String,
+    name: Option<String>,
+    age: u8,
+    role: Role,
+    home: Address,
+    tags: Vec<String>,
+    scores: HashMap<String, i64>
+}
+
+fn simple(id: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: id,
+        value: None
+    };
+}
+
+fn address_type() -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Udt,
+        value: Some(ColTypeOptionValue::UdtType(CUdt {
+            ks: CString::new("ks".to_string()),
+            udt_name: CString::new("address".to_string()),
+            descriptions: vec![(CString::new("street".to_string()), simple(ColType::Varchar)),
+                               (CString::new("zip".to_string()), simple(ColType::Int))]
+        }))
+    };
+}
+
+fn list_type(item_type: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::List,
+        value: Some(ColTypeOptionValue::CList(Box::new(simple(item_type))))
+    };
+}
+
+fn map_type(key_type: ColType, value_type: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Map,
+        value: Some(ColTypeOptionValue::CMap((Box::new(simple(key_type)), Box::new(simple(value_type)))))
+    };
+}
+
+fn bind_marker(name: &str, col_type: ColTypeOption) -> ColSpec {
+    return ColSpec {
+        ksname: None,
+        tablename: None,
+        name: CString::new(name.to_string()),
+        col_type: col_type
+    };
+}
+
+fn bytes<T: Into<Bytes>>(value: T) -> CBytes {
+    return CBytes::new(value.into().into_plain());
+}
+
+#[test]
+fn test_from_rows() {
+    let home = |zip: Option<i32>| {
+        let builder = UdtBuilder::from_col_type(&address_type()).unwrap().field("street", "Main St");
+        let builder = match zip {
+            Some(zip) => builder.field("zip", zip),
+            None => builder
+        };
+        return CBytes::new(builder.finalize().unwrap().into_plain());
+    };
+    let mut scores = HashMap::new();
+    scores.insert("math".to_string(), 5i64);
+
+    let body = MockRows::new("ks", "people")
+        .column("id", ColType::Uuid)
+        .column("name", ColType::Varchar)
+        .column("age", ColType::Tinyint)
+        .column("role", ColType::Varchar)
+        .column_with_type("home", address_type())
+        .column_with_type("tags", list_type(ColType::Varchar))
+        .column_with_type("scores", map_type(ColType::Varchar, ColType::Bigint))
+        .row(vec![
+            CBytes::new(vec![0x55; 16]),
+            bytes("john"),
+            bytes(30i8),
+            bytes("admin"),
+            home(Some(12345)),
+            bytes(vec!["a", "b"]),
+            bytes(scores.clone())
+        ])
+        .row(vec![
+            CBytes::new(vec![0x55; 16]),
+            CBytes::new_null(),
+            bytes(7i8),
+            bytes("guest"),
+            home(None),
+            bytes(Vec::<String>::new()),
+            bytes(HashMap::<String, i64>::new())
+        ])
+        .into_body();
+    let rows = Row::from_frame_body(body);
+
+    let people: Vec<Person> = from_rows(&rows).unwrap();
+    assert_eq!(people, vec![
+        Person {
+            id: "55555555-5555-5555-5555-555555555555".to_string(),
+            name: Some("john".to_string()),
+            age: 30,
+            role: Role::Admin,
+            home: Address { street: "Main St".to_string(), zip: Some(12345) },
+            tags: vec!["a".to_string(), "b".to_string()],
+            scores: scores
+        },
+        Person {
+            id: "55555555-5555-5555-5555-555555555555".to_string(),
+            name: None,
+            age: 7,
+            role: Role::Guest,
+            home: Address { street: "Main St".to_string(), zip: None },
+            tags: vec![],
+            scores: HashMap::new()
+        }
+    ]);
+
+    // a negative tinyint doesn't fit into `u8`
+    let body = MockRows::new("ks", "t").column("age", ColType::Tinyint).row(vec![bytes(-1i8)]).into_body();
+    #[derive(Deserialize)]
+    struct Age {
+        #[allow(dead_code)]
+        age: u8
+    }
+    assert!(from_rows::<Age>(&Row::from_frame_body(body)).is_err());
+}
+
+#[test]
+fn test_from_value() {
+    let value = CqlValue::Tuple(vec![CqlValue::Int(1), CqlValue::Text("x".to_string()), CqlValue::Null]);
+    let tuple: (i64, String, Option<f64>) = from_value(value).unwrap();
+    assert_eq!(tuple, (1, "x".to_string(), None));
+
+    let blob: Vec<u8> = from_value(CqlValue::Blob(vec![1, 2])).unwrap();
+    assert_eq!(blob, vec![1, 2]);
+
+    let map: Vec<(i32, bool)> = from_value::<HashMap<i32, bool>>(CqlValue::Map(vec![(CqlValue::Int(1),
+                                                                                     CqlValue::Boolean(true))]))
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(map, vec![(1, true)]);
+
+    assert!(from_value::<String>(CqlValue::Int(1)).is_err());
+}
+
+#[test]
+fn test_to_value() {
+    let address = Address { street: "Main St".to_string(), zip: None };
+    let expected = UdtBuilder::from_col_type(&address_type()).unwrap()
+        .field("street", "Main St")
+        .finalize()
+        .unwrap();
+    assert_eq!(to_value(&address, &address_type()).unwrap().body, expected.into_plain());
+
+    let value = to_value(&vec![1u8, 2], &list_type(ColType::Smallint)).unwrap();
+    assert_eq!(value.body, Bytes::from(vec![1i16, 2]).into_plain());
+
+    let uuid = to_value("55555555-5555-5555-5555-555555555555", &simple(ColType::Uuid)).unwrap();
+    assert_eq!(uuid.body, vec![0x55; 16]);
+
+    let role = to_value(&Role::Guest, &simple(ColType::Varchar)).unwrap();
+    assert_eq!(role.body, b"guest".to_vec());
+
+    let varint = to_value(&-129, &simple(ColType::Varint)).unwrap();
+    assert_eq!(varint.body, vec![0xff, 0x7f]);
+
+    let null = to_value(&None::<i32>, &simple(ColType::Int)).unwrap();
+    assert_eq!(null.into_cbytes(), vec![0xff, 0xff, 0xff, 0xff]);
+
+    // decimals are encoded in the same shape they're decoded
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Decimal {
+        unscaled: Vec<u8>,
+        scale: i32
+    }
+    let decimal = Decimal { unscaled: vec![0x30, 0x39], scale: 2 };
+    let bytes = to_value(&decimal, &simple(ColType::Decimal)).unwrap();
+    assert_eq!(bytes.body, vec![0, 0, 0, 2, 0x30, 0x39]);
+    let decoded = CqlValue::decode(&CBytes::new(bytes.body), &simple(ColType::Decimal)).unwrap();
+    assert_eq!(from_value::<Decimal>(decoded).unwrap(), decimal);
+    #[derive(Serialize)]
+    struct IntegerDecimal {
+        unscaled: i64,
+        scale: i32
+    }
+    let decimal = IntegerDecimal { unscaled: -129, scale: 0 };
+    assert_eq!(to_value(&decimal, &simple(ColType::Decimal)).unwrap().body, vec![0, 0, 0, 0, 0xff, 0x7f]);
+    let mut incomplete = HashMap::new();
+    incomplete.insert("scale", 1);
+    assert!(to_value(&incomplete, &simple(ColType::Decimal)).is_err());
+
+    // variants with data would lose their names
+    #[derive(Serialize)]
+    enum Contact {
+        Email(String),
+        Phone(i32, i32),
+        Address { street: String }
+    }
+    assert!(to_value(&Contact::Email("a@b.c".to_string()), &simple(ColType::Varchar)).is_err());
+    assert!(to_value(&Contact::Phone(1, 2), &simple(ColType::Varchar)).is_err());
+    assert!(to_value(&Contact::Address { street: "Main St".to_string() }, &address_type()).is_err());
+
+    assert!(to_value(&300, &simple(ColType::Tinyint)).is_err());
+    assert!(to_value("text", &simple(ColType::Int)).is_err());
+    assert!(to_value("not a uuid", &simple(ColType::Uuid)).is_err());
+}
+
+#[test]
+fn test_to_values() {
+    #[derive(Serialize)]
+    struct NewPerson {
+        id: i32,
+        home: Address,
+        unused: bool
+    }
+    let person = NewPerson {
+        id: 1,
+        home: Address { street: "Main St".to_string(), zip: Some(1) },
+        unused: true
+    };
+    let bind_markers = vec![bind_marker("id", simple(ColType::Int)),
+                            bind_marker("home", address_type()),
+                            bind_marker("name", simple(ColType::Varchar))];
+
+    let values = to_values(&person, &bind_markers).unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[0].body, Bytes::from(1i32).into_plain());
+    assert_eq!(values[1].body, to_value(&person.home, &address_type()).unwrap().body);
+    // markers which don't match any field are not set
+    assert_eq!(values[2].into_cbytes(), vec![0xff, 0xff, 0xff, 0xfe]);
+
+    let values = to_values(&(2, "x"), &[bind_marker("id", simple(ColType::Int)),
+                                        bind_marker("name", simple(ColType::Varchar))]).unwrap();
+    assert_eq!(values[1].body, b"x".to_vec());
+    assert!(to_values(&(2,), &bind_markers).is_err());
+}
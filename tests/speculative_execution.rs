@@ -0,0 +1,23 @@
+extern crate cdrs;
+
+use std::time::Duration;
+
+use cdrs::speculative_execution::{ConstantSpeculativeExecutionPolicy, NoSpeculativeExecutionPolicy,
+                                  SpeculativeExecutionPolicy};
+
+#[test]
+fn test_no_speculative_execution_policy() {
+    assert_eq!(NoSpeculativeExecutionPolicy.new_plan().next_execution(), None);
+}
+
+#[test]
+fn test_constant_speculative_execution_policy() {
+    let policy = ConstantSpeculativeExecutionPolicy::new(Duration::from_millis(100), 2);
+    let mut plan = policy.new_plan();
+    assert_eq!(plan.next_execution(), Some(Duration::from_millis(100)));
+    assert_eq!(plan.next_execution(), Some(Duration::from_millis(100)));
+    assert_eq!(plan.next_execution(), None);
+
+    // each request gets its own plan
+    assert_eq!(policy.new_plan().next_execution(), Some(Duration::from_millis(100)));
+}
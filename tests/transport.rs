@@ -0,0 +1,411 @@
+extern crate cdrs;
+extern crate r2d2;
+extern crate socket2;
+#[cfg(feature = "ssl")]
+extern crate openssl;
+#[cfg(feature = "tls-rustls")]
+extern crate rustls;
+
+use std::net::TcpListener;
+use std::time::Duration;
+
+use cdrs::transport::{CDRSTransport, TransportTcp};
+use cdrs::transport_config::TransportConfig;
+use socket2::Socket;
+
+#[test]
+fn test_transport_with_config() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut config = TransportConfig::new();
+    config
+        .nodelay(true)
+        .keepalive(Duration::from_secs(30))
+        .send_buffer_size(64 * 1024)
+        .recv_buffer_size(64 * 1024)
+        .connect_timeout(Duration::from_secs(1));
+
+    // localhost may be resolved into several addresses, e.g. ::1 and 127.0.0.1,
+    // while the listener is bound to 127.0.0.1 only
+    let transport = TransportTcp::with_config(format!("localhost:{}", addr.port()).as_str(), config)
+        .unwrap();
+    assert_eq!(transport.peer_addr().unwrap(), addr);
+
+    let clone = transport.try_clone().unwrap();
+    assert_eq!(clone.peer_addr().unwrap(), addr);
+}
+
+#[test]
+fn test_transport_config_options() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut config = TransportConfig::new();
+    config
+        .nodelay(true)
+        .keepalive(Duration::from_secs(30))
+        .send_buffer_size(64 * 1024)
+        .recv_buffer_size(64 * 1024);
+    let socket = Socket::from(config.connect(addr).unwrap());
+    assert!(socket.nodelay().unwrap());
+    assert_eq!(socket.keepalive().unwrap(), Some(Duration::from_secs(30)));
+    // a kernel may round buffer sizes up, e.g. Linux doubles them
+    assert!(socket.send_buffer_size().unwrap() >= 64 * 1024);
+    assert!(socket.recv_buffer_size().unwrap() >= 64 * 1024);
+
+    let mut config = TransportConfig::new();
+    config.nodelay(false);
+    let socket = Socket::from(config.connect_addr(&addr).unwrap());
+    assert!(!socket.nodelay().unwrap());
+    assert_eq!(socket.keepalive().unwrap(), None);
+
+    // defaults
+    let socket = Socket::from(TransportConfig::default().connect(addr).unwrap());
+    assert!(socket.nodelay().unwrap());
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_transport_alias() {
+    use cdrs::transport::Transport;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let transport: TransportTcp = Transport::new(addr.to_string().as_str()).unwrap();
+    assert_eq!(transport.peer_addr().unwrap(), addr);
+}
+
+#[test]
+fn test_transport_with_local_addr() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let mut config = TransportConfig::new();
+    config.local_addr("127.0.0.1:0".parse().unwrap());
+
+    let transport = TransportTcp::with_config(addr.to_string().as_str(), config).unwrap();
+    let (_, peer) = listener.accept().unwrap();
+    assert_eq!(peer.ip(), "127.0.0.1".parse::<std::net::IpAddr>().unwrap());
+    assert_eq!(transport.peer_addr().unwrap(), addr);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_transport_unix() {
+    use std::env;
+    use std::fs;
+    use std::os::unix::net::UnixListener;
+    use cdrs::transport_unix::TransportUnix;
+
+    let path = env::temp_dir().join(format!("cdrs-transport-test-{}.sock", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let _listener = UnixListener::bind(&path).unwrap();
+
+    let transport = TransportUnix::new(&path).unwrap();
+    assert_eq!(transport.path(), path.as_path());
+    assert!(transport.peer_addr().is_err());
+
+    let clone = transport.try_clone().unwrap();
+    assert_eq!(clone.path(), path.as_path());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_host_from_addr() {
+    use cdrs::tls_config::host_from_addr;
+
+    assert_eq!(host_from_addr("cassandra.local:9042"), "cassandra.local");
+    assert_eq!(host_from_addr("127.0.0.1:9042"), "127.0.0.1");
+    assert_eq!(host_from_addr("[::1]:9042"), "::1");
+    assert_eq!(host_from_addr("cassandra.local"), "cassandra.local");
+}
+
+#[cfg(feature = "ssl")]
+#[test]
+fn test_transport_tls_handshake_failure() {
+    use std::thread;
+    use cdrs::tls_config::TlsConfig;
+    use cdrs::transport_ssl::TransportTls;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        // accept and drop the connection in the middle of a handshake
+        let _ = listener.accept().unwrap();
+    });
+
+    let mut tls_config = TlsConfig::new();
+    tls_config.verify_hostname(false);
+    let transport = TransportTls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &tls_config, TransportConfig::default());
+    assert!(transport.is_err());
+    server.join().unwrap();
+}
+
+#[cfg(feature = "tls-rustls")]
+#[test]
+fn test_transport_rustls_handshake_failure() {
+    use std::thread;
+    use cdrs::tls_config::TlsConfig;
+    use cdrs::transport_rustls::TransportRustls;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let _ = listener.accept().unwrap();
+    });
+
+    let transport = TransportRustls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &TlsConfig::new(), TransportConfig::default());
+    assert!(transport.is_err());
+    server.join().unwrap();
+}
+
+/// Returns path to a test certificate or key. The certificates are signed by `ca.pem`,
+/// server's one is issued for `localhost` and `127.0.0.1`.
+#[cfg(any(feature = "ssl", feature = "tls-rustls"))]
+fn cert_path(name: &str) -> std::path::PathBuf {
+    return std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("certs").join(name);
+}
+
+/// Starts TLS server which accepts a single connection and writes `ok` to it
+/// once the handshake is done. If `verify_client` is set clients have to present
+/// a certificate signed by the test CA.
+#[cfg(feature = "ssl")]
+fn start_openssl_server(verify_client: bool)
+    -> (std::net::SocketAddr, std::thread::JoinHandle<Result<(), String>>) {
+    use std::io::Write;
+    use std::thread;
+    use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod, SslVerifyMode};
+
+    let mut acceptor = SslAcceptor::mozilla_intermediate(SslMethod::tls()).unwrap();
+    acceptor.set_private_key_file(cert_path("server.key"), SslFiletype::PEM).unwrap();
+    acceptor.set_certificate_chain_file(cert_path("server.pem")).unwrap();
+    if verify_client {
+        acceptor.set_ca_file(cert_path("ca.pem")).unwrap();
+        acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+    }
+    let acceptor = acceptor.build();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (socket, _) = listener.accept().unwrap();
+        let mut stream = try!(acceptor.accept(socket).map_err(|err| err.to_string()));
+        return stream.write_all(b"ok").map_err(|err| err.to_string());
+    });
+
+    return (addr, server);
+}
+
+#[cfg(feature = "ssl")]
+#[test]
+fn test_transport_tls_ca_file() {
+    use std::io::Read;
+    use cdrs::tls_config::TlsConfig;
+    use cdrs::transport_ssl::TransportTls;
+
+    let (addr, server) = start_openssl_server(false);
+    let mut tls_config = TlsConfig::new();
+    tls_config.ca_file(cert_path("ca.pem"));
+    let mut transport = TransportTls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &tls_config, TransportConfig::default()).unwrap();
+    assert_eq!(transport.domain(), "localhost");
+
+    let mut buf = [0; 2];
+    transport.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ok");
+    server.join().unwrap().unwrap();
+
+    // the test CA isn't trusted by default
+    let (addr, server) = start_openssl_server(false);
+    assert!(TransportTls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &TlsConfig::new(), TransportConfig::default()).is_err());
+    assert!(server.join().unwrap().is_err());
+
+    assert!(TransportTls::with_tls_config("localhost:9042", TlsConfig::new().ca_file(cert_path("missing.pem")),
+        TransportConfig::default()).is_err());
+}
+
+#[cfg(feature = "ssl")]
+#[test]
+fn test_transport_tls_client_cert() {
+    use std::io::Read;
+    use cdrs::tls_config::TlsConfig;
+    use cdrs::transport_ssl::TransportTls;
+
+    let (addr, server) = start_openssl_server(true);
+    let mut tls_config = TlsConfig::new();
+    tls_config
+        .ca_file(cert_path("ca.pem"))
+        .client_cert(cert_path("client.pem"), cert_path("client.key"));
+    let mut transport = TransportTls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &tls_config, TransportConfig::default()).unwrap();
+    let mut buf = [0; 2];
+    transport.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ok");
+    server.join().unwrap().unwrap();
+
+    // with TLS 1.3 a client may finish the handshake before the server rejects it
+    let (addr, server) = start_openssl_server(true);
+    let mut tls_config = TlsConfig::new();
+    tls_config.ca_file(cert_path("ca.pem"));
+    if let Ok(mut transport) = TransportTls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &tls_config, TransportConfig::default()) {
+        assert!(transport.read_exact(&mut buf).is_err());
+    }
+    assert!(server.join().unwrap().is_err());
+}
+
+#[cfg(feature = "tls-rustls")]
+fn start_rustls_server(verify_client: bool)
+    -> (std::net::SocketAddr, std::thread::JoinHandle<Result<(), String>>) {
+    use std::fs::File;
+    use std::io::{BufReader, Write};
+    use std::sync::Arc;
+    use std::thread;
+    use rustls::{AllowAnyAuthenticatedClient, NoClientAuth, RootCertStore, ServerConfig, ServerSession,
+                 Session};
+    use rustls::internal::pemfile;
+
+    let client_verifier = if verify_client {
+        let mut roots = RootCertStore::empty();
+        roots.add_pem_file(&mut BufReader::new(File::open(cert_path("ca.pem")).unwrap())).unwrap();
+        AllowAnyAuthenticatedClient::new(roots)
+    } else {
+        NoClientAuth::new()
+    };
+    let mut server_config = ServerConfig::new(client_verifier);
+    let certs = pemfile::certs(&mut BufReader::new(File::open(cert_path("server.pem")).unwrap())).unwrap();
+    let key = pemfile::pkcs8_private_keys(&mut BufReader::new(File::open(cert_path("server.key")).unwrap()))
+        .unwrap()
+        .remove(0);
+    server_config.set_single_cert(certs, key).unwrap();
+    let server_config = Arc::new(server_config);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (mut socket, _) = listener.accept().unwrap();
+        let mut session = ServerSession::new(&server_config);
+        while session.is_handshaking() {
+            try!(session.complete_io(&mut socket).map_err(|err| err.to_string()));
+        }
+        try!(session.write_all(b"ok").map_err(|err| err.to_string()));
+        return session.complete_io(&mut socket).map(|_| ()).map_err(|err| err.to_string());
+    });
+
+    return (addr, server);
+}
+
+#[cfg(feature = "tls-rustls")]
+#[test]
+fn test_transport_rustls_ca_file() {
+    use std::io::Read;
+    use cdrs::tls_config::TlsConfig;
+    use cdrs::transport_rustls::TransportRustls;
+
+    let (addr, server) = start_rustls_server(false);
+    let mut tls_config = TlsConfig::new();
+    tls_config.ca_file(cert_path("ca.pem"));
+    let mut transport = TransportRustls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &tls_config, TransportConfig::default()).unwrap();
+    let mut buf = [0; 2];
+    transport.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ok");
+    server.join().unwrap().unwrap();
+
+    // Mozilla's root certificates don't include the test CA
+    let (addr, server) = start_rustls_server(false);
+    assert!(TransportRustls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &TlsConfig::new(), TransportConfig::default()).is_err());
+    assert!(server.join().unwrap().is_err());
+
+    // a file without certificates
+    assert!(TransportRustls::with_tls_config("localhost:9042", TlsConfig::new().ca_file(cert_path("server.key")),
+        TransportConfig::default()).is_err());
+}
+
+#[cfg(feature = "tls-rustls")]
+#[test]
+fn test_transport_rustls_client_cert() {
+    use std::io::Read;
+    use cdrs::tls_config::TlsConfig;
+    use cdrs::transport_rustls::TransportRustls;
+
+    let (addr, server) = start_rustls_server(true);
+    let mut tls_config = TlsConfig::new();
+    tls_config
+        .ca_file(cert_path("ca.pem"))
+        .client_cert(cert_path("client.pem"), cert_path("client.key"));
+    let mut transport = TransportRustls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &tls_config, TransportConfig::default()).unwrap();
+    let mut buf = [0; 2];
+    transport.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ok");
+    server.join().unwrap().unwrap();
+
+    let (addr, server) = start_rustls_server(true);
+    let mut tls_config = TlsConfig::new();
+    tls_config.ca_file(cert_path("ca.pem"));
+    if let Ok(mut transport) = TransportRustls::with_tls_config(format!("localhost:{}", addr.port()).as_str(),
+        &tls_config, TransportConfig::default()) {
+        assert!(transport.read_exact(&mut buf).is_err());
+    }
+    assert!(server.join().unwrap().is_err());
+}
+
+#[test]
+fn test_transport_loopback_pair() {
+    use std::io::{ErrorKind, Read, Write};
+    use cdrs::transport_loopback::TransportLoopback;
+
+    let (mut client, mut server) = TransportLoopback::pair();
+    client.write_all(&[1, 2, 3]).unwrap();
+
+    let mut buf = [0; 3];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1, 2, 3]);
+    assert!(client.try_clone().is_err());
+
+    server.set_read_timeout(Some(Duration::from_millis(10))).unwrap();
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    drop(client);
+    assert_eq!(server.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn test_transport_loopback_connection_manager() {
+    use std::io::{Read, Write};
+    use std::thread;
+    use cdrs::authenticators::PasswordAuthenticator;
+    use cdrs::compression::Compression;
+    use cdrs::connection_manager::ConnectionManager;
+    use r2d2::ManageConnection;
+    use cdrs::transport_loopback::LoopbackListener;
+
+    let listener = LoopbackListener::new();
+    let transport = listener.connect().unwrap();
+    let server_listener = listener.clone();
+    let server = thread::spawn(move || {
+        // the first connection is a prototype which is cloned by the manager
+        let _prototype = server_listener.accept().unwrap();
+        let mut socket = server_listener.accept().unwrap();
+
+        let mut header = [0; 9];
+        socket.read_exact(&mut header).unwrap();
+        let mut body = vec![0; header[8] as usize];
+        socket.read_exact(&mut body).unwrap();
+        // READY
+        socket.write_all(&[0x84, 0, header[2], header[3], 0x02, 0, 0, 0, 0]).unwrap();
+    });
+
+    let manager = ConnectionManager::new(transport,
+        PasswordAuthenticator::new("user", "pass"), Compression::None);
+    assert!(manager.connect().is_ok());
+    server.join().unwrap();
+}
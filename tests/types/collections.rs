@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, QueryBuilder};
+use cdrs::compression::Compression;
+use cdrs::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue};
+use cdrs::testing::{MockResponse, MockRows, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use cdrs::types::{AsRust, CBytes, IntoRustByName};
+use cdrs::types::data_serialization_types::{decode_map, decode_set};
+use cdrs::types::list::List;
+use cdrs::types::map::Map;
+use cdrs::types::set::Set;
+use cdrs::types::value::Bytes;
+
+fn simple(id: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: id,
+        value: None
+    };
+}
+
+fn set_type(item_type: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Set,
+        value: Some(ColTypeOptionValue::CSet(Box::new(simple(item_type))))
+    };
+}
+
+fn map_type(key_type: ColType, value_type: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Map,
+        value: Some(ColTypeOptionValue::CMap((Box::new(simple(key_type)), Box::new(simple(value_type)))))
+    };
+}
+
+fn set(items: Bytes, item_type: ColType) -> Set {
+    return Set::new(decode_set(items.into_plain()).unwrap(), set_type(item_type));
+}
+
+fn map(entries: Bytes, key_type: ColType, value_type: ColType) -> Map {
+    return Map::new(decode_map(entries.into_plain()).unwrap(), map_type(key_type, value_type));
+}
+
+#[test]
+fn test_set_as_rust() {
+    let tags: HashSet<String> = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+    let decoded: HashSet<String> = set(Bytes::from(tags.clone()), ColType::Varchar).as_rust().unwrap();
+    assert_eq!(decoded, tags);
+
+    let numbers: BTreeSet<i32> = vec![3, 1, 2].into_iter().collect();
+    let bytes = Bytes::from(numbers.clone());
+    // sets are serialized in the same way as lists
+    assert_eq!(bytes, Bytes::from(vec![1, 2, 3]));
+    let decoded: BTreeSet<i32> = set(bytes.clone(), ColType::Int).as_rust().unwrap();
+    assert_eq!(decoded, numbers);
+    let decoded: Vec<i32> = set(bytes.clone(), ColType::Int).as_rust().unwrap();
+    assert_eq!(decoded, vec![1, 2, 3]);
+
+    let mismatch: ::cdrs::error::Result<HashSet<String>> = set(bytes, ColType::Int).as_rust();
+    assert!(mismatch.is_err());
+}
+
+#[test]
+fn test_map_with_non_string_keys() {
+    let mut scores: HashMap<i32, String> = HashMap::new();
+    scores.insert(1, "a".to_string());
+    scores.insert(2, "b".to_string());
+    let decoded: HashMap<i32, String> = map(Bytes::from(scores.clone()), ColType::Int, ColType::Varchar)
+        .as_rust()
+        .unwrap();
+    assert_eq!(decoded, scores);
+
+    let id = Uuid::parse_str("5d2fbb4c-0b9e-4b1a-9b3a-3f1e2c7d8a90").unwrap();
+    let mut owners: BTreeMap<Uuid, bool> = BTreeMap::new();
+    owners.insert(id, true);
+    let decoded: BTreeMap<Uuid, bool> = map(Bytes::from(owners.clone()), ColType::Uuid, ColType::Boolean)
+        .as_rust()
+        .unwrap();
+    assert_eq!(decoded, owners);
+
+    // string keys keep working
+    let mut options: BTreeMap<&str, &str> = BTreeMap::new();
+    options.insert("class", "SimpleStrategy");
+    let decoded: HashMap<String, String> = map(Bytes::from(options), ColType::Varchar, ColType::Varchar)
+        .as_rust()
+        .unwrap();
+    assert_eq!(decoded.get("class"), Some(&"SimpleStrategy".to_string()));
+
+    let mismatch: ::cdrs::error::Result<HashMap<String, String>> =
+        map(Bytes::from(scores), ColType::Int, ColType::Varchar).as_rust();
+    assert!(mismatch.is_err());
+}
+
+#[test]
+fn test_nested_collections() {
+    let mut groups: HashMap<i32, HashSet<i16>> = HashMap::new();
+    groups.insert(1, vec![10, 20].into_iter().collect());
+    let nested_type = ColTypeOption {
+        id: ColType::Map,
+        value: Some(ColTypeOptionValue::CMap((Box::new(simple(ColType::Int)), Box::new(set_type(ColType::Smallint)))))
+    };
+    let decoded = Map::new(decode_map(Bytes::from(groups).into_plain()).unwrap(), nested_type);
+    let decoded: HashMap<i32, Set> = decoded.as_rust().unwrap();
+    let group: BTreeSet<i16> = decoded.get(&1).unwrap().as_rust().unwrap();
+    assert_eq!(group, vec![10, 20].into_iter().collect());
+}
+
+#[test]
+fn test_row_set() {
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::query("SELECT tags FROM ks.posts"))
+        .then(MockResponse::rows(MockRows::new("ks", "posts")
+            .column_with_type("tags", set_type(ColType::Varchar))
+            .row(vec![CBytes::new(Bytes::from(vec!["a", "b", "a"]).into_plain())])));
+
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    let mut session = CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+    let rows = session.query(QueryBuilder::new("SELECT tags FROM ks.posts").finalize(), false, false)
+        .unwrap()
+        .get_body()
+        .into_rows()
+        .unwrap();
+
+    let tags: Set = rows[0].get_by_name("tags").unwrap().unwrap();
+    let tags: HashSet<String> = tags.as_rust().unwrap();
+    assert_eq!(tags, vec!["a".to_string(), "b".to_string()].into_iter().collect());
+    // sets are still available as lists
+    let tags: List = rows[0].get_by_name("tags").unwrap().unwrap();
+    let tags: Vec<String> = tags.as_rust().unwrap();
+    assert_eq!(tags, vec!["a", "b", "a"]);
+}
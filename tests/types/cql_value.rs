@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+
+use cdrs::IntoBytes;
+use cdrs::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue, CTuple, CUdt};
+use cdrs::testing::MockRows;
+use cdrs::types::{CBytes, CString, IntoRustByName};
+use cdrs::types::cql_value::CqlValue;
+use cdrs::types::duration::Duration;
+use cdrs::types::rows::Row;
+use cdrs::types::udt::UdtBuilder;
+use cdrs::types::value::{Bytes, Value};
+
+fn simple(id: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: id,
+        value: None
+    };
+}
+
+fn address() -> CUdt {
+    let lines = ColTypeOption {
+        id: ColType::List,
+        value: Some(ColTypeOptionValue::CList(Box::new(simple(ColType::Varchar))))
+    };
+    return CUdt {
+        ks: CString::new("ks".to_string()),
+        udt_name: CString::new("address".to_string()),
+        descriptions: vec![(CString::new("lines".to_string()), lines),
+                           (CString::new("zip".to_string()), simple(ColType::Int))]
+    };
+}
+
+fn bytes<T: Into<Bytes>>(value: T) -> CBytes {
+    return CBytes::new(value.into().into_plain());
+}
+
+fn text(value: &str) -> CqlValue {
+    return CqlValue::Text(value.to_string());
+}
+
+#[test]
+fn test_row_values() {
+    let set_type = ColTypeOption {
+        id: ColType::Set,
+        value: Some(ColTypeOptionValue::CSet(Box::new(simple(ColType::Varchar))))
+    };
+    let map_type = ColTypeOption {
+        id: ColType::Map,
+        value: Some(ColTypeOptionValue::CMap((Box::new(simple(ColType::Int)), Box::new(simple(ColType::Varchar)))))
+    };
+    let udt_type = ColTypeOption {
+        id: ColType::Udt,
+        value: Some(ColTypeOptionValue::UdtType(address()))
+    };
+    let tuple_type = ColTypeOption {
+        id: ColType::Tuple,
+        value: Some(ColTypeOptionValue::TupleType(CTuple {
+            types: vec![simple(ColType::Int), simple(ColType::Varchar)]
+        }))
+    };
+    let address = UdtBuilder::new(address()).field("lines", vec!["a", "b"]).field("zip", 7).finalize().unwrap();
+    // tuples are serialized in the same way as UDTs
+    let mut tuple = Value::from(1).into_cbytes();
+    tuple.extend_from_slice(Value::from("x").into_cbytes().as_slice());
+
+    let body = MockRows::new("ks", "t")
+        .column("id", ColType::Int)
+        .column("name", ColType::Varchar)
+        .column("nothing", ColType::Bigint)
+        .column("tiny", ColType::Tinyint)
+        .column("ip", ColType::Inet)
+        .column("length", ColType::Duration)
+        .column("amount", ColType::Decimal)
+        .column("big", ColType::Varint)
+        .column_with_type("tags", set_type)
+        .column_with_type("scores", map_type)
+        .column_with_type("home", udt_type)
+        .column_with_type("pair", tuple_type)
+        .row(vec![
+            bytes(1),
+            bytes("john"),
+            CBytes::new_null(),
+            bytes(-3i8),
+            bytes(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            bytes(Duration::new(1, 2, 3)),
+            CBytes::new(vec![0, 0, 0, 2, 0x30, 0x39]),
+            CBytes::new(vec![0x01, 0, 0, 0, 0, 0, 0, 0, 0]),
+            bytes(vec!["x"]),
+            bytes(vec![(2, "y")].into_iter().collect::<HashMap<i32, &str>>()),
+            CBytes::new(address.into_plain()),
+            CBytes::new(tuple)
+        ])
+        .into_body();
+    let row = Row::from_frame_body(body).remove(0);
+
+    let values = row.values().unwrap();
+    let names: Vec<&str> = values.iter().map(|&(ref name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["id", "name", "nothing", "tiny", "ip", "length", "amount", "big", "tags", "scores",
+                           "home", "pair"]);
+    let values: Vec<CqlValue> = values.into_iter().map(|(_, value)| value).collect();
+    assert_eq!(values, vec![
+        CqlValue::Int(1),
+        text("john"),
+        CqlValue::Null,
+        CqlValue::Tinyint(-3),
+        CqlValue::Inet(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+        CqlValue::Duration(Duration::new(1, 2, 3)),
+        CqlValue::Decimal { unscaled: vec![0x30, 0x39], scale: 2 },
+        CqlValue::Varint(vec![0x01, 0, 0, 0, 0, 0, 0, 0, 0]),
+        CqlValue::Set(vec![text("x")]),
+        CqlValue::Map(vec![(CqlValue::Int(2), text("y"))]),
+        CqlValue::Udt {
+            keyspace: "ks".to_string(),
+            name: "address".to_string(),
+            fields: vec![("lines".to_string(), CqlValue::List(vec![text("a"), text("b")])),
+                         ("zip".to_string(), CqlValue::Int(7))]
+        },
+        CqlValue::Tuple(vec![CqlValue::Int(1), text("x")])
+    ]);
+    assert!(values[2].is_null());
+
+    let name: CqlValue = row.get_by_name("name").unwrap().unwrap();
+    assert_eq!(name, text("john"));
+}
+
+#[test]
+fn test_udt_missing_trailing_fields() {
+    let udt_type = ColTypeOption {
+        id: ColType::Udt,
+        value: Some(ColTypeOptionValue::UdtType(address()))
+    };
+    // a value written before `zip` field was added to the type
+    let value = CBytes::new(Value::from(vec!["a"]).into_cbytes());
+    let value = CqlValue::decode(&value, &udt_type).unwrap();
+    assert_eq!(value, CqlValue::Udt {
+        keyspace: "ks".to_string(),
+        name: "address".to_string(),
+        fields: vec![("lines".to_string(), CqlValue::List(vec![text("a")])),
+                     ("zip".to_string(), CqlValue::Null)]
+    });
+}
+
+#[test]
+fn test_decode_errors() {
+    let list_without_item_type = simple(ColType::List);
+    assert!(CqlValue::decode(&bytes(vec![1]), &list_without_item_type).is_err());
+    assert!(CqlValue::decode(&CBytes::new(vec![0, 0]), &simple(ColType::Decimal)).is_err());
+    assert!(CqlValue::decode(&CBytes::new(vec![0xff, 0xfe]), &simple(ColType::Varchar)).is_err());
+}
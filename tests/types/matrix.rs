@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io::Cursor;
+
+use cdrs::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue, CUdt};
+use cdrs::testing::MockRows;
+use cdrs::types::{AsRust, CBytes, CString, IntoRustByName, to_vint, try_vint_from_cursor};
+use cdrs::types::data_serialization_types::{decode_list, decode_map, decode_set, decode_udt};
+use cdrs::types::duration::{DURATION_CLASS, Duration};
+use cdrs::types::list::List;
+use cdrs::types::map::Map;
+use cdrs::types::rows::Row;
+use cdrs::types::set::Set;
+use cdrs::types::udt::{UDT, UdtBuilder};
+use cdrs::types::value::Bytes;
+
+fn simple(id: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: id,
+        value: None
+    };
+}
+
+fn duration_custom() -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Custom,
+        value: Some(ColTypeOptionValue::CString(CString::new(DURATION_CLASS.to_string())))
+    };
+}
+
+/// Checks that a value of provided type is encoded and then decoded in the same way
+/// as a column, an item of a list and a set, a key and a value of a map and a field of an UDT.
+fn check<T>(col_type: ColTypeOption, value: T)
+    where T: Into<Bytes> + Clone + Debug + Eq + Hash,
+          Row: IntoRustByName<T>,
+          UDT: IntoRustByName<T>,
+          List: AsRust<Vec<T>>,
+          Set: AsRust<Vec<T>>,
+          Map: AsRust<HashMap<T, T>>
+{
+    let description = format!("{:?} {:?}", col_type.id, value);
+
+    let body = MockRows::new("ks", "t")
+        .column_with_type("v", col_type.clone())
+        .row(vec![CBytes::new(value.clone().into().into_plain())])
+        .into_body();
+    let row = Row::from_frame_body(body).remove(0);
+    let decoded: T = row.get_by_name("v").unwrap().expect(description.as_str());
+    assert_eq!(decoded, value, "row {}", description);
+
+    let list_type = ColTypeOption {
+        id: ColType::List,
+        value: Some(ColTypeOptionValue::CList(Box::new(col_type.clone())))
+    };
+    let list = List::new(decode_list(Bytes::from(vec![value.clone()]).into_plain()).unwrap(), list_type);
+    let decoded: Vec<T> = list.as_rust().expect(description.as_str());
+    assert_eq!(decoded, vec![value.clone()], "list {}", description);
+
+    let set_type = ColTypeOption {
+        id: ColType::Set,
+        value: Some(ColTypeOptionValue::CSet(Box::new(col_type.clone())))
+    };
+    let set = Set::new(decode_set(Bytes::from(vec![value.clone()]).into_plain()).unwrap(), set_type);
+    let decoded: Vec<T> = set.as_rust().expect(description.as_str());
+    assert_eq!(decoded, vec![value.clone()], "set {}", description);
+
+    let map_type = ColTypeOption {
+        id: ColType::Map,
+        value: Some(ColTypeOptionValue::CMap((Box::new(col_type.clone()), Box::new(col_type.clone()))))
+    };
+    let mut entries = HashMap::new();
+    entries.insert(value.clone(), value.clone());
+    let map = Map::new(decode_map(Bytes::from(entries.clone()).into_plain()).unwrap(), map_type);
+    let decoded: HashMap<T, T> = map.as_rust().expect(description.as_str());
+    assert_eq!(decoded, entries, "map {}", description);
+
+    let metadata = CUdt {
+        ks: CString::new("ks".to_string()),
+        udt_name: CString::new("t".to_string()),
+        descriptions: vec![(CString::new("v".to_string()), col_type.clone())]
+    };
+    let udt_bytes = UdtBuilder::new(metadata.clone()).field("v", value.clone()).finalize().unwrap();
+    let udt = UDT::new(decode_udt(udt_bytes.into_plain()).unwrap(), metadata);
+    let decoded: T = udt.get_by_name("v").unwrap().expect(description.as_str());
+    assert_eq!(decoded, value, "udt {}", description);
+}
+
+#[test]
+fn test_type_matrix() {
+    check(simple(ColType::Tinyint), 0i8);
+    check(simple(ColType::Tinyint), -5i8);
+    check(simple(ColType::Tinyint), i8::max_value());
+    check(simple(ColType::Tinyint), i8::min_value());
+
+    check(simple(ColType::Counter), 42i64);
+    check(simple(ColType::Counter), -1i64);
+    check(simple(ColType::Bigint), i64::max_value());
+
+    check(simple(ColType::Varchar), "zürich".to_string());
+    check(simple(ColType::Ascii), "abc".to_string());
+    check(simple(ColType::Varchar), String::new());
+
+    check(simple(ColType::Duration), Duration::new(0, 0, 0));
+    check(simple(ColType::Duration), Duration::new(14, 3, 1500000000));
+    check(simple(ColType::Duration), Duration::new(-1, -2, -3));
+    check(duration_custom(), Duration::new(1, 0, i64::max_value()));
+}
+
+#[test]
+fn test_type_mismatch() {
+    let body = MockRows::new("ks", "t")
+        .column("v", ColType::Int)
+        .row(vec![CBytes::new(Bytes::from(1i32).into_plain())])
+        .into_body();
+    let row = Row::from_frame_body(body).remove(0);
+    let tinyint: Option<::cdrs::error::Result<i8>> = row.get_by_name("v");
+    assert!(tinyint.unwrap().is_err());
+    let duration: Option<::cdrs::error::Result<Duration>> = row.get_by_name("v");
+    assert!(duration.unwrap().is_err());
+
+    // custom types other than duration aren't durations
+    let custom = ColTypeOption {
+        id: ColType::Custom,
+        value: Some(ColTypeOptionValue::CString(CString::new("org.example.Type".to_string())))
+    };
+    assert!(!Duration::is_duration_type(&custom));
+    assert!(Duration::is_duration_type(&duration_custom()));
+}
+
+#[test]
+fn test_vint() {
+    let cases: Vec<(i64, Vec<u8>)> = vec![
+        (0, vec![0]),
+        (-1, vec![1]),
+        (1, vec![2]),
+        (63, vec![126]),
+        (-64, vec![127]),
+        (64, vec![0x80, 0x80]),
+        (8191, vec![0xBF, 0xFE]),
+        (8192, vec![0xC0, 0x40, 0x00]),
+        (i64::max_value(), vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE]),
+        (i64::min_value(), vec![0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF])
+    ];
+    for (value, bytes) in cases {
+        assert_eq!(to_vint(value), bytes, "{}", value);
+        let mut cursor = Cursor::new(bytes);
+        assert_eq!(try_vint_from_cursor(&mut cursor).unwrap(), value);
+    }
+
+    // a vint which lacks its trailing bytes
+    assert!(try_vint_from_cursor(&mut Cursor::new(vec![0xC0, 0x40])).is_err());
+}
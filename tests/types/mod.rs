@@ -1,4 +1,5 @@
 use std::io::Cursor;
+use std::collections::HashMap;
 use cdrs::*;
 use cdrs::types::{
     CString,
@@ -8,8 +9,13 @@ use cdrs::types::{
     CBytesShort,
     CInt,
     CIntShort,
+    CBytesMap,
     cursor_next_value};
 
+mod collections;
+mod cql_value;
+mod matrix;
+mod udt;
 mod value;
 
 // CString
@@ -129,6 +135,15 @@ fn test_cbytes_into_cbytes() {
     assert_eq!(cbytes.into_cbytes(), vec![0, 0, 0, 3, 1, 2, 3]);
 }
 
+#[test]
+fn test_cbytes_from_cursor_null() {
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![255, 255, 255, 255, 1]);
+    let cbytes = CBytes::from_cursor(&mut cursor);
+    assert!(cbytes.is_null());
+    assert_eq!(cbytes.into_cbytes(), vec![255, 255, 255, 255]);
+    assert_eq!(cursor.position(), 4);
+}
+
 // CBytesShort
 #[test]
 fn test_cbytesshort_new() {
@@ -180,3 +195,19 @@ fn test_cursor_next_value() {
     let val = cursor_next_value(&mut cursor, l);
     assert_eq!(val, vec![0, 1, 2]);
 }
+
+// CBytesMap
+#[test]
+fn test_cbytes_map_into_cbytes() {
+    let mut map: CBytesMap = HashMap::new();
+    map.insert("k".to_string(), vec![1, 2]);
+    assert_eq!(map.into_cbytes(), vec![0, 1, 0, 1, 107, 0, 0, 0, 2, 1, 2]);
+}
+
+#[test]
+fn test_cbytes_map_from_cursor() {
+    let mut cursor: Cursor<Vec<u8>> = Cursor::new(vec![0, 1, 0, 1, 107, 0, 0, 0, 2, 1, 2]);
+    let map = CBytesMap::from_cursor(&mut cursor);
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get("k"), Some(&vec![1, 2]));
+}
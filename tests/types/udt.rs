@@ -0,0 +1,185 @@
+use cdrs::authenticators::PasswordAuthenticator;
+use cdrs::client::{CDRS, QueryBuilder};
+use cdrs::compression::Compression;
+use cdrs::frame::Opcode;
+use cdrs::frame::frame_request::RequestBody;
+use cdrs::frame::frame_response::ResponseBody;
+use cdrs::frame::frame_result::{ColType, ColTypeOption, ColTypeOptionValue, CUdt, ResResultBody};
+use cdrs::testing::{MockResponse, MockServer, RequestMatcher};
+use cdrs::transport::TransportTcp;
+use cdrs::types::{AsRust, CBytes, CString, IntoRustByName};
+use cdrs::types::data_serialization_types::decode_udt;
+use cdrs::types::list::List;
+use cdrs::types::udt::{UDT, UdtBuilder};
+use cdrs::types::value::{Bytes, Value};
+
+fn simple(id: ColType) -> ColTypeOption {
+    return ColTypeOption {
+        id: id,
+        value: None
+    };
+}
+
+fn udt(name: &str, fields: Vec<(&str, ColTypeOption)>) -> CUdt {
+    return CUdt {
+        ks: CString::new("ks".to_string()),
+        udt_name: CString::new(name.to_string()),
+        descriptions: fields.into_iter()
+            .map(|(name, col_type)| (CString::new(name.to_string()), col_type))
+            .collect()
+    };
+}
+
+fn udt_type(metadata: CUdt) -> ColTypeOption {
+    return ColTypeOption {
+        id: ColType::Udt,
+        value: Some(ColTypeOptionValue::UdtType(metadata))
+    };
+}
+
+fn address() -> CUdt {
+    return udt("address", vec![("street", simple(ColType::Varchar)), ("zip", simple(ColType::Int))]);
+}
+
+fn person() -> CUdt {
+    let addresses = ColTypeOption {
+        id: ColType::List,
+        value: Some(ColTypeOptionValue::CList(Box::new(udt_type(address()))))
+    };
+    return udt("person", vec![("name", simple(ColType::Varchar)),
+                              ("home", udt_type(address())),
+                              ("previous", addresses)]);
+}
+
+#[test]
+fn test_udt_builder_field_order() {
+    let bytes = UdtBuilder::new(address())
+        .field("zip", 1)
+        .field("street", "a")
+        .finalize()
+        .unwrap();
+    assert_eq!(bytes, Bytes::new(vec![0, 0, 0, 1, 97, 0, 0, 0, 4, 0, 0, 0, 1]));
+
+    // missing and not-set fields are nulls
+    let bytes = UdtBuilder::new(address()).field("street", "a").finalize().unwrap();
+    assert_eq!(bytes, Bytes::new(vec![0, 0, 0, 1, 97, 255, 255, 255, 255]));
+    let bytes = UdtBuilder::new(address()).field("zip", Value::new_not_set()).finalize().unwrap();
+    assert_eq!(bytes, Bytes::new(vec![255, 255, 255, 255, 255, 255, 255, 255]));
+    let bytes = UdtBuilder::new(address()).field("zip", None as Option<i32>).finalize().unwrap();
+    assert_eq!(bytes, Bytes::new(vec![255, 255, 255, 255, 255, 255, 255, 255]));
+
+    assert!(UdtBuilder::new(address()).field("city", "b").finalize().is_err());
+    assert!(UdtBuilder::from_col_type(&simple(ColType::Int)).is_err());
+}
+
+#[test]
+fn test_udt_builder_nested() {
+    let builder = UdtBuilder::new(person());
+    let home = UdtBuilder::from_col_type(builder.field_type("home").unwrap()).unwrap()
+        .field("street", "a")
+        .field("zip", 1)
+        .finalize()
+        .unwrap();
+    let address_type = match builder.field_type("previous").and_then(|col_type| col_type.value.clone()) {
+        Some(ColTypeOptionValue::CList(item_type)) => item_type,
+        other => panic!("Unexpected type {:?}", other)
+    };
+    let previous = vec![
+        UdtBuilder::from_col_type(&address_type).unwrap().field("street", "b").finalize().unwrap(),
+        UdtBuilder::from_col_type(&address_type).unwrap().field("zip", 3).finalize().unwrap()
+    ];
+    let bytes = builder.field("name", "john")
+        .field("home", home)
+        .field("previous", previous)
+        .finalize()
+        .unwrap();
+
+    let decoded = UDT::new(decode_udt(bytes.into_plain()).unwrap(), person());
+    let name: String = decoded.get_by_name("name").unwrap().unwrap();
+    assert_eq!(name, "john");
+    let home: UDT = decoded.get_by_name("home").unwrap().unwrap();
+    let street: String = home.get_by_name("street").unwrap().unwrap();
+    assert_eq!(street, "a");
+    let previous: List = decoded.get_by_name("previous").unwrap().unwrap();
+    let previous: Vec<UDT> = previous.as_rust().unwrap();
+    assert_eq!(previous.len(), 2);
+    let street: String = previous[0].get_by_name("street").unwrap().unwrap();
+    assert_eq!(street, "b");
+    assert_eq!(previous[0].is_null("zip"), Some(true));
+    let zip: i32 = previous[1].get_by_name("zip").unwrap().unwrap();
+    assert_eq!(zip, 3);
+}
+
+#[test]
+fn test_udt_missing_trailing_fields() {
+    // a value written before `zip` field was added to the type
+    let decoded = UDT::new(vec![CBytes::new(b"a".to_vec())], address());
+    assert_eq!(decoded.is_null("street"), Some(false));
+    assert_eq!(decoded.is_null("zip"), Some(true));
+    assert_eq!(decoded.is_null("city"), None);
+}
+
+#[test]
+fn test_udt_from_server_rows() {
+    fn string(value: &str) -> Vec<u8> {
+        let mut bytes = vec![0, value.len() as u8];
+        bytes.extend_from_slice(value.as_bytes());
+        return bytes;
+    }
+
+    // `SELECT home FROM ks.people` result as it's sent by a server
+    let mut body = vec![0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 1];
+    body.extend(string("ks"));
+    body.extend(string("people"));
+    body.extend(string("home"));
+    body.extend(vec![0, 0x30]);
+    body.extend(string("ks"));
+    body.extend(string("address"));
+    body.extend(vec![0, 2]);
+    body.extend(string("street"));
+    body.extend(vec![0, 0x0d]);
+    body.extend(string("zip"));
+    body.extend(vec![0, 0x09]);
+    // a row with `{street: 'Main', zip: 12345}` value, which isn't prefixed with a number of fields
+    let value = vec![0, 0, 0, 4, 77, 97, 105, 110, 0, 0, 0, 4, 0, 0, 0x30, 0x39];
+    body.extend(vec![0, 0, 0, 1, 0, 0, 0, value.len() as u8]);
+    body.extend(value.clone());
+
+    assert_eq!(decode_udt(value).unwrap().len(), 2);
+
+    let rows = ResponseBody::from(body, &Opcode::Result).into_rows().unwrap();
+    let home: UDT = rows[0].get_by_name("home").unwrap().unwrap();
+    let street: String = home.get_by_name("street").unwrap().unwrap();
+    assert_eq!(street, "Main");
+    let zip: i32 = home.get_by_name("zip").unwrap().unwrap();
+    assert_eq!(zip, 12345);
+}
+
+#[test]
+fn test_udt_builder_from_prepared_metadata() {
+    const INSERT: &'static str = "INSERT INTO ks.people (id, home) VALUES (1, ?)";
+    let server = MockServer::start().unwrap();
+    server.when(RequestMatcher::opcode(Opcode::Prepare))
+        .then(MockResponse::prepared(vec![1], vec![("home", udt_type(address()))]));
+
+    let transport = TransportTcp::new(server.addr().to_string().as_str()).unwrap();
+    let authenticator = PasswordAuthenticator::new("user", "pass");
+    let mut session = CDRS::new(transport, authenticator).start(Compression::None).unwrap();
+    let prepared = match session.prepare(INSERT.to_string(), false, false).unwrap().get_body() {
+        ResponseBody::Result(ResResultBody::Prepared(prepared)) => prepared,
+        other => panic!("Unexpected response {:?}", other)
+    };
+    let home = UdtBuilder::from_col_type(&prepared.metadata.col_specs[0].col_type).unwrap()
+        .field("street", "a")
+        .finalize()
+        .unwrap();
+    session.query(QueryBuilder::new(INSERT).values(vec![Value::from(home)]).finalize(), false, false).unwrap();
+
+    let values: Vec<Vec<u8>> = server.requests().iter()
+        .filter_map(|request| match request.body {
+            RequestBody::Query(ref query) => query.query_params.values.first().map(|value| value.body.clone()),
+            _ => None
+        })
+        .collect();
+    assert_eq!(values, vec![vec![0, 0, 0, 1, 97, 255, 255, 255, 255]]);
+}